@@ -0,0 +1,494 @@
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::{Attribute, Expr, Field, GenericArgument, LitInt, PathArguments, Type};
+
+/// Options given in #[bits(...)] on the struct
+#[derive(Default)]
+pub struct StructAttrs {
+    /// payload length in bytes, fields must fit into it
+    pub len: Option<usize>,
+}
+
+impl StructAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<StructAttrs> {
+        let mut result = StructAttrs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("bits")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("len") {
+                    result.len = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown bits attribute, expected `len`"))
+                }
+            })?;
+        }
+        Ok(result)
+    }
+}
+
+/// The base type of a field after removing Option
+pub enum Kind {
+    Bool,
+    Int {
+        signed: bool,
+        bits: usize,
+    },
+    Float,
+    Lookup,
+    /// `Vec<T>` of rows packed from the field start, the row type implements
+    /// BitDecode and BitEncode
+    Repeat {
+        count: Option<syn::Ident>,
+    },
+    /// the rest of the payload decoded through BitVariant, selected by an integer field
+    Variant {
+        select: syn::Ident,
+    },
+}
+
+/// Bit order of a field, mirrors bitfield::Endianness
+#[derive(Clone, Copy, PartialEq)]
+pub enum Order {
+    Big,
+    Little,
+    Intel,
+}
+
+/// A field annotated with #[bits(...)]
+pub struct FieldDef {
+    pub ident: syn::Ident,
+    pub ty: Type,
+    pub inner: Type,
+    pub optional: bool,
+    pub kind: Kind,
+    pub start: usize,
+    pub end: usize,
+    pub order: Order,
+    pub signed: bool,
+    pub resolution: Option<Expr>,
+    pub offset: Option<Expr>,
+    pub span: Span,
+}
+
+impl FieldDef {
+    pub fn parse(field: &Field) -> syn::Result<FieldDef> {
+        let ident = match &field.ident {
+            Some(ident) => ident.clone(),
+            None => {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "bitfield derives require named fields",
+                ))
+            }
+        };
+        let (inner, optional) = match option_inner(&field.ty) {
+            Some(inner) => (inner.clone(), true),
+            None => (field.ty.clone(), false),
+        };
+        let mut repeat = false;
+        let mut count = None;
+        let mut select = None;
+
+        let mut start = None;
+        let mut end = None;
+        let mut size = None;
+        let mut order = Order::Little;
+        let mut signed = None;
+        let mut lookup = false;
+        let mut resolution = None;
+        let mut offset = None;
+        let mut found = false;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("bits"))
+        {
+            found = true;
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("start") {
+                    start = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                } else if meta.path.is_ident("end") {
+                    end = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                } else if meta.path.is_ident("size") {
+                    size = Some(meta.value()?.parse::<LitInt>()?.base10_parse::<usize>()?);
+                } else if meta.path.is_ident("be") {
+                    order = Order::Big;
+                } else if meta.path.is_ident("le") {
+                    order = Order::Little;
+                } else if meta.path.is_ident("intel") {
+                    order = Order::Intel;
+                } else if meta.path.is_ident("signed") {
+                    signed = Some(true);
+                } else if meta.path.is_ident("unsigned") {
+                    signed = Some(false);
+                } else if meta.path.is_ident("lookup") {
+                    lookup = true;
+                } else if meta.path.is_ident("resolution") {
+                    resolution = Some(meta.value()?.parse::<Expr>()?);
+                } else if meta.path.is_ident("offset") {
+                    offset = Some(meta.value()?.parse::<Expr>()?);
+                } else if meta.path.is_ident("repeat") {
+                    repeat = true;
+                } else if meta.path.is_ident("count") {
+                    count = Some(meta.value()?.parse::<syn::Ident>()?);
+                } else if meta.path.is_ident("select") {
+                    select = Some(meta.value()?.parse::<syn::Ident>()?);
+                } else {
+                    return Err(meta.error(
+                        "unknown bits attribute, expected one of `start`, `end`, `size`, \
+                         `be`, `le`, `intel`, `signed`, `unsigned`, `lookup`, `resolution`, \
+                         `offset`, `repeat`, `count`, `select`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        if !found {
+            return Err(syn::Error::new(
+                field.span(),
+                "missing #[bits(start = .., end = ..)] attribute",
+            ));
+        }
+
+        let start: usize = match start {
+            Some(start) => start,
+            None => return Err(syn::Error::new(field.span(), "missing `start`")),
+        };
+        let other = end.is_some()
+            || size.is_some()
+            || order != Order::Little
+            || signed.is_some()
+            || lookup
+            || resolution.is_some()
+            || offset.is_some();
+        let kind = match (repeat, count, select) {
+            (true, count, None) => Some(Kind::Repeat { count }),
+            (false, Some(_), _) => {
+                return Err(syn::Error::new(field.span(), "`count` requires `repeat`"))
+            }
+            (false, None, Some(select)) => Some(Kind::Variant { select }),
+            (true, _, Some(_)) => {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "`repeat` and `select` are mutually exclusive",
+                ))
+            }
+            (false, None, None) => None,
+        };
+        if let Some(kind) = kind {
+            return FieldDef::parse_tail(field, ident, start, kind, other);
+        }
+        let end = match (end, size) {
+            (Some(_), Some(_)) => {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "`end` and `size` are mutually exclusive",
+                ))
+            }
+            (Some(end), None) => end,
+            (None, Some(size)) if size > 0 => start + size - 1,
+            _ => return Err(syn::Error::new(field.span(), "missing `end` or `size`")),
+        };
+        if end < start {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("start {} is greater than end {}", start, end),
+            ));
+        }
+        if end - start > 63 {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("field {}..={} is wider than 64 bits", start, end),
+            ));
+        }
+
+        let kind = if lookup {
+            Kind::Lookup
+        } else {
+            match type_name(&inner).as_deref() {
+                Some("bool") => Kind::Bool,
+                Some("f32") | Some("f64") => Kind::Float,
+                Some(name) => match int_type(name) {
+                    Some((signed, bits)) => Kind::Int { signed, bits },
+                    None => return Err(syn::Error::new(
+                        inner.span(),
+                        "unsupported field type, use an integer, float, bool or a `lookup` type",
+                    )),
+                },
+                None => {
+                    return Err(syn::Error::new(
+                        inner.span(),
+                        "unsupported field type, use an integer, float, bool or a `lookup` type",
+                    ))
+                }
+            }
+        };
+
+        let size = end - start + 1;
+        let signed = match &kind {
+            Kind::Int {
+                signed: type_signed,
+                bits,
+            } => {
+                if size > *bits {
+                    return Err(syn::Error::new(
+                        field.span(),
+                        format!("field of {} bits does not fit into its type", size),
+                    ));
+                }
+                signed.unwrap_or(*type_signed)
+            }
+            Kind::Lookup | Kind::Bool => {
+                if signed == Some(true) {
+                    return Err(syn::Error::new(
+                        field.span(),
+                        "`signed` is not supported for bool and lookup fields",
+                    ));
+                }
+                false
+            }
+            Kind::Float => signed.unwrap_or(false),
+            Kind::Repeat { .. } | Kind::Variant { .. } => unreachable!(),
+        };
+        if !matches!(kind, Kind::Float) && (resolution.is_some() || offset.is_some()) {
+            return Err(syn::Error::new(
+                field.span(),
+                "`resolution` and `offset` require a f32 or f64 field",
+            ));
+        }
+
+        Ok(FieldDef {
+            ident,
+            ty: field.ty.clone(),
+            inner,
+            optional,
+            kind,
+            start,
+            end,
+            order,
+            signed,
+            resolution,
+            offset,
+            span: field.span(),
+        })
+    }
+}
+
+impl FieldDef {
+    // a `Vec<T>` field with `repeat` or a BitVariant field with `select`, only `start`,
+    // `count` and `select` apply
+    fn parse_tail(
+        field: &Field,
+        ident: syn::Ident,
+        start: usize,
+        kind: Kind,
+        other: bool,
+    ) -> syn::Result<FieldDef> {
+        let attr = match kind {
+            Kind::Repeat { .. } => "repeat",
+            _ => "select",
+        };
+        if other {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("`{}` fields only take `start` and `{}`", attr, {
+                    if attr == "repeat" {
+                        "count"
+                    } else {
+                        "select"
+                    }
+                }),
+            ));
+        }
+        if start % 8 != 0 {
+            return Err(syn::Error::new(
+                field.span(),
+                format!("`{}` fields must start at a byte boundary", attr),
+            ));
+        }
+        let inner = match (&kind, vec_inner(&field.ty)) {
+            (Kind::Repeat { .. }, Some(inner)) => inner.clone(),
+            (Kind::Repeat { .. }, None) => {
+                return Err(syn::Error::new(
+                    field.ty.span(),
+                    "`repeat` fields must be of type `Vec<T>`",
+                ))
+            }
+            _ => field.ty.clone(),
+        };
+        Ok(FieldDef {
+            ident,
+            ty: field.ty.clone(),
+            inner,
+            optional: false,
+            kind,
+            start,
+            end: start,
+            order: Order::Little,
+            signed: false,
+            resolution: None,
+            offset: None,
+            span: field.span(),
+        })
+    }
+
+    pub fn is_repeat(&self) -> bool {
+        matches!(self.kind, Kind::Repeat { .. })
+    }
+
+    /// Repeat and variant fields take the rest of the payload
+    pub fn is_tail(&self) -> bool {
+        matches!(self.kind, Kind::Repeat { .. } | Kind::Variant { .. })
+    }
+}
+
+/// Check fields against each other and against the payload length. A repeat or variant
+/// field has to follow all other fields, its count or select field has to be an integer
+/// declared before it
+pub fn check_fields(fields: &[FieldDef], attrs: &StructAttrs) -> syn::Result<()> {
+    let mut tails = fields.iter().filter(|field| field.is_tail());
+    if let Some(tail) = tails.next() {
+        if let Some(other) = tails.next() {
+            return Err(syn::Error::new(
+                other.span,
+                "only one `repeat` or `select` field is supported",
+            ));
+        }
+        if attrs.len.is_some() {
+            return Err(syn::Error::new(
+                tail.span,
+                "`repeat` and `select` fields can not be combined with `len`",
+            ));
+        }
+        if let Some(field) = fields
+            .iter()
+            .find(|field| !field.is_tail() && field.end >= tail.start)
+        {
+            return Err(syn::Error::new(
+                field.span,
+                format!(
+                    "field `{}` ({}..={}) is not in front of field `{}`",
+                    field.ident, field.start, field.end, tail.ident
+                ),
+            ));
+        }
+        let (source, role) = match &tail.kind {
+            Kind::Repeat { count: Some(count) } => (count, "count"),
+            Kind::Variant { select } => (select, "select"),
+            _ => return check_overlaps(fields, attrs),
+        };
+        let position = |ident: &syn::Ident| fields.iter().position(|field| field.ident == *ident);
+        if let (Some(index), Some(tail_index)) = (position(source), position(&tail.ident)) {
+            if index > tail_index {
+                return Err(syn::Error::new(
+                    fields[index].span,
+                    format!(
+                        "{} field `{}` has to be declared before field `{}`",
+                        role, source, tail.ident
+                    ),
+                ));
+            }
+        }
+        match fields.iter().find(|field| field.ident == *source) {
+            Some(FieldDef {
+                kind: Kind::Int { .. },
+                optional,
+                ..
+            }) if role == "count" || !optional => (),
+            Some(_) => {
+                return Err(syn::Error::new(
+                    source.span(),
+                    format!("{} field `{}` is not an integer", role, source),
+                ))
+            }
+            None => {
+                return Err(syn::Error::new(
+                    source.span(),
+                    format!("{} field `{}` does not exist", role, source),
+                ))
+            }
+        }
+    }
+    check_overlaps(fields, attrs)
+}
+
+fn check_overlaps(fields: &[FieldDef], attrs: &StructAttrs) -> syn::Result<()> {
+    let mut sorted: Vec<&FieldDef> = fields.iter().collect();
+    sorted.sort_by_key(|field| field.start);
+    for pair in sorted.windows(2) {
+        if pair[1].start <= pair[0].end {
+            return Err(syn::Error::new(
+                pair[1].span,
+                format!(
+                    "field `{}` ({}..={}) overlaps field `{}` ({}..={})",
+                    pair[1].ident,
+                    pair[1].start,
+                    pair[1].end,
+                    pair[0].ident,
+                    pair[0].start,
+                    pair[0].end
+                ),
+            ));
+        }
+    }
+    if let Some(len) = attrs.len {
+        for field in fields {
+            if field.end >= len * 8 {
+                return Err(syn::Error::new(
+                    field.span,
+                    format!(
+                        "field `{}` ({}..={}) exceeds the payload length of {} bytes",
+                        field.ident, field.start, field.end, len
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn option_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, "Option")
+}
+
+fn vec_inner(ty: &Type) -> Option<&Type> {
+    generic_inner(ty, "Vec")
+}
+
+// the type argument of wrapper<T>
+fn generic_inner<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    if let Type::Path(path) = ty {
+        let segment = path.path.segments.last()?;
+        if segment.ident == wrapper {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                    return Some(inner);
+                }
+            }
+        }
+    }
+    None
+}
+
+fn type_name(ty: &Type) -> Option<String> {
+    if let Type::Path(path) = ty {
+        if path.qself.is_none() && path.path.segments.len() == 1 {
+            return Some(path.path.segments[0].ident.to_string());
+        }
+    }
+    None
+}
+
+fn int_type(name: &str) -> Option<(bool, usize)> {
+    match name {
+        "u8" => Some((false, 8)),
+        "u16" => Some((false, 16)),
+        "u32" => Some((false, 32)),
+        "u64" => Some((false, 64)),
+        "i8" => Some((true, 8)),
+        "i16" => Some((true, 16)),
+        "i32" => Some((true, 32)),
+        "i64" => Some((true, 64)),
+        _ => None,
+    }
+}
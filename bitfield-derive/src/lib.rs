@@ -0,0 +1,821 @@
+//! Derive macros for the bitfield crate, use them through the `derive` feature of `bitfield`.
+
+extern crate proc_macro;
+
+mod attrs;
+
+use crate::attrs::{check_fields, FieldDef, Kind, Order, StructAttrs};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, Lit};
+
+/// Derive `bitfield::BitDecode` for a struct with `#[bits(...)]` annotated fields
+#[proc_macro_derive(BitDecode, attributes(bits))]
+pub fn derive_bit_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_decode(&input)
+        .unwrap_or_else(|why| why.to_compile_error())
+        .into()
+}
+
+/// Derive `bitfield::BitEncode` for a struct with `#[bits(...)]` annotated fields
+#[proc_macro_derive(BitEncode, attributes(bits))]
+pub fn derive_bit_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_encode(&input)
+        .unwrap_or_else(|why| why.to_compile_error())
+        .into()
+}
+
+/// Derive `bitfield::BitLookup` for an enum of unit variants with an optional
+/// `#[bits(unknown)] Variant(u64)` catching all other values. Variant values are taken from
+/// `#[bits(value = N)]`, the discriminant or counted up from the previous variant
+#[proc_macro_derive(BitLookup, attributes(bits))]
+pub fn derive_bit_lookup(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_lookup(&input)
+        .unwrap_or_else(|why| why.to_compile_error())
+        .into()
+}
+
+/// Derive `bitfield::BitVariant` for an enum selected by a discriminator field. Variants are
+/// unit variants or hold a single `BitDecode + BitEncode` type, each selected by
+/// `#[bits(value = N)]` or its discriminant. An optional `#[bits(unknown)] Variant(u64, Vec<u8>)`
+/// keeps the discriminator and raw bytes of all other values
+#[proc_macro_derive(BitVariant, attributes(bits))]
+pub fn derive_bit_variant(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_variant(&input)
+        .unwrap_or_else(|why| why.to_compile_error())
+        .into()
+}
+
+/// Derive lazy view types `NameView<'a>` over a `BitField` with one accessor per field
+/// and `NameViewMut<'a>` over a `MutableBitField` with accessors and setters
+#[proc_macro_derive(BitView, attributes(bits))]
+pub fn derive_bit_view(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_view(&input)
+        .unwrap_or_else(|why| why.to_compile_error())
+        .into()
+}
+
+fn parse_struct(input: &DeriveInput) -> syn::Result<(StructAttrs, Vec<FieldDef>)> {
+    let attrs = StructAttrs::parse(&input.attrs)?;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields
+                .named
+                .iter()
+                .map(FieldDef::parse)
+                .collect::<syn::Result<Vec<FieldDef>>>()?,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "bitfield derives require a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "bitfield derives require a struct with named fields",
+            ))
+        }
+    };
+    check_fields(&fields, &attrs)?;
+    Ok((attrs, fields))
+}
+
+fn field_spec(field: &FieldDef) -> TokenStream2 {
+    let start = field.start;
+    let end = field.end;
+    let endianness = match field.order {
+        Order::Big => quote!(::bitfield::Endianness::Big),
+        Order::Little => quote!(::bitfield::Endianness::Little),
+        Order::Intel => quote!(::bitfield::Endianness::Intel),
+    };
+    let signed = field.signed;
+    let resolution = match &field.resolution {
+        Some(resolution) => quote!((#resolution) as f64),
+        None => quote!(1.0),
+    };
+    let offset = match &field.offset {
+        Some(offset) => quote!((#offset) as f64),
+        None => quote!(0.0),
+    };
+    let na = field.optional;
+    quote! {
+        ::bitfield::FieldSpec {
+            start: #start,
+            end: #end,
+            endianness: #endianness,
+            signed: #signed,
+            resolution: #resolution,
+            offset: #offset,
+            na: #na,
+        }
+    }
+}
+
+// expression decoding field, access yields the value of an already decoded field
+fn decode_field(
+    field: &FieldDef,
+    fields: &[FieldDef],
+    access: &dyn Fn(&FieldDef) -> TokenStream2,
+) -> TokenStream2 {
+    if let Kind::Repeat { count } = &field.kind {
+        let count = count
+            .as_ref()
+            .and_then(|count| fields.iter().find(|other| other.ident == *count))
+            .map(|count| {
+                let value = access(count);
+                if count.optional {
+                    quote!(#value.unwrap_or(0) as usize)
+                } else {
+                    quote!(#value as usize)
+                }
+            });
+        return decode_repeat(field, count);
+    }
+    if let Kind::Variant { select } = &field.kind {
+        let inner = &field.inner;
+        let first = field.start / 8;
+        let select = match fields.iter().find(|other| other.ident == *select) {
+            Some(select) => access(select),
+            None => unreachable!(),
+        };
+        return quote! {
+            {
+                let data = bitfield.as_bytes().get(#first..).unwrap_or(&[]);
+                <#inner as ::bitfield::BitVariant>::decode_variant(
+                    #select as u64,
+                    &::bitfield::BitField::new(data),
+                )?
+            }
+        };
+    }
+    let inner = &field.inner;
+    let spec = field_spec(field);
+    let value = match (&field.kind, field.optional) {
+        (Kind::Bool, false) => quote!(spec.get_raw(bitfield)? != 0),
+        (Kind::Bool, true) => quote!(spec.get_i64(bitfield)?.map(|value| value != 0)),
+        (Kind::Int { .. }, false) => quote!(spec.get_i64(bitfield)?.unwrap_or(0) as #inner),
+        (Kind::Int { .. }, true) => quote!(spec.get_i64(bitfield)?.map(|value| value as #inner)),
+        (Kind::Float, false) => quote!(spec.get_f64(bitfield)?.unwrap_or(f64::NAN) as #inner),
+        (Kind::Float, true) => quote!(spec.get_f64(bitfield)?.map(|value| value as #inner)),
+        (Kind::Lookup, false) => {
+            quote!(<#inner as ::bitfield::BitLookup>::from_raw(spec.get_raw(bitfield)?)?)
+        }
+        (Kind::Lookup, true) => quote! {
+            match spec.get_i64(bitfield)? {
+                Some(value) => Some(<#inner as ::bitfield::BitLookup>::from_raw(value as u64)?),
+                None => None,
+            }
+        },
+        (Kind::Repeat { .. }, _) | (Kind::Variant { .. }, _) => unreachable!(),
+    };
+    quote! {
+        {
+            let spec = #spec;
+            #value
+        }
+    }
+}
+
+// expression decoding the rows of a repeat field, count rows or as many as the payload holds
+fn decode_repeat(field: &FieldDef, count: Option<TokenStream2>) -> TokenStream2 {
+    let inner = &field.inner;
+    let ident = &field.ident;
+    let first = field.start / 8;
+    let count = count.unwrap_or_else(|| {
+        quote! {
+            if row == 0 {
+                0
+            } else {
+                bytes.len().saturating_sub(#first) / row
+            }
+        }
+    });
+    quote! {
+        {
+            let bytes = bitfield.as_bytes();
+            let row = <#inner as ::bitfield::BitEncode>::BIT_LEN.div_ceil(8);
+            let mut rows = Vec::new();
+            for index in 0..#count {
+                let offset = #first + index * row;
+                match bytes.get(offset..offset + row) {
+                    Some(data) => rows.push(<#inner as ::bitfield::BitDecode>::from_bytes(data)?),
+                    None => {
+                        return Err(::bitfield::Error::with_context(
+                            ::bitfield::ErrorKind::OutOfRange,
+                            &format!(
+                                "{}: row {} is past the end of the payload",
+                                stringify!(#ident),
+                                index
+                            ),
+                        ))
+                    }
+                }
+            }
+            rows
+        }
+    }
+}
+
+fn encode_field(field: &FieldDef, fields: &[FieldDef]) -> TokenStream2 {
+    let ident = &field.ident;
+    match &field.kind {
+        Kind::Repeat { .. } => return encode_repeat(field),
+        Kind::Variant { .. } => return encode_variant(field),
+        _ => (),
+    }
+    // count fields are written from the number of rows, select fields from the variant
+    let inner = &field.inner;
+    let value = fields.iter().find_map(|other| {
+        let tail = &other.ident;
+        match &other.kind {
+            Kind::Repeat { count: Some(count) } if *count == field.ident => {
+                let ident = &field.ident;
+                Some(quote! {
+                    (match <#inner as ::std::convert::TryFrom<usize>>::try_from(self.#tail.len()) {
+                        Ok(count) => count,
+                        Err(_) => {
+                            return Err(::bitfield::Error::with_context(
+                                ::bitfield::ErrorKind::OutOfRange,
+                                &format!(
+                                    "{}: {} rows exceed the count field {}",
+                                    stringify!(#tail),
+                                    self.#tail.len(),
+                                    stringify!(#ident)
+                                ),
+                            ))
+                        }
+                    })
+                })
+            }
+            Kind::Variant { select } if *select == field.ident => {
+                Some(quote!((::bitfield::BitVariant::discriminator(&self.#tail) as #inner)))
+            }
+            _ => None,
+        }
+    });
+    match value {
+        Some(value) if field.optional => encode_value(field, quote!(Some(#value))),
+        Some(value) => encode_value(field, value),
+        None => encode_value(field, quote!(self.#ident)),
+    }
+}
+
+// statement copying the bytes of a variant field into the payload
+fn encode_variant(field: &FieldDef) -> TokenStream2 {
+    let ident = &field.ident;
+    let first = field.start / 8;
+    quote! {
+        {
+            let bytes = ::bitfield::BitVariant::variant_bytes(&self.#ident)?;
+            match bitfield.as_bytes_mut().get_mut(#first..#first + bytes.len()) {
+                Some(data) => data.copy_from_slice(&bytes),
+                None => {
+                    return Err(::bitfield::Error::with_context(
+                        ::bitfield::ErrorKind::OutOfRange,
+                        &format!(
+                            "{}: variant is past the end of the payload",
+                            stringify!(#ident)
+                        ),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+// statement writing the rows of a repeat field behind each other
+fn encode_repeat(field: &FieldDef) -> TokenStream2 {
+    let inner = &field.inner;
+    let ident = &field.ident;
+    let first = field.start / 8;
+    quote! {
+        {
+            let row = <#inner as ::bitfield::BitEncode>::BIT_LEN.div_ceil(8);
+            for (index, value) in self.#ident.iter().enumerate() {
+                let offset = #first + index * row;
+                match bitfield.as_bytes_mut().get_mut(offset..offset + row) {
+                    Some(data) => ::bitfield::BitEncode::encode(
+                        value,
+                        &mut ::bitfield::MutableBitField::new(data),
+                    )?,
+                    None => {
+                        return Err(::bitfield::Error::with_context(
+                            ::bitfield::ErrorKind::OutOfRange,
+                            &format!(
+                                "{}: row {} is past the end of the payload",
+                                stringify!(#ident),
+                                index
+                            ),
+                        ))
+                    }
+                }
+            }
+        }
+    }
+}
+
+// statement writing value (an expression of the field type) to bitfield
+fn encode_value(field: &FieldDef, value: TokenStream2) -> TokenStream2 {
+    let spec = field_spec(field);
+    let set = match (&field.kind, field.optional) {
+        (Kind::Bool, false) | (Kind::Int { .. }, false) => {
+            quote!(spec.set_i64(bitfield, Some(#value as i64)))
+        }
+        (Kind::Bool, true) | (Kind::Int { .. }, true) => {
+            quote!(spec.set_i64(bitfield, #value.map(|value| value as i64)))
+        }
+        (Kind::Float, false) => quote!(spec.set_f64(bitfield, Some(#value as f64))),
+        (Kind::Float, true) => {
+            quote!(spec.set_f64(bitfield, #value.map(|value| value as f64)))
+        }
+        (Kind::Lookup, false) => quote! {
+            spec.set_i64(bitfield, Some(::bitfield::BitLookup::to_raw(&#value) as i64))
+        },
+        (Kind::Lookup, true) => quote! {
+            spec.set_i64(
+                bitfield,
+                #value
+                    .as_ref()
+                    .map(|value| ::bitfield::BitLookup::to_raw(value) as i64),
+            )
+        },
+        (Kind::Repeat { .. }, _) | (Kind::Variant { .. }, _) => unreachable!(),
+    };
+    quote! {
+        {
+            let spec = #spec;
+            #set?;
+        }
+    }
+}
+
+// payload length without rows or variant, these start behind all other fields
+fn bit_len(attrs: &StructAttrs, fields: &[FieldDef]) -> usize {
+    match (attrs.len, fields.iter().find(|field| field.is_tail())) {
+        (Some(len), _) => len * 8,
+        (None, Some(tail)) => tail.start,
+        (None, None) => fields.iter().map(|field| field.end + 1).max().unwrap_or(0),
+    }
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let (_, fields) = parse_struct(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let idents: Vec<&syn::Ident> = fields.iter().map(|field| &field.ident).collect();
+    // decoded into locals first, repeat and variant fields use the value of their count or
+    // select field
+    let local = |field: &FieldDef| format_ident!("field_{}", field.ident);
+    let locals: Vec<syn::Ident> = fields.iter().map(local).collect();
+    let access = |field: &FieldDef| {
+        let local = local(field);
+        quote!(#local)
+    };
+    let values = fields
+        .iter()
+        .map(|field| decode_field(field, &fields, &access));
+    Ok(quote! {
+        impl #impl_generics ::bitfield::BitDecode for #name #ty_generics #where_clause {
+            fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+                #(let #locals = #values;)*
+                Ok(#name {
+                    #(#idents: #locals,)*
+                })
+            }
+        }
+    })
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let (attrs, fields) = parse_struct(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let len = bit_len(&attrs, &fields);
+    let sets = fields.iter().map(|field| encode_field(field, &fields));
+    // the payload grows with the rows of a repeat field or the size of a variant
+    let to_vec = fields.iter().find(|field| field.is_tail()).map(|field| {
+        let ident = &field.ident;
+        let inner = &field.inner;
+        let extra = match &field.kind {
+            Kind::Repeat { .. } => quote! {
+                self.#ident.len() * <#inner as ::bitfield::BitEncode>::BIT_LEN.div_ceil(8)
+            },
+            _ => quote!(::bitfield::BitVariant::variant_bytes(&self.#ident)?.len()),
+        };
+        quote! {
+            fn to_vec(&self) -> ::bitfield::Result<Vec<u8>> {
+                let mut data = vec![0xFF; Self::BIT_LEN.div_ceil(8) + #extra];
+                self.encode(&mut ::bitfield::MutableBitField::new(&mut data))?;
+                Ok(data)
+            }
+        }
+    });
+    Ok(quote! {
+        impl #impl_generics ::bitfield::BitEncode for #name #ty_generics #where_clause {
+            const BIT_LEN: usize = #len;
+
+            fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+                #(#sets)*
+                Ok(())
+            }
+
+            #to_vec
+        }
+    })
+}
+
+fn expand_view(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let (_, fields) = parse_struct(input)?;
+    let name = &input.ident;
+    let vis = &input.vis;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "BitView can not be derived for generic structs",
+        ));
+    }
+    let view = format_ident!("{}View", name);
+    let view_mut = format_ident!("{}ViewMut", name);
+    let view_doc = format!("Lazy view of a [`{}`] payload", name);
+    let view_mut_doc = format!("Lazy mutable view of a [`{}`] payload", name);
+
+    let access = |field: &FieldDef| {
+        let ident = &field.ident;
+        quote!(self.#ident()?)
+    };
+    let getters: Vec<TokenStream2> = fields
+        .iter()
+        .map(|field| {
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let value = decode_field(field, &fields, &access);
+            let doc = if field.is_repeat() {
+                format!(
+                    "Get the rows of `{}` starting at bit {}",
+                    ident, field.start
+                )
+            } else if field.is_tail() {
+                format!(
+                    "Get the variant `{}` starting at bit {}",
+                    ident, field.start
+                )
+            } else {
+                format!("Get `{}` from bits {}..={}", ident, field.start, field.end)
+            };
+            quote! {
+                #[doc = #doc]
+                pub fn #ident(&self) -> ::bitfield::Result<#ty> {
+                    let bitfield = &self.bitfield();
+                    Ok(#value)
+                }
+            }
+        })
+        .collect();
+    // rows and variants can not be set, the payload has a fixed size
+    let setters = fields.iter().filter(|field| !field.is_tail()).map(|field| {
+        let ident = &field.ident;
+        let ty = &field.ty;
+        let setter = format_ident!("set_{}", ident);
+        let set = encode_value(field, quote!(value));
+        let doc = format!("Set `{}` in bits {}..={}", ident, field.start, field.end);
+        quote! {
+            #[doc = #doc]
+            pub fn #setter(&mut self, value: #ty) -> ::bitfield::Result<()> {
+                let bitfield = &mut self.bitfield;
+                #set
+                Ok(())
+            }
+        }
+    });
+    let idents: Vec<&syn::Ident> = fields.iter().map(|field| &field.ident).collect();
+
+    Ok(quote! {
+        #[doc = #view_doc]
+        #vis struct #view<'a> {
+            bitfield: ::bitfield::BitField<'a>,
+        }
+
+        impl<'a> #view<'a> {
+            pub fn new(bitfield: ::bitfield::BitField<'a>) -> #view<'a> {
+                #view { bitfield }
+            }
+
+            pub fn from_bytes(data: &'a [u8]) -> #view<'a> {
+                #view::new(::bitfield::BitField::new(data))
+            }
+
+            fn bitfield(&self) -> &::bitfield::BitField<'a> {
+                &self.bitfield
+            }
+
+            /// Decode all fields
+            pub fn to_owned(&self) -> ::bitfield::Result<#name> {
+                Ok(#name {
+                    #(#idents: self.#idents()?,)*
+                })
+            }
+
+            #(#getters)*
+        }
+
+        #[doc = #view_mut_doc]
+        #vis struct #view_mut<'a> {
+            bitfield: ::bitfield::MutableBitField<'a>,
+        }
+
+        impl<'a> #view_mut<'a> {
+            pub fn new(bitfield: ::bitfield::MutableBitField<'a>) -> #view_mut<'a> {
+                #view_mut { bitfield }
+            }
+
+            pub fn from_bytes(data: &'a mut [u8]) -> #view_mut<'a> {
+                #view_mut::new(::bitfield::MutableBitField::new(data))
+            }
+
+            fn bitfield(&self) -> ::bitfield::BitField<'_> {
+                ::bitfield::BitField::new(self.bitfield.as_bytes())
+            }
+
+            /// Decode all fields
+            pub fn to_owned(&self) -> ::bitfield::Result<#name> {
+                Ok(#name {
+                    #(#idents: self.#idents()?,)*
+                })
+            }
+
+            #(#getters)*
+
+            #(#setters)*
+        }
+    })
+}
+
+fn expand_lookup(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "BitLookup can only be derived for enums",
+            ))
+        }
+    };
+    let name = &input.ident;
+    let mut from_arms = Vec::new();
+    let mut to_arms = Vec::new();
+    let mut name_arms = Vec::new();
+    let mut from_name_arms = Vec::new();
+    let mut unknown = None;
+    let mut next: u64 = 0;
+    let mut seen = std::collections::HashSet::new();
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        let (is_unknown, explicit, display) = variant_attrs(variant)?;
+        if is_unknown {
+            match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 && unknown.is_none() => {
+                    unknown = Some(ident.clone());
+                    to_arms.push(quote!(#name::#ident(value) => *value));
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        variant.span(),
+                        "expected a single `#[bits(unknown)] Variant(u64)`",
+                    ))
+                }
+            }
+            continue;
+        }
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(
+                variant.span(),
+                "lookup variants must be unit variants",
+            ));
+        }
+        let value = match (explicit, &variant.discriminant) {
+            (Some(value), _) => value,
+            (None, Some((_, expr))) => discriminant(expr)?,
+            (None, None) => next,
+        };
+        if !seen.insert(value) {
+            return Err(syn::Error::new(
+                variant.span(),
+                format!("duplicate lookup value {}", value),
+            ));
+        }
+        next = value + 1;
+        let display = display.unwrap_or_else(|| ident.to_string());
+        from_arms.push(quote!(#value => Ok(#name::#ident)));
+        to_arms.push(quote!(#name::#ident => #value));
+        name_arms.push(quote!(#name::#ident => Some(#display)));
+        from_name_arms.push(quote!(#display => Some(#name::#ident)));
+    }
+    if let Some(ident) = &unknown {
+        name_arms.push(quote!(#name::#ident(_) => None));
+    }
+    let fallback = match unknown {
+        Some(ident) => quote!(value => Ok(#name::#ident(value))),
+        None => quote! {
+            value => Err(::bitfield::Error::with_context(
+                ::bitfield::ErrorKind::NotFound,
+                &format!("{}: unknown lookup value {}", stringify!(#name), value),
+            ))
+        },
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bitfield::BitLookup for #name #ty_generics #where_clause {
+            fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+                match raw {
+                    #(#from_arms,)*
+                    #fallback,
+                }
+            }
+
+            fn to_raw(&self) -> u64 {
+                match self {
+                    #(#to_arms,)*
+                }
+            }
+
+            fn name(&self) -> Option<&'static str> {
+                match self {
+                    #(#name_arms,)*
+                }
+            }
+
+            fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    #(#from_name_arms,)*
+                    _ => None,
+                }
+            }
+        }
+    })
+}
+
+fn expand_variant(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "BitVariant can only be derived for enums",
+            ))
+        }
+    };
+    let name = &input.ident;
+    let mut decode_arms = Vec::new();
+    let mut discriminator_arms = Vec::new();
+    let mut bytes_arms = Vec::new();
+    let mut unknown = None;
+    let mut seen = std::collections::HashSet::new();
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        let (is_unknown, explicit, display) = variant_attrs(variant)?;
+        if display.is_some() {
+            return Err(syn::Error::new(
+                variant.span(),
+                "`name` is only supported for lookups",
+            ));
+        }
+        if is_unknown {
+            match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 2 && unknown.is_none() => {
+                    unknown = Some(ident.clone());
+                    discriminator_arms.push(quote!(#name::#ident(value, _) => *value));
+                    bytes_arms.push(quote!(#name::#ident(_, bytes) => Ok(bytes.clone())));
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        variant.span(),
+                        "expected a single `#[bits(unknown)] Variant(u64, Vec<u8>)`",
+                    ))
+                }
+            }
+            continue;
+        }
+        let value = match (explicit, &variant.discriminant) {
+            (Some(value), _) => value,
+            (None, Some((_, expr))) => discriminant(expr)?,
+            (None, None) => {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    "missing `#[bits(value = N)]` selecting the variant",
+                ))
+            }
+        };
+        if !seen.insert(value) {
+            return Err(syn::Error::new(
+                variant.span(),
+                format!("duplicate variant value {}", value),
+            ));
+        }
+        match &variant.fields {
+            Fields::Unit => {
+                decode_arms.push(quote!(#value => Ok(#name::#ident)));
+                discriminator_arms.push(quote!(#name::#ident => #value));
+                bytes_arms.push(quote!(#name::#ident => Ok(Vec::new())));
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                decode_arms.push(quote! {
+                    #value => Ok(#name::#ident(<#ty as ::bitfield::BitDecode>::decode(bitfield)?))
+                });
+                discriminator_arms.push(quote!(#name::#ident(_) => #value));
+                bytes_arms
+                    .push(quote!(#name::#ident(value) => ::bitfield::BitEncode::to_vec(value)));
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    variant.span(),
+                    "variants must be unit variants or hold a single BitDecode + BitEncode type",
+                ))
+            }
+        }
+    }
+    let fallback = match unknown {
+        Some(ident) => quote!(value => Ok(#name::#ident(value, bitfield.as_bytes().to_vec()))),
+        None => quote! {
+            value => Err(::bitfield::Error::with_context(
+                ::bitfield::ErrorKind::NotFound,
+                &format!("{}: unknown variant {}", stringify!(#name), value),
+            ))
+        },
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::bitfield::BitVariant for #name #ty_generics #where_clause {
+            fn decode_variant(
+                discriminator: u64,
+                bitfield: &::bitfield::BitField,
+            ) -> ::bitfield::Result<Self> {
+                match discriminator {
+                    #(#decode_arms,)*
+                    #fallback,
+                }
+            }
+
+            fn discriminator(&self) -> u64 {
+                match self {
+                    #(#discriminator_arms,)*
+                }
+            }
+
+            fn variant_bytes(&self) -> ::bitfield::Result<Vec<u8>> {
+                match self {
+                    #(#bytes_arms,)*
+                }
+            }
+        }
+    })
+}
+
+// the `unknown` flag and `value` of an enum variant
+fn variant_attrs(variant: &syn::Variant) -> syn::Result<(bool, Option<u64>, Option<String>)> {
+    let mut is_unknown = false;
+    let mut explicit = None;
+    let mut display = None;
+    for attr in variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("bits"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("unknown") {
+                is_unknown = true;
+                Ok(())
+            } else if meta.path.is_ident("value") {
+                explicit = Some(meta.value()?.parse::<syn::LitInt>()?.base10_parse()?);
+                Ok(())
+            } else if meta.path.is_ident("name") {
+                display = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unknown bits attribute, expected `unknown`, `value` or `name`"))
+            }
+        })?;
+    }
+    Ok((is_unknown, explicit, display))
+}
+
+fn discriminant(expr: &Expr) -> syn::Result<u64> {
+    if let Expr::Lit(lit) = expr {
+        if let Lit::Int(value) = &lit.lit {
+            return value.base10_parse();
+        }
+    }
+    Err(syn::Error::new(
+        expr.span(),
+        "lookup values must be integer literals",
+    ))
+}
@@ -0,0 +1,295 @@
+use bitfield::{BitDecode, BitEncode, BitLookup, BitVariant, BitView, ErrorKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, BitLookup)]
+enum Mode {
+    Standby,
+    Auto,
+    Wind = 4,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, BitLookup)]
+enum Reference {
+    #[bits(value = 0)]
+    True,
+    #[bits(value = 1, name = "Magnetic North")]
+    Magnetic,
+    #[bits(unknown)]
+    Unknown(u64),
+}
+
+#[derive(Debug, PartialEq, BitDecode, BitEncode)]
+#[bits(len = 8)]
+struct Sample {
+    #[bits(start = 0, end = 7)]
+    sid: u8,
+    #[bits(start = 8, end = 23, be)]
+    counter: u16,
+    #[bits(start = 24, end = 39, resolution = 0.01, offset = -10)]
+    level: f64,
+    #[bits(start = 40, end = 47)]
+    temp: Option<i8>,
+    #[bits(start = 48, end = 50, lookup)]
+    mode: Mode,
+    #[bits(start = 51, end = 52, lookup)]
+    reference: Reference,
+    #[bits(start = 53, size = 1)]
+    flag: bool,
+    #[bits(start = 54, size = 2)]
+    alarm: Option<bool>,
+}
+
+#[test]
+fn test_derive_roundtrip() {
+    let sample = Sample {
+        sid: 7,
+        counter: 0x1234,
+        level: 5.5,
+        temp: Some(-20),
+        mode: Mode::Wind,
+        reference: Reference::Unknown(3),
+        flag: true,
+        alarm: None,
+    };
+    assert_eq!(Sample::BIT_LEN, 64);
+    let data = sample.to_vec().unwrap();
+    assert_eq!(data[..5], [0x07, 0x12, 0x34, 0x0E, 0x06]);
+    assert_eq!(data[5], -20i8 as u8);
+    assert_eq!(data[6], 0b10011111);
+    assert_eq!(Sample::from_bytes(&data).unwrap(), sample);
+
+    let mut data = data;
+    data[5] = 0x7F;
+    data[6] = 0b00101000;
+    let decoded = Sample::from_bytes(&data).unwrap();
+    assert_eq!(decoded.temp, None);
+    assert_eq!(decoded.mode, Mode::Auto);
+    assert_eq!(decoded.reference, Reference::Magnetic);
+    assert!(!decoded.flag);
+    assert_eq!(decoded.alarm, Some(false));
+
+    data[6] = 0b01000000;
+    assert_eq!(
+        Sample::from_bytes(&data).unwrap_err().kind(),
+        ErrorKind::NotFound
+    );
+    assert!(Sample::from_bytes(&data[..6]).is_err());
+
+    assert_eq!(Mode::Wind.name(), Some("Wind"));
+    assert_eq!(Mode::from_name("Auto"), Some(Mode::Auto));
+    assert_eq!(Reference::Magnetic.name(), Some("Magnetic North"));
+    assert_eq!(
+        Reference::from_name("Magnetic North"),
+        Some(Reference::Magnetic)
+    );
+    assert_eq!(Reference::Unknown(3).name(), None);
+    assert_eq!(Reference::from_name("Grid"), None);
+}
+
+#[derive(Debug, PartialEq, BitDecode, BitEncode, BitView)]
+#[bits(len = 8)]
+pub struct PositionRapid {
+    #[bits(start = 0, end = 31, signed, resolution = 1e-7)]
+    latitude: Option<f64>,
+    #[bits(start = 32, end = 63, signed, resolution = 1e-7)]
+    longitude: Option<f64>,
+}
+
+#[test]
+fn test_view() {
+    let data = [0x80, 0x96, 0x98, 0x00, 0xFF, 0xFF, 0xFF, 0x7F];
+    let view = PositionRapidView::from_bytes(&data);
+    assert!((view.latitude().unwrap().unwrap() - 1.0).abs() < 1e-9);
+    assert_eq!(view.longitude().unwrap(), None);
+    assert_eq!(
+        view.to_owned().unwrap(),
+        PositionRapid::from_bytes(&data).unwrap()
+    );
+    assert!(PositionRapidView::from_bytes(&data[..4])
+        .longitude()
+        .is_err());
+
+    let mut data = [0xFF; 8];
+    let mut view = PositionRapidViewMut::from_bytes(&mut data);
+    view.set_latitude(Some(-1.0)).unwrap();
+    view.set_longitude(Some(2.0)).unwrap();
+    assert!((view.latitude().unwrap().unwrap() + 1.0).abs() < 1e-9);
+    view.set_longitude(None).unwrap();
+    assert_eq!(view.longitude().unwrap(), None);
+    assert_eq!(data, [0x80, 0x69, 0x67, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F]);
+}
+
+#[derive(Debug, Clone, PartialEq, BitDecode, BitEncode)]
+struct Satellite {
+    #[bits(start = 0, end = 7, intel)]
+    prn: u8,
+    #[bits(start = 8, end = 23, intel, resolution = 0.01)]
+    snr: Option<f64>,
+}
+
+#[derive(Debug, PartialEq, BitDecode, BitEncode, BitView)]
+pub struct SatsInView {
+    #[bits(start = 0, end = 7, intel)]
+    sid: u8,
+    #[bits(start = 8, end = 15, intel)]
+    count: Option<u8>,
+    #[bits(start = 16, repeat, count = count)]
+    satellites: Vec<Satellite>,
+}
+
+#[derive(Debug, PartialEq, BitDecode, BitEncode)]
+struct PgnList {
+    #[bits(start = 0, end = 7, intel)]
+    function: u8,
+    #[bits(start = 8, repeat)]
+    pgns: Vec<Pgn>,
+}
+
+#[derive(Debug, Clone, PartialEq, BitDecode, BitEncode)]
+struct Pgn {
+    #[bits(start = 0, end = 23, intel)]
+    pgn: u32,
+}
+
+#[derive(Debug, PartialEq, BitDecode, BitEncode)]
+struct NibbleList {
+    #[bits(start = 0, end = 3, intel)]
+    count: u8,
+    #[bits(start = 8, repeat, count = count)]
+    items: Vec<Pgn>,
+}
+
+#[test]
+fn test_repeat() {
+    let data = [0x01, 0x02, 0x05, 0x88, 0x13, 0x0C, 0xFF, 0xFF];
+    let sats = SatsInView::from_bytes(&data).unwrap();
+    assert_eq!(sats.count, Some(2));
+    assert_eq!(
+        sats.satellites,
+        vec![
+            Satellite {
+                prn: 5,
+                snr: Some(50.0)
+            },
+            Satellite { prn: 12, snr: None }
+        ]
+    );
+    assert_eq!(SatsInView::BIT_LEN, 16);
+    assert_eq!(sats.to_vec().unwrap(), data);
+    assert_eq!(
+        SatsInView::from_bytes(&data[..6]).unwrap_err().kind(),
+        ErrorKind::OutOfRange
+    );
+
+    // the count is written from the rows
+    let sats = SatsInView {
+        sid: 1,
+        count: None,
+        satellites: vec![Satellite { prn: 3, snr: None }],
+    };
+    assert_eq!(sats.to_vec().unwrap(), [0x01, 0x01, 0x03, 0xFF, 0xFF]);
+    let sats = SatsInView {
+        satellites: vec![Satellite { prn: 3, snr: None }; 256],
+        ..sats
+    };
+    assert_eq!(sats.to_vec().unwrap_err().kind(), ErrorKind::OutOfRange);
+    // 255 rows would read back as a "not available" count
+    let sats = SatsInView {
+        satellites: vec![Satellite { prn: 3, snr: None }; 255],
+        ..sats
+    };
+    assert_eq!(sats.to_vec().unwrap_err().kind(), ErrorKind::OutOfRange);
+    // the count is limited by its bit width, not only by its type
+    let list = NibbleList {
+        count: 0,
+        items: vec![Pgn { pgn: 1 }; 16],
+    };
+    assert_eq!(list.to_vec().unwrap_err().kind(), ErrorKind::OutOfRange);
+    let list = NibbleList {
+        items: vec![Pgn { pgn: 1 }; 15],
+        ..list
+    };
+    assert_eq!(list.to_vec().unwrap()[0] & 0x0F, 0x0F);
+
+    let view = SatsInViewView::from_bytes(&data);
+    assert_eq!(view.satellites().unwrap().len(), 2);
+    let mut data = data;
+    SatsInViewViewMut::from_bytes(&mut data)
+        .set_count(Some(1))
+        .unwrap();
+    assert_eq!(SatsInView::from_bytes(&data).unwrap().satellites.len(), 1);
+
+    // without a count field the rows fill the payload
+    let data = [0x00, 0x00, 0xEE, 0x00, 0x10, 0xF0, 0x01, 0xFF];
+    let list = PgnList::from_bytes(&data).unwrap();
+    assert_eq!(list.pgns, vec![Pgn { pgn: 60928 }, Pgn { pgn: 126992 }]);
+    assert_eq!(list.to_vec().unwrap(), data[..7]);
+}
+
+#[derive(Debug, PartialEq, BitDecode, BitEncode)]
+struct MaretronCommand {
+    #[bits(start = 0, end = 7, intel)]
+    command: u8,
+    #[bits(start = 8, end = 23, intel)]
+    level: u16,
+}
+
+#[derive(Debug, PartialEq, BitVariant)]
+enum ProprietaryData {
+    #[bits(value = 137)]
+    Maretron(MaretronCommand),
+    #[bits(value = 229)]
+    Garmin,
+    #[bits(unknown)]
+    Unknown(u64, Vec<u8>),
+}
+
+#[derive(Debug, PartialEq, BitDecode, BitEncode, BitView)]
+pub struct Proprietary {
+    #[bits(start = 0, end = 10, intel)]
+    manufacturer: u16,
+    #[bits(start = 13, end = 15, intel)]
+    industry: u8,
+    #[bits(start = 16, select = manufacturer)]
+    data: ProprietaryData,
+}
+
+#[test]
+fn test_variant() {
+    let data = [0x89, 0x98, 0x02, 0x10, 0x27];
+    let message = Proprietary::from_bytes(&data).unwrap();
+    assert_eq!(message.manufacturer, 137);
+    assert_eq!(
+        message.data,
+        ProprietaryData::Maretron(MaretronCommand {
+            command: 2,
+            level: 10000
+        })
+    );
+    assert_eq!(message.to_vec().unwrap(), data);
+    assert_eq!(
+        ProprietaryView::from_bytes(&data).data().unwrap(),
+        message.data
+    );
+
+    // unknown manufacturers keep their bytes
+    let data = [0x13, 0x99, 0x01, 0x02, 0x03];
+    let message = Proprietary::from_bytes(&data).unwrap();
+    assert_eq!(
+        message.data,
+        ProprietaryData::Unknown(275, vec![0x01, 0x02, 0x03])
+    );
+    assert_eq!(message.to_vec().unwrap(), data);
+
+    // the discriminator is written from the variant
+    let message = Proprietary {
+        manufacturer: 0,
+        industry: 4,
+        data: ProprietaryData::Garmin,
+    };
+    assert_eq!(message.to_vec().unwrap(), [0xE5, 0x98]);
+    assert_eq!(ProprietaryData::Garmin.discriminator(), 229);
+    assert_eq!(
+        ProprietaryData::decode_variant(229, &bitfield::BitField::new(&[])).unwrap(),
+        ProprietaryData::Garmin
+    );
+}
@@ -0,0 +1,309 @@
+use crate::can::{CanFrame, FrameSink, BROADCAST};
+use crate::clock::Clock;
+use crate::error::Result;
+use crate::iso_name::{parse_address_claim, IsoName, ADDRESS_CLAIM, NULL_ADDRESS};
+use crate::request::{IsoRequest, ISO_REQUEST};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+// ISO 11783-5 address claiming. A node claims its preferred address and may use it once no
+// other node contested the claim for 250 ms. When two nodes claim the same address the one
+// with the lower NAME keeps it, the other one either claims a free address from the self
+// configurable range 128 - 247 if it is arbitrary address capable, or announces that it
+// cannot claim an address by claiming the null address 254. Requests for the address claim
+// PGN are answered with the current claim.
+
+/// Time a claim has to stand before the address can be used
+pub const CLAIM_TIMEOUT: Duration = Duration::from_millis(250);
+/// First and last address picked by arbitrary address capable nodes
+pub const SELF_CONFIGURABLE: (u8, u8) = (128, 247);
+
+/// State of the local node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimState {
+    /// not started
+    Idle,
+    /// the claim for address was sent at since
+    Claiming { address: u8, since: Duration },
+    /// the address can be used
+    Claimed(u8),
+    /// no address is free, the node may only answer requests
+    CannotClaim,
+}
+
+/// Address claim procedure of a local node
+#[derive(Debug)]
+pub struct AddressClaimer<C: Clock> {
+    name: IsoName,
+    preferred: u8,
+    clock: C,
+    state: ClaimState,
+    /// addresses claimed by other nodes
+    peers: BTreeMap<u8, IsoName>,
+}
+
+impl<C: Clock> AddressClaimer<C> {
+    pub fn new(name: IsoName, preferred: u8, clock: C) -> AddressClaimer<C> {
+        AddressClaimer {
+            name,
+            preferred,
+            clock,
+            state: ClaimState::Idle,
+            peers: BTreeMap::new(),
+        }
+    }
+
+    pub fn name(&self) -> IsoName {
+        self.name
+    }
+
+    /// Get the state, a standing claim becomes Claimed after CLAIM_TIMEOUT
+    pub fn state(&mut self) -> ClaimState {
+        self.poll();
+        self.state
+    }
+
+    /// Get the address the node may send from
+    pub fn address(&mut self) -> Option<u8> {
+        match self.state() {
+            ClaimState::Claimed(address) => Some(address),
+            _ => None,
+        }
+    }
+
+    /// Get the addresses claimed by other nodes
+    pub fn peers(&self) -> &BTreeMap<u8, IsoName> {
+        &self.peers
+    }
+
+    /// Claim the preferred address
+    pub fn start<S: FrameSink>(&mut self, sink: &mut S) -> Result<()> {
+        self.claim(self.preferred, sink)
+    }
+
+    /// Move a standing claim to Claimed
+    pub fn poll(&mut self) {
+        if let ClaimState::Claiming { address, since } = self.state {
+            if self.clock.now() >= since + CLAIM_TIMEOUT {
+                self.state = ClaimState::Claimed(address);
+            }
+        }
+    }
+
+    /// Handle a received frame, answers go to sink
+    pub fn push<S: FrameSink>(&mut self, frame: &CanFrame, sink: &mut S) -> Result<()> {
+        self.poll();
+        let header = match frame.header() {
+            Ok(header) => header,
+            Err(_) => return Ok(()),
+        };
+        match header.pgn {
+            ISO_REQUEST => {
+                let request = match IsoRequest::from_frame(frame) {
+                    Ok((_, request)) => request,
+                    Err(_) => return Ok(()),
+                };
+                let own = self.own_address();
+                if request.pgn == ADDRESS_CLAIM
+                    && (header.destination == BROADCAST || Some(header.destination) == own)
+                {
+                    match own {
+                        Some(address) => sink.send(self.name.address_claim(address)?)?,
+                        None if self.state == ClaimState::CannotClaim => {
+                            sink.send(self.name.cannot_claim()?)?
+                        }
+                        None => (),
+                    }
+                }
+            }
+            ADDRESS_CLAIM if frame.data().len() == 8 => {
+                let (address, name) = parse_address_claim(frame)?;
+                if name == self.name {
+                    return Ok(());
+                }
+                self.peers.retain(|_, peer| *peer != name);
+                if address == NULL_ADDRESS {
+                    return Ok(());
+                }
+                if self.own_address() == Some(address) && self.name < name {
+                    // defend, the other node has to yield
+                    sink.send(self.name.address_claim(address)?)?;
+                    return Ok(());
+                }
+                // of two other nodes contending the lower NAME keeps the address
+                if self.peers.get(&address).map_or(true, |peer| *peer > name) {
+                    self.peers.insert(address, name);
+                }
+                if self.own_address() == Some(address) {
+                    self.yield_address(sink)?;
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// The address claimed or being claimed
+    fn own_address(&self) -> Option<u8> {
+        match self.state {
+            ClaimState::Claiming { address, .. } | ClaimState::Claimed(address) => Some(address),
+            _ => None,
+        }
+    }
+
+    fn claim<S: FrameSink>(&mut self, address: u8, sink: &mut S) -> Result<()> {
+        sink.send(self.name.address_claim(address)?)?;
+        self.state = ClaimState::Claiming {
+            address,
+            since: self.clock.now(),
+        };
+        Ok(())
+    }
+
+    /// Claim a free address or give up after losing the current one
+    fn yield_address<S: FrameSink>(&mut self, sink: &mut S) -> Result<()> {
+        let free = if self.name.arbitrary_address_capable() {
+            (SELF_CONFIGURABLE.0..=SELF_CONFIGURABLE.1)
+                .find(|address| !self.peers.contains_key(address))
+        } else {
+            None
+        };
+        match free {
+            Some(address) => self.claim(address, sink),
+            None => {
+                self.state = ClaimState::CannotClaim;
+                sink.send(self.name.cannot_claim()?)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use crate::iso_name::{request_address_claim, NameFields};
+
+    fn name(unique_number: u32, arbitrary_address_capable: bool) -> IsoName {
+        NameFields {
+            unique_number,
+            manufacturer_code: 229,
+            industry_group: 4,
+            arbitrary_address_capable,
+            ..NameFields::default()
+        }
+        .to_name()
+        .unwrap()
+    }
+
+    /// Nodes on an in-memory bus, every frame is delivered to all other nodes
+    struct Bus {
+        nodes: Vec<AddressClaimer<ManualClock>>,
+        log: Vec<CanFrame>,
+    }
+
+    impl Bus {
+        fn new(nodes: Vec<AddressClaimer<ManualClock>>) -> Bus {
+            Bus {
+                nodes,
+                log: Vec::new(),
+            }
+        }
+
+        fn start(&mut self, index: usize) {
+            let mut frames = Vec::new();
+            self.nodes[index].start(&mut frames).unwrap();
+            self.deliver(index, frames);
+        }
+
+        /// Deliver frames sent by node from until the bus is quiet, None sends from outside
+        fn deliver<I: Into<Option<usize>>>(&mut self, from: I, frames: Vec<CanFrame>) {
+            let from = from.into();
+            let mut queue: Vec<(Option<usize>, CanFrame)> =
+                frames.into_iter().map(|frame| (from, frame)).collect();
+            while !queue.is_empty() {
+                let (sender, frame) = queue.remove(0);
+                self.log.push(frame.clone());
+                for index in 0..self.nodes.len() {
+                    if Some(index) == sender {
+                        continue;
+                    }
+                    let mut answers = Vec::new();
+                    self.nodes[index].push(&frame, &mut answers).unwrap();
+                    queue.extend(answers.into_iter().map(|answer| (Some(index), answer)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_claim() {
+        let clock = ManualClock::new();
+        let mut bus = Bus::new(vec![AddressClaimer::new(name(1, false), 35, clock.clone())]);
+        bus.start(0);
+        assert_eq!(bus.log, vec![name(1, false).address_claim(35).unwrap()]);
+        assert!(matches!(
+            bus.nodes[0].state(),
+            ClaimState::Claiming { address: 35, .. }
+        ));
+        assert_eq!(bus.nodes[0].address(), None);
+        clock.advance(CLAIM_TIMEOUT);
+        assert_eq!(bus.nodes[0].state(), ClaimState::Claimed(35));
+        assert_eq!(bus.nodes[0].address(), Some(35));
+
+        // requests to all nodes and to the own address are answered
+        bus.log.clear();
+        bus.deliver(
+            None,
+            vec![request_address_claim(NULL_ADDRESS, BROADCAST).unwrap()],
+        );
+        bus.deliver(None, vec![request_address_claim(0x10, 35).unwrap()]);
+        bus.deliver(None, vec![request_address_claim(0x10, 36).unwrap()]);
+        assert_eq!(bus.log.len(), 5);
+        assert_eq!(bus.log[1], name(1, false).address_claim(35).unwrap());
+        assert_eq!(bus.log[3], bus.log[1]);
+    }
+
+    #[test]
+    fn test_contention() {
+        let clock = ManualClock::new();
+        let mut bus = Bus::new(vec![
+            AddressClaimer::new(name(1, false), 35, clock.clone()),
+            AddressClaimer::new(name(2, true), 35, clock.clone()),
+            AddressClaimer::new(name(3, false), 35, clock.clone()),
+        ]);
+        bus.start(0);
+        clock.advance(CLAIM_TIMEOUT);
+        // the higher NAME moves to a self configurable address
+        bus.start(1);
+        clock.advance(CLAIM_TIMEOUT);
+        assert_eq!(bus.nodes[0].state(), ClaimState::Claimed(35));
+        assert_eq!(bus.nodes[1].state(), ClaimState::Claimed(128));
+        assert_eq!(bus.nodes[1].peers()[&35], name(1, false));
+        assert_eq!(bus.nodes[0].peers()[&128], name(2, true));
+
+        // a node that is not arbitrary address capable gives up
+        bus.start(2);
+        assert_eq!(bus.nodes[2].state(), ClaimState::CannotClaim);
+        assert_eq!(
+            bus.log.last(),
+            Some(&name(3, false).cannot_claim().unwrap())
+        );
+        assert_eq!(bus.nodes[0].state(), ClaimState::Claimed(35));
+        bus.log.clear();
+        bus.deliver(
+            None,
+            vec![request_address_claim(NULL_ADDRESS, BROADCAST).unwrap()],
+        );
+        assert!(bus.log.contains(&name(3, false).cannot_claim().unwrap()));
+
+        // a lower NAME takes the address of a claimed node
+        let mut frames = Vec::new();
+        let mut winner = AddressClaimer::new(name(0, false), 128, clock.clone());
+        winner.start(&mut frames).unwrap();
+        bus.deliver(None, frames);
+        clock.advance(CLAIM_TIMEOUT);
+        assert_eq!(bus.nodes[1].state(), ClaimState::Claimed(129));
+        assert_eq!(bus.nodes[1].peers()[&128], name(0, false));
+    }
+}
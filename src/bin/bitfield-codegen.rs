@@ -0,0 +1,56 @@
+use bitfield::codegen::CodeGenerator;
+use bitfield::PgnDatabase;
+use std::env;
+use std::process;
+
+// Generate Rust types from a canboat PGN database:
+// bitfield-codegen <pgns.json|pgns.xml> [output.rs] [--pgn <number>]...
+
+fn usage() -> ! {
+    eprintln!("usage: bitfield-codegen <pgns.json|pgns.xml> [output.rs] [--pgn <number>]...");
+    process::exit(2);
+}
+
+fn main() {
+    let mut input = None;
+    let mut output = None;
+    let mut pgns = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--pgn" {
+            match args.next().and_then(|pgn| pgn.parse().ok()) {
+                Some(pgn) => pgns.push(pgn),
+                None => usage(),
+            }
+        } else if arg.starts_with('-') {
+            usage();
+        } else if input.is_none() {
+            input = Some(arg);
+        } else if output.is_none() {
+            output = Some(arg);
+        } else {
+            usage();
+        }
+    }
+    let input = input.unwrap_or_else(|| usage());
+
+    let database = match PgnDatabase::load(&input) {
+        Ok(database) => database,
+        Err(why) => {
+            eprintln!("failed to load {}: {}", input, why);
+            process::exit(1);
+        }
+    };
+    let mut generator = CodeGenerator::new(&database);
+    if !pgns.is_empty() {
+        generator = generator.with_pgns(&pgns);
+    }
+    let result = match &output {
+        Some(output) => generator.write_to(output),
+        None => generator.generate().map(|source| print!("{}", source)),
+    };
+    if let Err(why) = result {
+        eprintln!("failed to generate code: {}", why);
+        process::exit(1);
+    }
+}
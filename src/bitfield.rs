@@ -1,13 +1,52 @@
+#![allow(
+    clippy::assign_op_pattern,
+    clippy::bool_assert_comparison,
+    clippy::precedence,
+    clippy::unnecessary_cast,
+    clippy::useless_format
+)]
+
 use crate::error::{Error, ErrorKind, Result, ToError};
 use log::debug;
+use std::borrow::Cow;
+
+/// Byte order of multi byte values
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Endianness {
+    Big,
+    Little,
+    /// Little endian with bits counted from the least significant bit of each byte,
+    /// bit n is bit n % 8 of byte n / 8. This is the layout used by NMEA 2000,
+    /// ISO 11783 and canboat bit offsets.
+    Intel,
+}
 
 pub struct BitField<'a> {
-    data: &'a [u8],
+    data: Cow<'a, [u8]>,
 }
 
 impl<'a> BitField<'a> {
     pub fn new(data: &'a [u8]) -> BitField<'a> {
-        BitField { data }
+        BitField {
+            data: Cow::Borrowed(data),
+        }
+    }
+
+    /// Create a bitfield owning its data
+    pub fn from_vec(data: Vec<u8>) -> BitField<'static> {
+        BitField {
+            data: Cow::Owned(data),
+        }
+    }
+
+    /// Get the underlying bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get the size of the bitfield in bits
+    pub fn bit_len(&self) -> usize {
+        self.data.len() * 8
     }
 
     /// Get a single bit
@@ -25,6 +64,72 @@ impl<'a> BitField<'a> {
             == 1)
     }
 
+    /// Get a i64 value from the given offset and size, see get_u64
+    pub fn get_i64(&self, start: usize, end: usize, endianness: Endianness) -> Result<i64> {
+        let value = self.get_u64(start, end, endianness)?;
+        let shift = 63 - (end - start);
+        Ok(((value << shift) as i64) >> shift)
+    }
+
+    /// Get a u64 value from the given offset and size.
+    /// Gives the same result as get_u64_be / get_u64_le but reads the bytes in one go,
+    /// use this for decoding large numbers of values
+    pub fn get_u64(&self, start: usize, end: usize, endianness: Endianness) -> Result<u64> {
+        if end < start {
+            return Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!("Start is greater that end {} > {}", start, end),
+            ));
+        }
+        let size = end - start + 1;
+        if size > 64 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "get_u64: too many bits {} to {} = {} > 64",
+                    start, end, size
+                ),
+            ));
+        }
+        if end / 8 >= self.data.len() {
+            return Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!(
+                    "get_u64: bit index is out of range: {} >= {}",
+                    end,
+                    self.data.len() * 8
+                ),
+            ));
+        }
+
+        let mut raw: u128 = 0;
+        if endianness == Endianness::Intel {
+            for byte in self.data[start / 8..=end / 8].iter().rev() {
+                raw = raw << 8 | *byte as u128;
+            }
+            return Ok((raw >> (start % 8)) as u64 & BitField::mask_u64(size));
+        }
+        for byte in &self.data[start / 8..=end / 8] {
+            raw = raw << 8 | *byte as u128;
+        }
+        let value = (raw >> (7 - end % 8)) as u64 & BitField::mask_u64(size);
+        if endianness == Endianness::Big {
+            return Ok(value);
+        }
+        // little endian: the first byte in the field is least significant, a trailing partial
+        // byte is most significant
+        let full = size / 8;
+        let mut result = if size % 8 != 0 {
+            (value & BitField::mask_u64(size % 8)) << (full * 8)
+        } else {
+            0
+        };
+        for index in 0..full {
+            result |= ((value >> (size - 8 * (index + 1))) & 0xFF) << (index * 8);
+        }
+        Ok(result)
+    }
+
     /// Get a i64 big endian value from the given offset and size
     pub fn get_i64_be(&self, start: usize, end: usize) -> Result<i64> {
         debug!("get_i64_be: {},{}", start, end);
@@ -350,6 +455,14 @@ impl<'a> BitField<'a> {
         }
     }
 
+    fn mask_u64(size: usize) -> u64 {
+        if size >= 64 {
+            u64::MAX
+        } else {
+            (1u64 << size) - 1
+        }
+    }
+
     fn twos_complement_u64(val: u64, sign_bit: usize) -> Result<i64> {
         debug!("twos_complement_u64: {:016x}, {}", val, sign_bit);
         if sign_bit > 63 {
@@ -684,4 +797,56 @@ mod tests {
             0b000000001010101010101010101010101
         );
     }
+
+    #[test]
+    fn test_get_u64() {
+        const BYTES: [u8; 10] = [
+            0b10110010, 0b00011111, 0b11100101, 0b01011010, 0b10000001, 0b01111110, 0b11001100,
+            0b00110011, 0b10011001, 0b01100110,
+        ];
+        let bitfield = BitField::new(&BYTES);
+        for start in 0..16 {
+            for end in start..(start + 64).min(80) {
+                assert_eq!(
+                    bitfield.get_u64(start, end, Endianness::Big).unwrap(),
+                    bitfield.get_u64_be(start, end).unwrap()
+                );
+                assert_eq!(
+                    bitfield.get_u64(start, end, Endianness::Little).unwrap(),
+                    bitfield.get_u64_le(start, end).unwrap()
+                );
+                assert_eq!(
+                    bitfield.get_i64(start, end, Endianness::Little).unwrap(),
+                    bitfield.get_i64_le(start, end).unwrap()
+                );
+            }
+        }
+        assert!(bitfield.get_u64(0, 64, Endianness::Big).is_err());
+        assert!(bitfield.get_u64(20, 80, Endianness::Big).is_err());
+        assert!(bitfield.get_u64(8, 7, Endianness::Big).is_err());
+    }
+
+    #[test]
+    fn test_get_u64_intel() {
+        // engine instance 0, speed 0x1F40 (2000 rpm), tilt/trim -5
+        const BYTES: [u8; 8] = [0x00, 0x40, 0x1F, 0xFF, 0xFF, 0xFB, 0xFF, 0xFF];
+        let bitfield = BitField::new(&BYTES);
+        assert_eq!(bitfield.get_u64(8, 23, Endianness::Intel).unwrap(), 0x1F40);
+        assert_eq!(bitfield.get_i64(40, 47, Endianness::Intel).unwrap(), -5);
+        // 4 bit fields, low nibble first
+        let bitfield = BitField::new(&[0xA5, 0x3C]);
+        assert_eq!(bitfield.get_u64(0, 3, Endianness::Intel).unwrap(), 0x5);
+        assert_eq!(bitfield.get_u64(4, 7, Endianness::Intel).unwrap(), 0xA);
+        assert_eq!(bitfield.get_u64(4, 11, Endianness::Intel).unwrap(), 0xCA);
+        assert_eq!(bitfield.get_u64(2, 12, Endianness::Intel).unwrap(), 0x729);
+        // a full 64 bit value spanning 9 bytes
+        let bitfield = BitField::new(&[0x0F, 0x21, 0x43, 0x65, 0x87, 0xA9, 0xCB, 0xED, 0x0F]);
+        assert_eq!(
+            bitfield.get_u64(4, 67, Endianness::Intel).unwrap(),
+            0xFEDC_BA98_7654_3210
+        );
+        assert!(bitfield.get_u64(4, 72, Endianness::Intel).is_err());
+        assert!(bitfield.get_u64(8, 72, Endianness::Intel).is_err());
+        assert!(bitfield.get_u64(8, 7, Endianness::Intel).is_err());
+    }
 }
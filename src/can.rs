@@ -0,0 +1,606 @@
+use crate::bitfield::BitField;
+use crate::error::{Error, ErrorKind, Result};
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+// 29 bit extended CAN identifiers as used by J1939 and NMEA 2000:
+//
+//   bits 28..26  priority
+//   bit  25      extended data page (EDP), reserved in NMEA 2000
+//   bit  24      data page (DP)
+//   bits 23..16  PDU format (PF)
+//   bits 15..8   PDU specific (PS), the destination address for PF < 240 (PDU1),
+//                the group extension for PF >= 240 (PDU2)
+//   bits 7..0    source address (SA)
+
+/// Largest 29 bit identifier
+pub const MAX_CAN_ID: u32 = 0x1FFF_FFFF;
+/// Largest 11 bit identifier
+pub const MAX_STANDARD_ID: u32 = 0x7FF;
+/// Payload lengths of CAN FD frames by DLC
+const FD_LENGTHS: [usize; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+/// The global destination address
+pub const BROADCAST: u8 = 0xFF;
+
+/// The fields of a 29 bit extended CAN identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CanId {
+    /// 0 (highest) to 7
+    pub priority: u8,
+    pub edp: bool,
+    pub dp: bool,
+    pub pf: u8,
+    pub ps: u8,
+    pub sa: u8,
+}
+
+impl CanId {
+    /// Split a raw identifier, bits above bit 28 must be 0
+    pub fn from_raw(raw: u32) -> Result<CanId> {
+        if raw > MAX_CAN_ID {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("CanId::from_raw: {:#X} is not a 29 bit identifier", raw),
+            ));
+        }
+        Ok(CanId {
+            priority: (raw >> 26) as u8 & 0x07,
+            edp: raw >> 25 & 1 == 1,
+            dp: raw >> 24 & 1 == 1,
+            pf: (raw >> 16) as u8,
+            ps: (raw >> 8) as u8,
+            sa: raw as u8,
+        })
+    }
+
+    /// Join the fields to a raw identifier, the priority must be 0 to 7
+    pub fn to_raw(&self) -> Result<u32> {
+        if self.priority > 7 {
+            return Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!("CanId::to_raw: invalid priority {}", self.priority),
+            ));
+        }
+        Ok((self.priority as u32) << 26
+            | (self.edp as u32) << 25
+            | (self.dp as u32) << 24
+            | (self.pf as u32) << 16
+            | (self.ps as u32) << 8
+            | self.sa as u32)
+    }
+
+    /// Whether PS holds a destination address
+    pub fn is_pdu1(&self) -> bool {
+        self.pf < 240
+    }
+
+    /// Get the parameter group number, PS is not part of it for PDU1
+    pub fn pgn(&self) -> u32 {
+        let pgn = (self.edp as u32) << 17 | (self.dp as u32) << 16 | (self.pf as u32) << 8;
+        if self.is_pdu1() {
+            pgn
+        } else {
+            pgn | self.ps as u32
+        }
+    }
+
+    /// Get the destination address, broadcast for PDU2
+    pub fn destination(&self) -> u8 {
+        if self.is_pdu1() {
+            self.ps
+        } else {
+            BROADCAST
+        }
+    }
+
+    /// Whether the message is sent to all nodes
+    pub fn is_broadcast(&self) -> bool {
+        self.destination() == BROADCAST
+    }
+
+    /// Whether the PGN is one of the manufacturer proprietary PGNs
+    pub fn is_proprietary(&self) -> bool {
+        is_proprietary(self.pgn())
+    }
+
+    /// Check the reserved bits: EDP must be 0, EDP 1 with DP 1 is used by ISO 15765-3 and EDP 1
+    /// with DP 0 is reserved
+    pub fn check(&self) -> Result<()> {
+        if self.edp {
+            Err(Error::with_context(
+                ErrorKind::InvFormat,
+                &format!(
+                    "CanId::check: extended data page set in {:#X}",
+                    self.to_raw().unwrap_or_default()
+                ),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Display for CanId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:08X}", self.to_raw().unwrap_or_default())
+    }
+}
+
+/// Whether pgn is a manufacturer proprietary PGN: single frame addressed (61184),
+/// single frame broadcast (65280 - 65535), fast packet addressed (126720) or fast packet
+/// broadcast (130816 - 131071)
+pub fn is_proprietary(pgn: u32) -> bool {
+    matches!(pgn, 0xEF00 | 0xFF00..=0xFFFF | 0x1EF00 | 0x1FF00..=0x1FFFF)
+}
+
+/// The header of an NMEA 2000 message as carried by a CAN identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct N2kHeader {
+    pub priority: u8,
+    pub pgn: u32,
+    pub source: u8,
+    /// BROADCAST for PDU2 PGNs
+    pub destination: u8,
+}
+
+impl N2kHeader {
+    /// Create a broadcast header
+    pub fn new(priority: u8, pgn: u32, source: u8) -> N2kHeader {
+        N2kHeader {
+            priority,
+            pgn,
+            source,
+            destination: BROADCAST,
+        }
+    }
+
+    /// Set the destination, returns self for chaining
+    pub fn with_destination(mut self, destination: u8) -> N2kHeader {
+        self.destination = destination;
+        self
+    }
+
+    /// Decode a raw identifier, the reserved bits must be 0
+    pub fn from_can_id(raw: u32) -> Result<N2kHeader> {
+        let id = CanId::from_raw(raw)?;
+        id.check()?;
+        Ok(N2kHeader::from(id))
+    }
+
+    /// Encode to a raw identifier
+    pub fn to_can_id(&self) -> Result<u32> {
+        self.id()?.to_raw()
+    }
+
+    /// Get the identifier fields. PDU1 PGNs must have a 0 low byte, PDU2 PGNs can only be
+    /// broadcast
+    pub fn id(&self) -> Result<CanId> {
+        if self.pgn > 0x1_FFFF {
+            return Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!("N2kHeader::id: invalid PGN {}", self.pgn),
+            ));
+        }
+        let mut id = CanId {
+            priority: self.priority,
+            edp: false,
+            dp: self.pgn >> 16 & 1 == 1,
+            pf: (self.pgn >> 8) as u8,
+            ps: self.pgn as u8,
+            sa: self.source,
+        };
+        if id.is_pdu1() {
+            if id.ps != 0 {
+                return Err(Error::with_context(
+                    ErrorKind::InvParam,
+                    &format!(
+                        "N2kHeader::id: PDU1 PGN {} has a non zero low byte",
+                        self.pgn
+                    ),
+                ));
+            }
+            id.ps = self.destination;
+        } else if self.destination != BROADCAST {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "N2kHeader::id: PDU2 PGN {} can not be sent to {}",
+                    self.pgn, self.destination
+                ),
+            ));
+        }
+        id.check()?;
+        Ok(id)
+    }
+
+    pub fn is_broadcast(&self) -> bool {
+        self.destination == BROADCAST
+    }
+
+    pub fn is_proprietary(&self) -> bool {
+        is_proprietary(self.pgn)
+    }
+}
+
+impl From<CanId> for N2kHeader {
+    fn from(id: CanId) -> N2kHeader {
+        N2kHeader {
+            priority: id.priority,
+            pgn: id.pgn(),
+            source: id.sa,
+            destination: id.destination(),
+        }
+    }
+}
+
+/// The interface a frame was received on or is sent to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Interface {
+    Index(u32),
+    Name(String),
+}
+
+impl Display for Interface {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Interface::Index(index) => write!(f, "#{}", index),
+            Interface::Name(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// A classic or FD CAN frame with its receive metadata
+///
+/// The payload is stored inline so frames can be created and copied without allocating,
+/// `bitfield` gives a `BitField` over the payload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanFrame {
+    id: u32,
+    extended: bool,
+    fd: bool,
+    rtr: bool,
+    error: bool,
+    len: u8,
+    data: [u8; 64],
+    timestamp: Option<Duration>,
+    interface: Option<Interface>,
+}
+
+impl CanFrame {
+    /// Create a data frame with a 29 bit identifier and up to 8 bytes
+    pub fn extended(id: u32, data: &[u8]) -> Result<CanFrame> {
+        CanFrame::create(id, true, false, data)
+    }
+
+    /// Create a data frame with an 11 bit identifier and up to 8 bytes
+    pub fn standard(id: u32, data: &[u8]) -> Result<CanFrame> {
+        CanFrame::create(id, false, false, data)
+    }
+
+    /// Create a CAN FD frame, data must have one of the FD lengths up to 64 bytes
+    pub fn fd(id: u32, extended: bool, data: &[u8]) -> Result<CanFrame> {
+        CanFrame::create(id, extended, true, data)
+    }
+
+    /// Create a remote frame requesting len bytes
+    pub fn remote(id: u32, extended: bool, len: usize) -> Result<CanFrame> {
+        if len > 8 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("CanFrame::remote: invalid length {}", len),
+            ));
+        }
+        let mut frame = CanFrame::create(id, extended, false, &[])?;
+        frame.rtr = true;
+        frame.len = len as u8;
+        Ok(frame)
+    }
+
+    /// Create an error frame, id holds the error class
+    pub fn error(id: u32, data: &[u8]) -> Result<CanFrame> {
+        let mut frame = CanFrame::create(id, true, false, data)?;
+        frame.error = true;
+        Ok(frame)
+    }
+
+    fn create(id: u32, extended: bool, fd: bool, data: &[u8]) -> Result<CanFrame> {
+        let max_id = if extended {
+            MAX_CAN_ID
+        } else {
+            MAX_STANDARD_ID
+        };
+        if id > max_id {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("CanFrame::create: invalid identifier {:#X}", id),
+            ));
+        }
+        let valid = if fd {
+            FD_LENGTHS.contains(&data.len())
+        } else {
+            data.len() <= 8
+        };
+        if !valid {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("CanFrame::create: invalid payload length {}", data.len()),
+            ));
+        }
+        let mut frame = CanFrame {
+            id,
+            extended,
+            fd,
+            rtr: false,
+            error: false,
+            len: data.len() as u8,
+            data: [0; 64],
+            timestamp: None,
+            interface: None,
+        };
+        frame.data[..data.len()].copy_from_slice(data);
+        Ok(frame)
+    }
+
+    /// Set the receive timestamp, returns self for chaining
+    pub fn with_timestamp(mut self, timestamp: Duration) -> CanFrame {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Set the interface, returns self for chaining
+    pub fn with_interface(mut self, interface: Interface) -> CanFrame {
+        self.interface = Some(interface);
+        self
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn is_extended(&self) -> bool {
+        self.extended
+    }
+
+    pub fn is_fd(&self) -> bool {
+        self.fd
+    }
+
+    pub fn is_rtr(&self) -> bool {
+        self.rtr
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.error
+    }
+
+    /// Get the data length code, the requested length for remote frames
+    pub fn dlc(&self) -> u8 {
+        FD_LENGTHS
+            .iter()
+            .position(|len| *len == self.len as usize)
+            .unwrap_or_default() as u8
+    }
+
+    /// Get the payload, empty for remote frames
+    pub fn data(&self) -> &[u8] {
+        if self.rtr {
+            &[]
+        } else {
+            &self.data[..self.len as usize]
+        }
+    }
+
+    pub fn timestamp(&self) -> Option<Duration> {
+        self.timestamp
+    }
+
+    pub fn interface(&self) -> Option<&Interface> {
+        self.interface.as_ref()
+    }
+
+    /// Get a `BitField` over the payload
+    pub fn bitfield(&self) -> BitField<'_> {
+        BitField::new(self.data())
+    }
+
+    /// Split the identifier of an extended data frame
+    pub fn can_id(&self) -> Result<CanId> {
+        if !self.extended || self.error || self.rtr {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "CanFrame::can_id: {:#X} is not an extended data frame",
+                    self.id
+                ),
+            ));
+        }
+        CanId::from_raw(self.id)
+    }
+
+    /// Get the NMEA 2000 header of an extended data frame
+    pub fn header(&self) -> Result<N2kHeader> {
+        let id = self.can_id()?;
+        id.check()?;
+        Ok(N2kHeader::from(id))
+    }
+}
+
+impl<'a> From<&'a CanFrame> for BitField<'a> {
+    fn from(frame: &'a CanFrame) -> BitField<'a> {
+        frame.bitfield()
+    }
+}
+
+impl Display for CanFrame {
+    /// candump style: interface, identifier and payload
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(interface) = &self.interface {
+            write!(f, "{}  ", interface)?;
+        }
+        if self.extended {
+            write!(f, "{:08X}", self.id)?;
+        } else {
+            write!(f, "{:03X}", self.id)?;
+        }
+        if self.rtr {
+            return write!(f, "#R{}", self.len);
+        }
+        write!(f, "{}", if self.fd { "##" } else { "#" })?;
+        for byte in self.data() {
+            write!(f, "{:02X}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where protocol state machines send their frames
+pub trait FrameSink {
+    fn send(&mut self, frame: CanFrame) -> Result<()>;
+}
+
+impl FrameSink for Vec<CanFrame> {
+    fn send(&mut self, frame: CanFrame) -> Result<()> {
+        self.push(frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_can_id() {
+        // PDU2: wind data 130306 from 35 with priority 2
+        let id = CanId::from_raw(0x09FD_0223).unwrap();
+        assert_eq!(
+            id,
+            CanId {
+                priority: 2,
+                edp: false,
+                dp: true,
+                pf: 0xFD,
+                ps: 0x02,
+                sa: 0x23
+            }
+        );
+        assert!(!id.is_pdu1());
+        assert_eq!(id.pgn(), 130306);
+        assert!(id.is_broadcast());
+        assert!(!id.is_proprietary());
+        assert_eq!(id.to_raw().unwrap(), 0x09FD_0223);
+        assert_eq!(id.to_string(), "09FD0223");
+
+        // PDU1: ISO request 59904 from 0x80 to 0x23
+        let id = CanId::from_raw(0x18EA_2380).unwrap();
+        assert!(id.is_pdu1());
+        assert_eq!(id.pgn(), 59904);
+        assert_eq!(id.destination(), 0x23);
+        assert!(!id.is_broadcast());
+
+        assert_eq!(
+            CanId::from_raw(0x2000_0000).unwrap_err().kind(),
+            ErrorKind::InvParam
+        );
+        let id = CanId::from_raw(0x1BFD_0223).unwrap();
+        assert!(id.edp);
+        assert_eq!(id.pgn(), 0x3FD02);
+        assert_eq!(id.check().unwrap_err().kind(), ErrorKind::InvFormat);
+        assert!(CanId { priority: 8, ..id }.to_raw().is_err());
+
+        assert!(CanId::from_raw(0x1CEF_2380).unwrap().is_proprietary());
+        assert!(CanId::from_raw(0x19FF_1080).unwrap().is_proprietary());
+        assert!(!CanId::from_raw(0x19FE_1080).unwrap().is_proprietary());
+    }
+
+    #[test]
+    fn test_header() {
+        let header = N2kHeader::from_can_id(0x09FD_0223).unwrap();
+        assert_eq!(header, N2kHeader::new(2, 130306, 0x23));
+        assert_eq!(header.to_can_id().unwrap(), 0x09FD_0223);
+
+        let request = N2kHeader::new(6, 59904, 0x80).with_destination(0x23);
+        assert_eq!(request.to_can_id().unwrap(), 0x18EA_2380);
+        assert_eq!(N2kHeader::from_can_id(0x18EA_2380).unwrap(), request);
+        assert!(!request.is_broadcast());
+        // PDU1 to all nodes
+        let claim = N2kHeader::new(6, 60928, 0x23);
+        assert_eq!(claim.to_can_id().unwrap(), 0x18EE_FF23);
+        assert!(claim.is_broadcast());
+
+        assert_eq!(
+            N2kHeader::new(2, 130306, 0x23)
+                .with_destination(0x10)
+                .to_can_id()
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvParam
+        );
+        assert_eq!(
+            N2kHeader::new(6, 59905, 0x80)
+                .to_can_id()
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvParam
+        );
+        assert_eq!(
+            N2kHeader::new(6, 0x2_0000, 0x80)
+                .to_can_id()
+                .unwrap_err()
+                .kind(),
+            ErrorKind::OutOfRange
+        );
+        assert_eq!(
+            N2kHeader::from_can_id(0x1BFD_0223).unwrap_err().kind(),
+            ErrorKind::InvFormat
+        );
+        assert!(N2kHeader::new(7, 130820, 0x23).is_proprietary());
+    }
+
+    #[test]
+    fn test_frame() {
+        let data = [0x01, 0x64, 0x00, 0x10, 0x27, 0xFA, 0xFF, 0xFF];
+        let frame = CanFrame::extended(0x09FD_0223, &data)
+            .unwrap()
+            .with_timestamp(Duration::from_millis(1500))
+            .with_interface(Interface::Name("can0".to_string()));
+        assert_eq!(frame.data(), data);
+        assert_eq!(frame.dlc(), 8);
+        assert_eq!(frame.header().unwrap(), N2kHeader::new(2, 130306, 0x23));
+        assert_eq!(frame.timestamp(), Some(Duration::from_millis(1500)));
+        assert_eq!(frame.bitfield().get_u64_le(8, 23).unwrap(), 100);
+        assert_eq!(BitField::from(&frame).bit_len(), 64);
+        assert_eq!(frame.to_string(), "can0  09FD0223#0164001027FAFFFF");
+
+        let frame = CanFrame::standard(0x123, &[0xAB]).unwrap();
+        assert!(!frame.is_extended());
+        assert_eq!(frame.to_string(), "123#AB");
+        assert!(frame.header().is_err());
+        assert!(CanFrame::standard(0x800, &[]).is_err());
+        assert!(CanFrame::extended(0x2000_0000, &[]).is_err());
+        assert!(CanFrame::extended(0x100, &[0; 9]).is_err());
+
+        let frame = CanFrame::fd(0x100, true, &[0x55; 12]).unwrap();
+        assert!(frame.is_fd());
+        assert_eq!(frame.dlc(), 9);
+        assert_eq!(frame.bitfield().bit_len(), 96);
+        assert!(CanFrame::fd(0x100, true, &[0; 10]).is_err());
+        assert_eq!(CanFrame::fd(0x100, true, &[0; 64]).unwrap().dlc(), 15);
+
+        let frame = CanFrame::remote(0x7FF, false, 4)
+            .unwrap()
+            .with_interface(Interface::Index(2));
+        assert!(frame.is_rtr());
+        assert_eq!(frame.dlc(), 4);
+        assert!(frame.data().is_empty());
+        assert_eq!(frame.to_string(), "#2  7FF#R4");
+        assert!(CanFrame::remote(0x09FD_0223, true, 8)
+            .unwrap()
+            .header()
+            .is_err());
+
+        let frame = CanFrame::error(0x04, &[0, 0x10, 0, 0, 0, 0, 0, 0]).unwrap();
+        assert!(frame.is_error());
+        assert!(frame.can_id().is_err());
+    }
+}
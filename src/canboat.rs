@@ -0,0 +1,708 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::schema::{
+    FieldDescriptor, FieldType, IndirectLookup, Lookup, MessageSchema, RepeatingGroup, Transport,
+};
+use crate::Endianness;
+use serde_json::{Map, Value as Json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+// Loader for the canboat PGN database (https://github.com/canboat/canboat), pgns.json or pgns.xml.
+// canboat counts bits from the least significant bit of each byte, all fields are created
+// with Endianness::Intel and canboat bit offsets. Repeating field sets become repeating
+// groups named set1 and set2.
+
+/// The PGN definitions and lookup tables of a canboat database
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PgnDatabase {
+    pub version: Option<String>,
+    /// value to name tables by name
+    pub lookups: BTreeMap<String, Lookup>,
+    /// bit number to name tables by name
+    pub bit_lookups: BTreeMap<String, Lookup>,
+    /// two key tables by name, their key is set per field
+    pub indirect_lookups: BTreeMap<String, IndirectLookup>,
+    /// message schemas in file order, a PGN can occur more than once for proprietary PGNs
+    pub messages: Vec<MessageSchema>,
+}
+
+impl PgnDatabase {
+    /// Load a database file, files ending in .xml are read as pgns.xml, all others as pgns.json
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<PgnDatabase> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|why| {
+            Error::with_all(
+                ErrorKind::FileNotFound,
+                &format!("PgnDatabase::load: failed to read {}", path.display()),
+                Box::new(why),
+            )
+        })?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("xml") => PgnDatabase::from_xml(&text),
+            _ => PgnDatabase::from_json(&text),
+        }
+    }
+
+    /// Parse the contents of a pgns.json file
+    pub fn from_json(text: &str) -> Result<PgnDatabase> {
+        let root: Json = serde_json::from_str(text).map_err(|why| {
+            Error::with_all(
+                ErrorKind::InvFormat,
+                "PgnDatabase::from_json: failed to parse JSON",
+                Box::new(why),
+            )
+        })?;
+        PgnDatabase::from_tree(&root)
+    }
+
+    /// Parse the contents of a pgns.xml file
+    pub fn from_xml(text: &str) -> Result<PgnDatabase> {
+        let document = roxmltree::Document::parse(text).map_err(|why| {
+            Error::with_all(
+                ErrorKind::InvFormat,
+                "PgnDatabase::from_xml: failed to parse XML",
+                Box::new(why),
+            )
+        })?;
+        PgnDatabase::from_tree(&xml_to_json(document.root_element()))
+    }
+
+    /// Get the first definition of pgn
+    pub fn get(&self, pgn: u32) -> Option<&MessageSchema> {
+        self.messages.iter().find(|message| message.id == pgn)
+    }
+
+    /// Get all definitions of pgn
+    pub fn get_all(&self, pgn: u32) -> impl Iterator<Item = &MessageSchema> {
+        self.messages
+            .iter()
+            .filter(move |message| message.id == pgn)
+    }
+
+    /// Get a message by its canboat id, e.g. "engineParametersRapidUpdate"
+    pub fn get_by_name(&self, name: &str) -> Option<&MessageSchema> {
+        self.messages.iter().find(|message| message.name == name)
+    }
+
+    fn from_tree(root: &Json) -> Result<PgnDatabase> {
+        let mut database = PgnDatabase {
+            version: get_str(root, "Version").map(|version| version.to_owned()),
+            ..PgnDatabase::default()
+        };
+        for entry in get_array(root, "LookupEnumerations") {
+            let lookup = parse_lookup(entry, "EnumValues", "Value")?;
+            database.lookups.insert(lookup.name.clone(), lookup);
+        }
+        for entry in get_array(root, "LookupBitEnumerations") {
+            let lookup = parse_lookup(entry, "EnumBitValues", "Bit")?;
+            database.bit_lookups.insert(lookup.name.clone(), lookup);
+        }
+        for entry in get_array(root, "LookupIndirectEnumerations") {
+            let lookup = parse_indirect_lookup(entry)?;
+            database
+                .indirect_lookups
+                .insert(lookup.name.clone(), lookup);
+        }
+        for entry in get_array(root, "PGNs") {
+            let message = database.parse_message(entry)?;
+            database.messages.push(message);
+        }
+        Ok(database)
+    }
+
+    fn parse_message(&self, entry: &Json) -> Result<MessageSchema> {
+        let id = get_u64(entry, "PGN").ok_or_else(|| {
+            Error::with_context(
+                ErrorKind::InvFormat,
+                "PgnDatabase::parse_message: PGN without number",
+            )
+        })? as u32;
+        let name = get_str(entry, "Id")
+            .or_else(|| get_str(entry, "Description"))
+            .unwrap_or_default();
+        let length = get_u64(entry, "Length").map(|length| length as usize);
+        let transport = match get_str(entry, "Type") {
+            Some("Fast") => Transport::FastPacket,
+            // ISO messages longer than a frame use the transport protocol
+            Some("ISO") if length.map_or(true, |length| length > 8) => Transport::TransportProtocol,
+            _ => Transport::Single,
+        };
+        let mut message = MessageSchema {
+            description: get_str(entry, "Description").map(|text| text.to_owned()),
+            length,
+            transport,
+            ..MessageSchema::new(id, name)
+        };
+
+        // fields following a variable length field have no offset, they follow on
+        let mut next = 0;
+        let mut variable = false;
+        for field in get_array(entry, "Fields") {
+            let field = match self.parse_field(field, next, variable)? {
+                Some(field) => field,
+                // the remaining fields depend on data that can not be described here
+                None => break,
+            };
+            next = field.end + 1;
+            variable |= field.is_variable();
+            message.fields.push(field);
+        }
+        // indirect lookups and repeating field sets refer to 1 based field orders, the key
+        // of an indirect lookup may follow its field
+        let keys: Vec<Option<String>> = get_array(entry, "Fields")
+            .iter()
+            .map(|field| {
+                let order = get_u64(field, "LookupIndirectEnumerationFieldOrder")?;
+                let key = message.fields.get((order as usize).checked_sub(1)?)?;
+                Some(key.name.clone())
+            })
+            .collect();
+        for (field, key) in message.fields.iter_mut().zip(keys) {
+            match (key, &mut field.indirect_lookup) {
+                (Some(key), Some(lookup)) => lookup.key = key,
+                _ => field.indirect_lookup = None,
+            }
+        }
+        for set in 1..=2 {
+            let key = format!("RepeatingFieldSet{}", set);
+            let (size, first) = match (
+                get_u64(entry, &format!("{}Size", key)),
+                get_u64(entry, &format!("{}StartField", key)),
+            ) {
+                (Some(size), Some(first)) if size > 0 && first > 0 => {
+                    (size as usize, first as usize - 1)
+                }
+                _ => continue,
+            };
+            if first + size > message.fields.len() {
+                // the fields of the set were not parsed
+                continue;
+            }
+            let count_field = get_u64(entry, &format!("{}CountField", key))
+                .and_then(|order| message.fields.get((order as usize).checked_sub(1)?))
+                .map(|field| field.name.clone());
+            message.groups.push(RepeatingGroup {
+                count_field,
+                ..RepeatingGroup::new(&format!("set{}", set), first, size)
+            });
+        }
+        if variable || !message.groups.is_empty() {
+            message.length = None;
+        }
+        Ok(message)
+    }
+
+    fn parse_field(
+        &self,
+        entry: &Json,
+        next: usize,
+        variable: bool,
+    ) -> Result<Option<FieldDescriptor>> {
+        let name = get_str(entry, "Id")
+            .or_else(|| get_str(entry, "Name"))
+            .unwrap_or_default();
+        let kind = get_str(entry, "FieldType").unwrap_or("NUMBER");
+        let signed = get_bool(entry, "Signed").unwrap_or(false);
+        let (field_type, size) = match kind {
+            "NUMBER" | "DURATION" | "MMSI" | "PGN" | "ISO_NAME" | "FIELD_INDEX" => (
+                if signed {
+                    FieldType::Signed
+                } else {
+                    FieldType::Unsigned
+                },
+                None,
+            ),
+            "FLOAT" => (FieldType::Float, None),
+            "LOOKUP" | "INDIRECT_LOOKUP" | "FIELDTYPE_LOOKUP" => (FieldType::Lookup, None),
+            "BITLOOKUP" => (FieldType::BitLookup, None),
+            "DATE" => (FieldType::Date, None),
+            "TIME" => (FieldType::Time, None),
+            "STRING_FIX" => (FieldType::String, None),
+            "STRING_LZ" => (FieldType::StringLz, Some(8)),
+            "STRING_LAU" => (FieldType::StringLau, Some(16)),
+            "RESERVED" | "SPARE" => (FieldType::Reserved, None),
+            _ => (FieldType::Binary, None),
+        };
+        let size = match (size, get_u64(entry, "BitLength")) {
+            (Some(size), _) => size,
+            (None, Some(size)) if size > 0 => size as usize,
+            _ => return Ok(None),
+        };
+        let start = match get_u64(entry, "BitOffset") {
+            Some(offset) if !variable => offset as usize,
+            _ => next,
+        };
+        let resolution = get_f64(entry, "Resolution").unwrap_or(1.0);
+        let lookup = match kind {
+            "LOOKUP" => self.find_lookup(&self.lookups, entry, "LookupEnumeration", name)?,
+            "BITLOOKUP" => {
+                self.find_lookup(&self.bit_lookups, entry, "LookupBitEnumeration", name)?
+            }
+            _ => None,
+        };
+        let indirect_lookup = match (kind, get_str(entry, "LookupIndirectEnumeration")) {
+            ("INDIRECT_LOOKUP", Some(lookup)) => match self.indirect_lookups.get(lookup) {
+                Some(lookup) => Some(lookup.clone()),
+                None => {
+                    return Err(Error::with_context(
+                        ErrorKind::NotFound,
+                        &format!(
+                            "PgnDatabase::parse_field: unknown lookup {} for field {}",
+                            lookup, name
+                        ),
+                    ))
+                }
+            },
+            _ => None,
+        };
+
+        let field = FieldDescriptor {
+            endianness: Endianness::Intel,
+            resolution,
+            // canboat offsets are added to the raw value
+            offset: get_f64(entry, "Offset").unwrap_or(0.0) * resolution,
+            unit: get_str(entry, "Unit").map(|unit| unit.to_owned()),
+            lookup,
+            indirect_lookup,
+            ..FieldDescriptor::new(name, start, start + size - 1, field_type)
+        };
+        field.check()?;
+        Ok(Some(field))
+    }
+
+    fn find_lookup(
+        &self,
+        lookups: &BTreeMap<String, Lookup>,
+        entry: &Json,
+        key: &str,
+        field: &str,
+    ) -> Result<Option<Lookup>> {
+        match get_str(entry, key) {
+            Some(name) => match lookups.get(name) {
+                Some(lookup) => Ok(Some(lookup.clone())),
+                None => Err(Error::with_context(
+                    ErrorKind::NotFound,
+                    &format!(
+                        "PgnDatabase::find_lookup: unknown lookup {} for field {}",
+                        name, field
+                    ),
+                )),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+fn parse_lookup(entry: &Json, values: &str, value: &str) -> Result<Lookup> {
+    let mut lookup = Lookup::new(get_str(entry, "Name").unwrap_or_default());
+    for pair in get_array(entry, values) {
+        match (get_u64(pair, value), get_str(pair, "Name")) {
+            (Some(value), Some(name)) => {
+                lookup.values.insert(value, name.to_owned());
+            }
+            _ => {
+                return Err(Error::with_context(
+                    ErrorKind::InvFormat,
+                    &format!("parse_lookup: invalid entry in lookup {}", lookup.name),
+                ))
+            }
+        }
+    }
+    Ok(lookup)
+}
+
+// Value1 is the value of the key field, Value2 the value of the field itself
+fn parse_indirect_lookup(entry: &Json) -> Result<IndirectLookup> {
+    let mut lookup = IndirectLookup::new(get_str(entry, "Name").unwrap_or_default(), "");
+    for triple in get_array(entry, "EnumValues") {
+        match (
+            get_u64(triple, "Value1"),
+            get_u64(triple, "Value2"),
+            get_str(triple, "Name"),
+        ) {
+            (Some(key), Some(value), Some(name)) => {
+                lookup.values.insert((key, value), name.to_owned());
+            }
+            _ => {
+                return Err(Error::with_context(
+                    ErrorKind::InvFormat,
+                    &format!(
+                        "parse_indirect_lookup: invalid entry in lookup {}",
+                        lookup.name
+                    ),
+                ))
+            }
+        }
+    }
+    Ok(lookup)
+}
+
+// pgns.xml holds the same tree as pgns.json with all values as text, convert it to JSON
+// so both formats share one parser
+fn xml_to_json(node: roxmltree::Node) -> Json {
+    let mut object = Map::new();
+    for attribute in node.attributes() {
+        object.insert(
+            attribute.name().to_owned(),
+            Json::String(attribute.value().to_owned()),
+        );
+    }
+    for child in node.children().filter(|child| child.is_element()) {
+        let name = child.tag_name().name();
+        let value = match name {
+            "LookupEnumerations"
+            | "LookupBitEnumerations"
+            | "LookupIndirectEnumerations"
+            | "PGNs"
+            | "Fields" => Json::Array(
+                child
+                    .children()
+                    .filter(|item| item.is_element())
+                    .map(xml_to_json)
+                    .collect(),
+            ),
+            _ if child.children().any(|item| item.is_element()) => xml_to_json(child),
+            _ => Json::String(child.text().unwrap_or_default().trim().to_owned()),
+        };
+        object.insert(name.to_owned(), value);
+    }
+    // lookup entries are child elements of the enumeration
+    match node.tag_name().name() {
+        "LookupEnumeration" => {
+            object.insert("EnumValues".to_owned(), child_entries(node, "EnumPair"));
+        }
+        "LookupBitEnumeration" => {
+            object.insert("EnumBitValues".to_owned(), child_entries(node, "BitPair"));
+        }
+        "LookupIndirectEnumeration" => {
+            object.insert("EnumValues".to_owned(), child_entries(node, "EnumTriplet"));
+        }
+        _ => (),
+    }
+    Json::Object(object)
+}
+
+fn child_entries(node: roxmltree::Node, tag: &str) -> Json {
+    Json::Array(
+        node.children()
+            .filter(|child| child.has_tag_name(tag))
+            .map(xml_to_json)
+            .collect(),
+    )
+}
+
+fn get_array<'a>(entry: &'a Json, key: &str) -> &'a [Json] {
+    match entry.get(key) {
+        Some(Json::Array(items)) => items,
+        _ => &[],
+    }
+}
+
+fn get_str<'a>(entry: &'a Json, key: &str) -> Option<&'a str> {
+    entry.get(key).and_then(|value| value.as_str())
+}
+
+// numbers are given as text in pgns.xml
+fn get_u64(entry: &Json, key: &str) -> Option<u64> {
+    match entry.get(key)? {
+        Json::Number(number) => number.as_u64(),
+        Json::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
+
+fn get_f64(entry: &Json, key: &str) -> Option<f64> {
+    match entry.get(key)? {
+        Json::Number(number) => number.as_f64(),
+        Json::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
+
+fn get_bool(entry: &Json, key: &str) -> Option<bool> {
+    match entry.get(key)? {
+        Json::Bool(value) => Some(*value),
+        Json::String(text) => text.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dynamic::{decode_dynamic, encode_dynamic_to_vec, Value};
+    use crate::BitField;
+
+    const SAMPLE_JSON: &str = include_str!("../data/canboat/pgns-sample.json");
+    const SAMPLE_XML: &str = include_str!("../data/canboat/pgns-sample.xml");
+
+    fn decode(database: &PgnDatabase, pgn: u32, data: &[u8]) -> crate::DecodedMessage {
+        decode_dynamic(database.get(pgn).unwrap(), &BitField::new(data)).unwrap()
+    }
+
+    #[test]
+    fn test_load() {
+        let database = PgnDatabase::from_json(SAMPLE_JSON).unwrap();
+        assert_eq!(database.version.as_deref(), Some("5.0.0"));
+        assert_eq!(database.messages.len(), 10);
+        assert_eq!(
+            database.lookups["MANUFACTURER_CODE"].name_of(137),
+            Some("Maretron")
+        );
+        assert_eq!(
+            database.bit_lookups["ENGINE_STATUS_1"].name_of(2),
+            Some("Low Oil Pressure")
+        );
+        assert_eq!(
+            database.indirect_lookups["DEVICE_FUNCTION"].name_of(60, 140),
+            Some("Ownship Attitude")
+        );
+        let claim = database.get(60928).unwrap();
+        assert_eq!(
+            claim
+                .get_field("deviceFunction")
+                .unwrap()
+                .indirect_lookup
+                .as_ref()
+                .unwrap()
+                .key,
+            "deviceClass"
+        );
+
+        let rapid = database.get(127488).unwrap();
+        assert_eq!(rapid.name, "engineParametersRapidUpdate");
+        assert_eq!(
+            rapid.description.as_deref(),
+            Some("Engine Parameters, Rapid Update")
+        );
+        assert_eq!(rapid.length, Some(8));
+        assert_eq!(rapid.transport, Transport::Single);
+        let speed = rapid.get_field("speed").unwrap();
+        assert_eq!((speed.start, speed.end), (8, 23));
+        assert_eq!(speed.endianness, Endianness::Intel);
+        assert_eq!(speed.unit.as_deref(), Some("rpm"));
+        assert_eq!(
+            rapid.get_field("tiltTrim").unwrap().field_type,
+            FieldType::Signed
+        );
+
+        let config = database.get_by_name("configurationInformation").unwrap();
+        assert_eq!(config.length, None);
+        assert_eq!(config.transport, Transport::FastPacket);
+        assert_eq!(config.fields[2].start, 32);
+
+        for message in &database.messages {
+            assert_eq!(crate::validate(message), vec![], "{}", message.name);
+        }
+
+        // both formats describe the same database
+        assert_eq!(PgnDatabase::from_xml(SAMPLE_XML).unwrap(), database);
+        assert_eq!(
+            PgnDatabase::from_json("{\"PGNs\": [{\"Id\": \"x\"}]}")
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvFormat
+        );
+        assert!(PgnDatabase::from_json("{").is_err());
+        assert!(PgnDatabase::from_xml("<PGNDefinitions>").is_err());
+    }
+
+    #[test]
+    fn test_load_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("data/canboat");
+        let json = PgnDatabase::load(path.join("pgns-sample.json")).unwrap();
+        let xml = PgnDatabase::load(path.join("pgns-sample.xml")).unwrap();
+        assert_eq!(json, xml);
+        assert_eq!(
+            PgnDatabase::load(path.join("missing.json"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::FileNotFound
+        );
+    }
+
+    #[test]
+    fn test_decode_numbers() {
+        let database = PgnDatabase::from_json(SAMPLE_JSON).unwrap();
+        let message = decode(
+            &database,
+            127488,
+            &[0x00, 0x40, 0x1F, 0xFF, 0xFF, 0xFB, 0xFF, 0xFF],
+        );
+        assert_eq!(
+            message.get("instance"),
+            Some(&Value::Lookup(
+                0,
+                Some("Single Engine or Dual Engine Port".to_owned())
+            ))
+        );
+        assert_eq!(message.get("speed"), Some(&Value::Float(2000.0)));
+        assert_eq!(message.get("boostPressure"), Some(&Value::NotAvailable));
+        assert_eq!(message.get("tiltTrim"), Some(&Value::Signed(-5)));
+
+        let message = decode(
+            &database,
+            129025,
+            &[0x40, 0xDD, 0x4A, 0x1F, 0x80, 0xAD, 0xFC, 0x07],
+        );
+        assert!((message.get("latitude").unwrap().as_f64().unwrap() - 52.5).abs() < 1e-9);
+        assert!((message.get("longitude").unwrap().as_f64().unwrap() - 13.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_decode_date_time() {
+        let database = PgnDatabase::from_json(SAMPLE_JSON).unwrap();
+        let data = [0x01, 0xF0, 0x38, 0x4A, 0x00, 0xCC, 0xBF, 0x19];
+        let message = decode(&database, 126992, &data);
+        assert_eq!(
+            message.get("source"),
+            Some(&Value::Lookup(0, Some("GPS".to_owned())))
+        );
+        assert_eq!(message.get("date"), Some(&Value::Date(19000)));
+        assert_eq!(message.get("date").unwrap().to_string(), "2022-01-08");
+        assert_eq!(message.get("time").unwrap().to_string(), "12:00:00");
+        let schema = database.get(126992).unwrap();
+        assert_eq!(encode_dynamic_to_vec(schema, &message).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_sub_byte_fields() {
+        let database = PgnDatabase::from_xml(SAMPLE_XML).unwrap();
+        let data = [0x45, 0x23, 0x21, 0x11, 0x11, 0x82, 0x33, 0xC0];
+        let message = decode(&database, 60928, &data);
+        assert_eq!(
+            message.get("uniqueNumber"),
+            Some(&Value::Bytes(vec![0x45, 0x23, 0x01]))
+        );
+        assert_eq!(
+            message.get("manufacturerCode"),
+            Some(&Value::Lookup(137, Some("Maretron".to_owned())))
+        );
+        assert_eq!(
+            message.get("deviceInstanceLower"),
+            Some(&Value::Unsigned(1))
+        );
+        assert_eq!(
+            message.get("deviceInstanceUpper"),
+            Some(&Value::Unsigned(2))
+        );
+        // named by the device class following it
+        assert_eq!(
+            message.get("deviceFunction"),
+            Some(&Value::Lookup(130, Some("PC Gateway".to_owned())))
+        );
+        assert_eq!(
+            message.get("deviceClass"),
+            Some(&Value::Lookup(
+                25,
+                Some("Inter/Intranetwork Device".to_owned())
+            ))
+        );
+        assert_eq!(
+            message.get("industryGroup").unwrap().as_str(),
+            Some("Marine")
+        );
+        assert_eq!(
+            message.get("arbitraryAddressCapable").unwrap().as_str(),
+            Some("Yes")
+        );
+        let schema = database.get(60928).unwrap();
+        assert_eq!(encode_dynamic_to_vec(schema, &message).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_bit_lookup() {
+        let database = PgnDatabase::from_json(SAMPLE_JSON).unwrap();
+        let mut data = vec![0xFF; 26];
+        data[0] = 0x01;
+        data[20] = 0x05;
+        data[21] = 0x00;
+        data[22] = 0x80;
+        data[23] = 0x00;
+        let message = decode(&database, 127489, &data);
+        assert_eq!(
+            message.get("discreteStatus1"),
+            Some(&Value::BitLookup(
+                5,
+                vec!["Check Engine".to_owned(), "Low Oil Pressure".to_owned()]
+            ))
+        );
+        assert_eq!(
+            message.get("discreteStatus2").unwrap().to_string(),
+            "Engine Shutting Down"
+        );
+        assert_eq!(message.get("oilPressure"), Some(&Value::NotAvailable));
+    }
+
+    #[test]
+    fn test_decode_strings() {
+        let database = PgnDatabase::from_json(SAMPLE_JSON).unwrap();
+        let data = [
+            0x07, 0x01, b'H', b'e', b'l', b'l', b'o', 0x02, 0x01, 0x05, 0x01, b'A', b'B', b'C',
+        ];
+        let message = decode(&database, 126998, &data);
+        assert_eq!(
+            message.get("installationDescription1").unwrap().as_str(),
+            Some("Hello")
+        );
+        assert_eq!(
+            message.get("installationDescription2").unwrap().as_str(),
+            Some("")
+        );
+        assert_eq!(
+            message.get("manufacturerInformation").unwrap().as_str(),
+            Some("ABC")
+        );
+        let schema = database.get(126998).unwrap();
+        assert_eq!(encode_dynamic_to_vec(schema, &message).unwrap(), data);
+
+        let mut data = vec![0xFF; 134];
+        data[0..4].copy_from_slice(&[0x64, 0x08, 0x39, 0x30]);
+        data[4..9].copy_from_slice(b"DST80");
+        let message = decode(&database, 126996, &data);
+        assert_eq!(message.get("nmea2000Version"), Some(&Value::Float(2.148)));
+        assert_eq!(message.get("productCode"), Some(&Value::Unsigned(12345)));
+        assert_eq!(message.get("modelId").unwrap().as_str(), Some("DST80"));
+    }
+
+    #[test]
+    fn test_repeating_fields() {
+        let database = PgnDatabase::from_json(SAMPLE_JSON).unwrap();
+        let schema = database.get(129540).unwrap();
+        assert_eq!(schema.length, None);
+        assert_eq!(
+            schema.groups,
+            vec![RepeatingGroup {
+                count_field: Some("satsInView".to_owned()),
+                ..RepeatingGroup::new("set1", 4, 7)
+            }]
+        );
+        let mut data = vec![0x01, 0xFC, 0x02];
+        data.extend_from_slice(&[0x05, 0x10, 0x27, 0x20, 0x4E, 0x88, 0x13]);
+        data.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0x7F, 0xF2]);
+        data.extend_from_slice(&[0x0C, 0xFF, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF]);
+        data.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0x7F, 0xF0]);
+        let message = decode(&database, 129540, &data);
+        let rows = message.get("set1").unwrap().as_group().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0].value, Value::Unsigned(5));
+        assert_eq!(rows[0][1].value, Value::Float(1.0));
+        assert_eq!(rows[0][2].value, Value::Float(2.0));
+        assert_eq!(rows[0][3].value, Value::Float(50.0));
+        assert_eq!(rows[0][5].value.as_str(), Some("Used"));
+        assert_eq!(rows[1][0].value, Value::Unsigned(12));
+        assert_eq!(rows[1][1].value, Value::NotAvailable);
+        assert_eq!(encode_dynamic_to_vec(schema, &message).unwrap(), data);
+
+        // no count field, the PGNs fill the payload
+        let schema = database.get(126464).unwrap();
+        assert_eq!(schema.groups[0].count_field, None);
+        let data = [0x00, 0x00, 0xEE, 0x00, 0x10, 0xF0, 0x01];
+        let message = decode(&database, 126464, &data);
+        let rows = message.get("set1").unwrap().as_group().unwrap();
+        assert_eq!(rows[0][0].value, Value::Unsigned(60928));
+        assert_eq!(rows[1][0].value, Value::Unsigned(126992));
+        assert_eq!(encode_dynamic_to_vec(schema, &message).unwrap(), data);
+    }
+}
@@ -0,0 +1,70 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+// Protocol state machines read the time from a `Clock` so they can be driven by the system
+// time or stepped deterministically with a `ManualClock`.
+
+/// A monotonic time source
+pub trait Clock {
+    /// Get the time since an arbitrary fixed point
+    fn now(&self) -> Duration;
+}
+
+/// The system's monotonic clock, counting from its creation
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when told to, clones share the time
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    now: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> ManualClock {
+        ManualClock::default()
+    }
+
+    pub fn set(&self, now: Duration) {
+        self.now.set(now);
+    }
+
+    pub fn advance(&self, step: Duration) {
+        self.now.set(self.now.get() + step);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> Duration {
+        (**self).now()
+    }
+}
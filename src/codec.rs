@@ -0,0 +1,244 @@
+//! Traits for structs decoded from and encoded to bitfields.
+//!
+//! With the `derive` feature the traits can be derived, each field is annotated with
+//! `#[bits(...)]`:
+//!
+//! * `start = N`, `end = N` or `size = N`: position of the field, end is inclusive
+//! * `le` (default), `be` or `intel`: byte order, `intel` counts bits from the least
+//!   significant bit of each byte as NMEA 2000 does, see `Endianness::Intel`
+//! * `signed` / `unsigned`: raw value encoding, defaults to the signedness of integer fields
+//! * `resolution = expr`, `offset = expr`: scaling for `f32` / `f64` fields
+//! * `lookup`: the field type implements [`BitLookup`]. Deriving `BitLookup` on an enum maps
+//!   unit variants to their discriminant or `#[bits(value = N)]` and names them after the
+//!   variant or `#[bits(name = "...")]`, an `#[bits(unknown)] Unknown(u64)` variant keeps
+//!   unknown values
+//! * `repeat`, `count = field`: a `Vec<T>` of rows packed from a byte aligned `start` behind
+//!   all other fields, `T` implements [`BitDecode`] and [`BitEncode`] and each row takes
+//!   `T::BIT_LEN` rounded up to whole bytes. The number of rows is read from the integer
+//!   `count` field and written to it on encode, without `count` the rows fill the payload
+//! * `select = field`: the rest of the payload from a byte aligned `start` as a type
+//!   implementing [`BitVariant`], chosen by the value of the integer `field`. The field is
+//!   written from the variant on encode
+//!
+//! Fields of type `Option<T>` map the "not available" value to `None`.
+//! `#[bits(len = N)]` on the struct sets the payload length in bytes.
+//!
+// the examples need the derive macros and are ignored without the derive feature
+#![cfg_attr(feature = "derive", doc = "```")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use bitfield::{BitDecode, BitEncode, BitField, BitLookup};
+//!
+//! #[derive(Debug, PartialEq, BitLookup)]
+//! enum Tilt {
+//!     Up = 0,
+//!     Down = 1,
+//! }
+//!
+//! #[derive(Debug, PartialEq, BitDecode, BitEncode)]
+//! #[bits(len = 8)]
+//! struct EngineRapid {
+//!     #[bits(start = 0, end = 7)]
+//!     instance: u8,
+//!     #[bits(start = 8, end = 23, resolution = 0.25)]
+//!     speed: Option<f64>,
+//!     #[bits(start = 24, size = 16, signed)]
+//!     boost: Option<f32>,
+//!     #[bits(start = 46, end = 47, lookup)]
+//!     tilt: Option<Tilt>,
+//! }
+//!
+//! let bitfield = BitField::new(&[0x00, 0x10, 0x27, 0xFF, 0x7F, 0xFD, 0xFF, 0xFF]);
+//! let engine = EngineRapid::decode(&bitfield).unwrap();
+//! assert_eq!(engine.speed, Some(2500.0));
+//! assert_eq!(engine.boost, None);
+//! assert_eq!(engine.tilt, Some(Tilt::Down));
+//! assert_eq!(engine.to_vec().unwrap(), bitfield.as_bytes());
+//! ```
+//!
+//! Deriving `BitView` as well generates `EngineRapidView<'a>` wrapping a `BitField<'a>` and
+//! `EngineRapidViewMut<'a>` wrapping a `MutableBitField<'a>`, which decode (and set) single
+//! fields on demand:
+//!
+#![cfg_attr(feature = "derive", doc = "```")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use bitfield::BitView;
+//!
+//! #[derive(BitView)]
+//! pub struct EngineRapid {
+//!     #[bits(start = 0, end = 7)]
+//!     instance: u8,
+//!     #[bits(start = 8, end = 23, resolution = 0.25)]
+//!     speed: Option<f64>,
+//! }
+//!
+//! let mut data = [0x00, 0x10, 0x27];
+//! assert_eq!(EngineRapidView::from_bytes(&data).speed().unwrap(), Some(2500.0));
+//! let mut view = EngineRapidViewMut::from_bytes(&mut data);
+//! view.set_speed(None).unwrap();
+//! assert_eq!(data, [0x00, 0xFF, 0xFF]);
+//! ```
+//!
+//! Repeating fields, the satellite count is taken from the rows on encode:
+//!
+#![cfg_attr(feature = "derive", doc = "```")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use bitfield::{BitDecode, BitEncode};
+//!
+//! #[derive(Debug, PartialEq, BitDecode, BitEncode)]
+//! struct Satellite {
+//!     #[bits(start = 0, end = 7, intel)]
+//!     prn: u8,
+//!     #[bits(start = 8, end = 23, intel, resolution = 0.01)]
+//!     snr: Option<f64>,
+//! }
+//!
+//! #[derive(Debug, PartialEq, BitDecode, BitEncode)]
+//! struct SatsInView {
+//!     #[bits(start = 0, end = 7, intel)]
+//!     count: u8,
+//!     #[bits(start = 8, repeat, count = count)]
+//!     satellites: Vec<Satellite>,
+//! }
+//!
+//! let sats = SatsInView::from_bytes(&[0x01, 0x05, 0x88, 0x13]).unwrap();
+//! assert_eq!(sats.satellites, vec![Satellite { prn: 5, snr: Some(50.0) }]);
+//! let sats = SatsInView { count: 0, ..sats };
+//! assert_eq!(sats.to_vec().unwrap(), [0x01, 0x05, 0x88, 0x13]);
+//! ```
+//!
+//! Variant layouts derive `BitVariant` on an enum, unknown discriminators are kept with their
+//! raw bytes when the enum has an `unknown` variant:
+//!
+#![cfg_attr(feature = "derive", doc = "```")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use bitfield::{BitDecode, BitEncode, BitVariant};
+//!
+//! #[derive(Debug, PartialEq, BitDecode, BitEncode)]
+//! struct Level {
+//!     #[bits(start = 0, end = 15, intel)]
+//!     level: u16,
+//! }
+//!
+//! #[derive(Debug, PartialEq, BitVariant)]
+//! enum Command {
+//!     #[bits(value = 1)]
+//!     Reset,
+//!     #[bits(value = 2)]
+//!     SetLevel(Level),
+//!     #[bits(unknown)]
+//!     Unknown(u64, Vec<u8>),
+//! }
+//!
+//! #[derive(Debug, PartialEq, BitDecode, BitEncode)]
+//! struct Request {
+//!     #[bits(start = 0, end = 7, intel)]
+//!     code: u8,
+//!     #[bits(start = 8, select = code)]
+//!     command: Command,
+//! }
+//!
+//! let request = Request::from_bytes(&[0x02, 0x10, 0x27]).unwrap();
+//! assert_eq!(request.command, Command::SetLevel(Level { level: 10000 }));
+//! let request = Request::from_bytes(&[0x07, 0xAA]).unwrap();
+//! assert_eq!(request.command, Command::Unknown(7, vec![0xAA]));
+//! let request = Request { code: 0, command: Command::Reset };
+//! assert_eq!(request.to_vec().unwrap(), [0x01]);
+//! ```
+//!
+//! Overlapping fields are rejected at compile time:
+//!
+#![cfg_attr(feature = "derive", doc = "```compile_fail")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use bitfield::BitDecode;
+//!
+//! #[derive(BitDecode)]
+//! struct Overlap {
+//!     #[bits(start = 0, end = 7)]
+//!     a: u8,
+//!     #[bits(start = 7, end = 14)]
+//!     b: u8,
+//! }
+//! ```
+//!
+//! as are fields past the payload length or wider than their type:
+//!
+#![cfg_attr(feature = "derive", doc = "```compile_fail")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use bitfield::BitDecode;
+//!
+//! #[derive(BitDecode)]
+//! #[bits(len = 2)]
+//! struct TooLong {
+//!     #[bits(start = 8, end = 23)]
+//!     a: u16,
+//! }
+//! ```
+//!
+#![cfg_attr(feature = "derive", doc = "```compile_fail")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use bitfield::BitDecode;
+//!
+//! #[derive(BitDecode)]
+//! struct TooWide {
+//!     #[bits(start = 0, end = 8)]
+//!     a: u8,
+//! }
+//! ```
+
+use crate::error::Result;
+use crate::{BitField, MutableBitField};
+
+/// A type decoded from a bitfield
+pub trait BitDecode: Sized {
+    fn decode(bitfield: &BitField) -> Result<Self>;
+
+    /// Decode from a byte slice
+    fn from_bytes(data: &[u8]) -> Result<Self> {
+        Self::decode(&BitField::new(data))
+    }
+}
+
+/// A type encoded into a bitfield
+pub trait BitEncode {
+    /// Size of the encoded payload in bits
+    const BIT_LEN: usize;
+
+    fn encode(&self, bitfield: &mut MutableBitField) -> Result<()>;
+
+    /// Encode into a new payload, bits not covered by fields are set to 1 as NMEA 2000
+    /// requires for reserved bits
+    fn to_vec(&self) -> Result<Vec<u8>> {
+        let mut data = vec![0xFF; Self::BIT_LEN.div_ceil(8)];
+        self.encode(&mut MutableBitField::new(&mut data))?;
+        Ok(data)
+    }
+}
+
+/// A type whose layout is selected by a discriminator value, decoded from the rest of a
+/// payload
+pub trait BitVariant: Sized {
+    /// Decode the variant selected by discriminator
+    fn decode_variant(discriminator: u64, bitfield: &BitField) -> Result<Self>;
+
+    /// Get the discriminator value selecting this variant
+    fn discriminator(&self) -> u64;
+
+    /// Encode the variant into its payload bytes
+    fn variant_bytes(&self) -> Result<Vec<u8>>;
+}
+
+/// A type mapped to and from raw field values
+pub trait BitLookup: Sized {
+    fn from_raw(raw: u64) -> Result<Self>;
+    fn to_raw(&self) -> u64;
+
+    /// Get the name of the value, None for unknown values
+    fn name(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Get the value with the given name
+    fn from_name(_name: &str) -> Option<Self> {
+        None
+    }
+}
@@ -0,0 +1,1161 @@
+use crate::canboat::PgnDatabase;
+use crate::error::{Error, ErrorKind, Result};
+use crate::schema::{FieldDescriptor, FieldType, Lookup, MessageSchema, RepeatingGroup};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+// Rust source generator for a PGN database. Every message becomes a struct implementing
+// BitDecode and BitEncode, every lookup table used by a field an enum implementing BitLookup
+// and every bit lookup table a flags struct implementing BitLookup. Indirect lookup fields get
+// a method naming their value by the key field.
+// The output only depends on the bitfield crate, use it from build.rs:
+//
+//     let database = PgnDatabase::load("pgns.json")?;
+//     CodeGenerator::new(&database).write_to(out_dir.join("pgns.rs"))?;
+//
+// and include!(concat!(env!("OUT_DIR"), "/pgns.rs")) in a module.
+//
+// A trailing repeating group of fixed size fields becomes a Vec of a row struct, its count
+// field is not part of the struct and is set from the number of rows on encode. Messages
+// with other repeating layouts or with variants are skipped with a comment.
+
+// type names of the lookup tables by whether they are bit lookups and their name
+type LookupNames = BTreeMap<(bool, String), String>;
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Generates Rust types for the messages of a PGN database
+pub struct CodeGenerator<'a> {
+    database: &'a PgnDatabase,
+    pgns: Option<BTreeSet<u32>>,
+}
+
+// a message with the names chosen for its type and fields
+struct MessageDef<'a> {
+    schema: &'a MessageSchema,
+    type_name: String,
+    fields: Vec<(&'a FieldDescriptor, String)>,
+    group: Option<GroupDef<'a>>,
+}
+
+// the trailing repeating group of a message
+struct GroupDef<'a> {
+    group: &'a RepeatingGroup,
+    ident: String,
+    count: Option<(&'a FieldDescriptor, String)>,
+    // the fields of one repetition starting at bit 0
+    row: Box<MessageDef<'a>>,
+}
+
+impl<'a> CodeGenerator<'a> {
+    pub fn new(database: &'a PgnDatabase) -> CodeGenerator<'a> {
+        CodeGenerator {
+            database,
+            pgns: None,
+        }
+    }
+
+    /// Only generate the given PGNs, returns self for chaining
+    pub fn with_pgns(mut self, pgns: &[u32]) -> CodeGenerator<'a> {
+        self.pgns = Some(pgns.iter().copied().collect());
+        self
+    }
+
+    /// Generate the source of all selected messages and the lookups they use
+    pub fn generate(&self) -> Result<String> {
+        let selected: Vec<&MessageSchema> = self
+            .database
+            .messages
+            .iter()
+            .filter(|schema| {
+                self.pgns
+                    .as_ref()
+                    .map_or(true, |pgns| pgns.contains(&schema.id))
+            })
+            .collect();
+        let rows: Vec<Option<MessageSchema>> =
+            selected.iter().map(|schema| row_schema(schema)).collect();
+
+        let mut names = BTreeSet::new();
+        let mut messages = Vec::new();
+        let mut skipped = Vec::new();
+        for (schema, row) in selected.iter().zip(&rows) {
+            if schema.variants.is_some() || (!schema.groups.is_empty() && row.is_none()) {
+                skipped.push(*schema);
+                continue;
+            }
+            let type_name = unique(&mut names, camel_case(&schema.name));
+            let mut field_names = BTreeSet::new();
+            let group = match (schema.groups.first(), row) {
+                (Some(group), Some(row)) => {
+                    let row_name = unique(
+                        &mut names,
+                        format!("{}{}", type_name, camel_case(&group.name)),
+                    );
+                    Some(GroupDef {
+                        group,
+                        ident: unique(&mut field_names, snake_case(&group.name)),
+                        count: None,
+                        row: Box::new(MessageDef {
+                            schema: row,
+                            type_name: row_name,
+                            fields: visible_fields(&row.fields),
+                            group: None,
+                        }),
+                    })
+                }
+                _ => None,
+            };
+            let end = group
+                .as_ref()
+                .map_or(schema.fields.len(), |group| group.group.first);
+            let count = group
+                .as_ref()
+                .and_then(|group| group.group.count_field.as_deref());
+            let mut fields = Vec::new();
+            let mut count_def = None;
+            for field in schema.fields[..end]
+                .iter()
+                .filter(|field| field.field_type != FieldType::Reserved)
+            {
+                let ident = unique(&mut field_names, snake_case(&field.name));
+                if Some(field.name.as_str()) == count {
+                    count_def = Some((field, ident));
+                } else {
+                    fields.push((field, ident));
+                }
+            }
+            let group = group.map(|group| GroupDef {
+                count: count_def,
+                ..group
+            });
+            messages.push(MessageDef {
+                schema,
+                type_name,
+                fields,
+                group,
+            });
+        }
+
+        let mut lookups = BTreeMap::new();
+        for message in &messages {
+            let rows = message.group.iter().flat_map(|group| &group.row.fields);
+            for (field, _) in message.fields.iter().chain(rows) {
+                if let Some(lookup) = &field.lookup {
+                    match field.field_type {
+                        FieldType::Lookup => lookups.entry((false, lookup.name.clone())),
+                        FieldType::BitLookup => lookups.entry((true, lookup.name.clone())),
+                        _ => continue,
+                    }
+                    .or_insert(lookup);
+                }
+            }
+        }
+        let lookup_names: LookupNames = lookups
+            .keys()
+            .map(|key| {
+                let mut type_name = camel_case(&key.1);
+                if names.contains(&type_name) {
+                    type_name.push_str(if key.0 { "Flags" } else { "Lookup" });
+                }
+                (key.clone(), unique(&mut names, type_name))
+            })
+            .collect();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "// Generated by bitfield-codegen, do not edit.");
+        if let Some(version) = &self.database.version {
+            let _ = writeln!(out, "// PGN database version {}", version);
+        }
+        for (key, lookup) in &lookups {
+            out.push('\n');
+            if key.0 {
+                write_flags(&mut out, &lookup_names[key], lookup);
+            } else {
+                write_lookup(&mut out, &lookup_names[key], lookup);
+            }
+        }
+        for message in &messages {
+            out.push('\n');
+            write_message(&mut out, message, &lookup_names)?;
+        }
+        for schema in skipped {
+            let _ = writeln!(
+                out,
+                "\n// PGN {} {} skipped, its variants or repeating fields are not supported",
+                schema.id, schema.name
+            );
+        }
+        Ok(out)
+    }
+
+    /// Generate and write the source to path, the file is left untouched if it is up to date
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        let source = self.generate()?;
+        if fs::read_to_string(path).ok().as_deref() == Some(source.as_str()) {
+            return Ok(());
+        }
+        fs::write(path, source).map_err(|why| {
+            Error::with_all(
+                ErrorKind::Upstream,
+                &format!(
+                    "CodeGenerator::write_to: failed to write {}",
+                    path.display()
+                ),
+                Box::new(why),
+            )
+        })
+    }
+}
+
+fn write_lookup(out: &mut String, type_name: &str, lookup: &Lookup) {
+    let mut variants = BTreeSet::new();
+    variants.insert("Unknown".to_owned());
+    let entries: Vec<(u64, &str, String)> = lookup
+        .values
+        .iter()
+        .map(|(value, name)| {
+            let mut variant = camel_case(name);
+            if variant.is_empty() || variant.starts_with(|c: char| c.is_ascii_digit()) {
+                variant = format!("V{}", variant);
+            }
+            if variants.contains(&variant) {
+                variant = format!("{}{}", variant, value);
+            }
+            (*value, name.as_str(), unique(&mut variants, variant))
+        })
+        .collect();
+
+    let _ = writeln!(out, "/// Lookup {}", lookup.name);
+    let _ = writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq)]");
+    let _ = writeln!(out, "pub enum {} {{", type_name);
+    for (value, name, variant) in &entries {
+        let _ = writeln!(out, "    /// {} = {}", doc_text(name), value);
+        let _ = writeln!(out, "    {},", variant);
+    }
+    let _ = writeln!(out, "    Unknown(u64),");
+    let _ = writeln!(out, "}}\n");
+
+    let _ = writeln!(out, "impl ::bitfield::BitLookup for {} {{", type_name);
+    let _ = writeln!(
+        out,
+        "    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {{"
+    );
+    let _ = writeln!(out, "        Ok(match raw {{");
+    for (value, _, variant) in &entries {
+        let _ = writeln!(out, "            {} => {}::{},", value, type_name, variant);
+    }
+    let _ = writeln!(out, "            raw => {}::Unknown(raw),", type_name);
+    let _ = writeln!(out, "        }})\n    }}\n");
+    let _ = writeln!(out, "    fn to_raw(&self) -> u64 {{");
+    let _ = writeln!(out, "        match self {{");
+    for (value, _, variant) in &entries {
+        let _ = writeln!(out, "            {}::{} => {},", type_name, variant, value);
+    }
+    let _ = writeln!(out, "            {}::Unknown(raw) => *raw,", type_name);
+    let _ = writeln!(out, "        }}\n    }}\n");
+    let _ = writeln!(out, "    fn name(&self) -> Option<&'static str> {{");
+    let _ = writeln!(out, "        match self {{");
+    for (_, name, variant) in &entries {
+        let _ = writeln!(
+            out,
+            "            {}::{} => Some({:?}),",
+            type_name, variant, name
+        );
+    }
+    let _ = writeln!(out, "            {}::Unknown(_) => None,", type_name);
+    let _ = writeln!(out, "        }}\n    }}\n");
+    write_from_name(
+        out,
+        type_name,
+        entries.iter().map(|(_, name, variant)| (*name, variant)),
+    );
+    let _ = writeln!(out, "}}");
+}
+
+// the flags of a bit lookup table, unknown bits are kept
+fn write_flags(out: &mut String, type_name: &str, lookup: &Lookup) {
+    let mut consts = BTreeSet::new();
+    let entries: Vec<(u64, &str, String)> = lookup
+        .values
+        .iter()
+        .filter(|(bit, _)| **bit < 64)
+        .map(|(bit, name)| {
+            let ident = snake_case(&camel_case(name)).to_ascii_uppercase();
+            (*bit, name.as_str(), unique(&mut consts, ident))
+        })
+        .collect();
+
+    let _ = writeln!(out, "/// Bit lookup {}", lookup.name);
+    let _ = writeln!(out, "#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]");
+    let _ = writeln!(out, "pub struct {}(pub u64);\n", type_name);
+
+    let _ = writeln!(out, "impl {} {{", type_name);
+    for (bit, name, ident) in &entries {
+        let _ = writeln!(out, "    /// {}", doc_text(name));
+        let _ = writeln!(
+            out,
+            "    pub const {}: {} = {}(1 << {});",
+            ident, type_name, type_name, bit
+        );
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "    /// Whether all bits of flags are set");
+    let _ = writeln!(
+        out,
+        "    pub fn contains(&self, flags: {}) -> bool {{",
+        type_name
+    );
+    let _ = writeln!(out, "        self.0 & flags.0 == flags.0\n    }}\n");
+    let _ = writeln!(
+        out,
+        "    /// Get the names of the set flags, unknown bits have no name"
+    );
+    let _ = writeln!(out, "    pub fn names(&self) -> Vec<&'static str> {{");
+    let _ = writeln!(out, "        let mut names = Vec::new();");
+    for (_, name, ident) in &entries {
+        let _ = writeln!(
+            out,
+            "        if self.contains({}::{}) {{\n            names.push({:?});\n        }}",
+            type_name, ident, name
+        );
+    }
+    let _ = writeln!(out, "        names\n    }}\n");
+    let _ = writeln!(
+        out,
+        "    /// Get the flags with the given names, None if a name is unknown"
+    );
+    let _ = writeln!(
+        out,
+        "    pub fn from_names(names: &[&str]) -> Option<{}> {{",
+        type_name
+    );
+    let _ = writeln!(out, "        let mut raw = 0;");
+    let _ = writeln!(out, "        for name in names {{");
+    let _ = writeln!(
+        out,
+        "            raw |= <{} as ::bitfield::BitLookup>::from_name(name)?.0;",
+        type_name
+    );
+    let _ = writeln!(out, "        }}");
+    let _ = writeln!(out, "        Some({}(raw))\n    }}\n}}\n", type_name);
+
+    let _ = writeln!(out, "impl std::ops::BitOr for {} {{", type_name);
+    let _ = writeln!(out, "    type Output = {};\n", type_name);
+    let _ = writeln!(
+        out,
+        "    fn bitor(self, other: {}) -> {} {{",
+        type_name, type_name
+    );
+    let _ = writeln!(out, "        {}(self.0 | other.0)\n    }}\n}}\n", type_name);
+
+    let _ = writeln!(out, "impl ::bitfield::BitLookup for {} {{", type_name);
+    let _ = writeln!(
+        out,
+        "    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {{"
+    );
+    let _ = writeln!(out, "        Ok({}(raw))\n    }}\n", type_name);
+    let _ = writeln!(out, "    fn to_raw(&self) -> u64 {{");
+    let _ = writeln!(out, "        self.0\n    }}\n");
+    write_from_name(
+        out,
+        type_name,
+        entries.iter().map(|(_, name, ident)| (*name, ident)),
+    );
+    let _ = writeln!(out, "}}");
+}
+
+fn write_from_name<'a, I: Iterator<Item = (&'a str, &'a String)>>(
+    out: &mut String,
+    type_name: &str,
+    entries: I,
+) {
+    let _ = writeln!(out, "    fn from_name(name: &str) -> Option<Self> {{");
+    let _ = writeln!(out, "        match name {{");
+    let mut seen = BTreeSet::new();
+    for (name, ident) in entries {
+        // the first of equally named values wins
+        if seen.insert(name) {
+            let _ = writeln!(
+                out,
+                "            {:?} => Some({}::{}),",
+                name, type_name, ident
+            );
+        }
+    }
+    let _ = writeln!(out, "            _ => None,");
+    let _ = writeln!(out, "        }}\n    }}");
+}
+
+fn write_message(out: &mut String, message: &MessageDef, lookups: &LookupNames) -> Result<()> {
+    let schema = message.schema;
+    for field in &schema.fields {
+        field.check().map_err(|why| {
+            Error::with_all(
+                why.kind(),
+                &format!("write_message: invalid field in {}", schema.name),
+                Box::new(why),
+            )
+        })?;
+    }
+    if let Some(group) = &message.group {
+        write_row(out, &group.row, &message.type_name, lookups);
+        out.push('\n');
+    }
+    let variable = schema.fields.iter().any(|field| field.is_variable());
+    let name = &message.type_name;
+    // the fields in front of the group, the group is handled separately
+    let fields = &schema.fields[..message
+        .group
+        .as_ref()
+        .map_or(schema.fields.len(), |group| group.group.first)];
+    // shift is only needed when a variable length field is followed by fields using it
+    let followed = |decode: bool| {
+        schema.fields.iter().enumerate().any(|(index, field)| {
+            field.is_variable()
+                && schema.fields[index + 1..]
+                    .iter()
+                    .any(|field| !decode || field.field_type != FieldType::Reserved)
+        })
+    };
+
+    let _ = writeln!(
+        out,
+        "/// {} (PGN {})",
+        doc_text(schema.description.as_deref().unwrap_or(&schema.name)),
+        schema.id
+    );
+    write_struct(out, message, lookups);
+
+    let _ = writeln!(out, "impl {} {{", name);
+    let _ = writeln!(out, "    pub const PGN: u32 = {};", schema.id);
+    let count = message
+        .group
+        .as_ref()
+        .and_then(|group| group.count.as_ref());
+    for (field, _) in message
+        .fields
+        .iter()
+        .chain(count)
+        .filter(|(field, _)| is_numeric(field))
+    {
+        let _ = writeln!(out, "    {}", spec_def(message, field));
+    }
+    write_indirect(out, message, lookups);
+    let _ = writeln!(out, "}}\n");
+
+    // decode
+    let _ = writeln!(out, "impl ::bitfield::BitDecode for {} {{", name);
+    let _ = writeln!(
+        out,
+        "    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {{"
+    );
+    if followed(true) {
+        let _ = writeln!(out, "        let mut shift = 0;");
+    }
+    let mut moved = false;
+    for (index, field) in fields.iter().enumerate() {
+        if field.field_type == FieldType::Reserved {
+            continue;
+        }
+        let ident = field_name(message, field);
+        let _ = writeln!(
+            out,
+            "        let {} = {};",
+            ident,
+            decode_expr(message, field, moved, lookups)
+        );
+        if field.is_variable() {
+            let follows = schema.fields[index + 1..]
+                .iter()
+                .any(|field| field.field_type != FieldType::Reserved);
+            if follows {
+                let _ = writeln!(out, "        shift += {};", decode_shift(field, moved));
+            }
+            moved = true;
+        }
+    }
+    if let Some(group) = &message.group {
+        let row = &group.row.type_name;
+        let start = position(schema.fields[group.group.first].start, moved);
+        let _ = writeln!(out, "        let mut {} = Vec::new();", group.ident);
+        match &group.count {
+            Some((_, count)) => {
+                let _ = writeln!(
+                    out,
+                    "        for index in 0..{}.unwrap_or(0) as usize {{",
+                    count
+                );
+                let _ = writeln!(
+                    out,
+                    "            {}.push({}::decode_at(bitfield, {} + index * {}::BIT_LEN)?);",
+                    group.ident, row, start, row
+                );
+            }
+            None => {
+                let _ = writeln!(
+                    out,
+                    "        while {} + ({}.len() + 1) * {}::BIT_LEN <= bitfield.bit_len() {{",
+                    start, group.ident, row
+                );
+                let _ = writeln!(
+                    out,
+                    "            {}.push({}::decode_at(bitfield, {} + {}.len() * {}::BIT_LEN)?);",
+                    group.ident, row, start, group.ident, row
+                );
+            }
+        }
+        let _ = writeln!(out, "        }}");
+    }
+    let _ = writeln!(out, "        Ok({} {{", name);
+    for (_, ident) in &message.fields {
+        let _ = writeln!(out, "            {},", ident);
+    }
+    if let Some(group) = &message.group {
+        let _ = writeln!(out, "            {},", group.ident);
+    }
+    let _ = writeln!(out, "        }})\n    }}\n}}\n");
+
+    // encode
+    let min_len = match &message.group {
+        Some(group) => schema.fields[group.group.first].start,
+        None => schema.bit_len(),
+    };
+    let _ = writeln!(out, "impl ::bitfield::BitEncode for {} {{", name);
+    let _ = writeln!(out, "    const BIT_LEN: usize = {};\n", min_len);
+    let _ = writeln!(
+        out,
+        "    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {{"
+    );
+    if followed(false) {
+        let _ = writeln!(out, "        let mut shift = 0;");
+    }
+    let mut moved = false;
+    for (index, field) in fields.iter().enumerate() {
+        match message
+            .group
+            .as_ref()
+            .and_then(|group| group.count.as_ref())
+        {
+            Some((count, _)) if std::ptr::eq(*count, field) => {
+                let ident = &message.group.as_ref().unwrap().ident;
+                let _ = writeln!(
+                    out,
+                    "        {}.set_count(bitfield, self.{}.len())?;",
+                    spec_expr(message, field, moved),
+                    ident
+                );
+            }
+            _ => {
+                let _ = writeln!(out, "        {};", encode_expr(message, field, moved));
+            }
+        }
+        if field.is_variable() {
+            if index + 1 < schema.fields.len() {
+                let _ = writeln!(
+                    out,
+                    "        shift += {};",
+                    encode_shift(field, &field_ident(message, field))
+                );
+            }
+            moved = true;
+        }
+    }
+    if let Some(group) = &message.group {
+        let _ = writeln!(
+            out,
+            "        for (index, row) in self.{}.iter().enumerate() {{",
+            group.ident
+        );
+        let _ = writeln!(
+            out,
+            "            row.encode_at(bitfield, {} + index * {}::BIT_LEN)?;",
+            position(schema.fields[group.group.first].start, moved),
+            group.row.type_name
+        );
+        let _ = writeln!(out, "        }}");
+    }
+    let _ = writeln!(out, "        Ok(())\n    }}");
+    if variable || message.group.is_some() {
+        let mut extra: Vec<String> = schema
+            .fields
+            .iter()
+            .filter(|field| field.is_variable())
+            .map(|field| encode_shift(field, &field_ident(message, field)))
+            .collect();
+        if let Some(group) = &message.group {
+            extra.push(format!(
+                "self.{}.len() * {}::BIT_LEN",
+                group.ident, group.row.type_name
+            ));
+        }
+        let _ = writeln!(
+            out,
+            "\n    fn to_vec(&self) -> ::bitfield::Result<Vec<u8>> {{"
+        );
+        let _ = writeln!(
+            out,
+            "        let bits = Self::BIT_LEN + {};",
+            extra.join(" + ")
+        );
+        let _ = writeln!(out, "        let mut data = vec![0xFF; bits.div_ceil(8)];");
+        let _ = writeln!(
+            out,
+            "        self.encode(&mut ::bitfield::MutableBitField::new(&mut data))?;"
+        );
+        let _ = writeln!(out, "        Ok(data)\n    }}");
+    }
+    let _ = writeln!(out, "}}");
+    Ok(())
+}
+
+// the struct of a repeating group row, decoded and encoded at a bit position
+fn write_row(out: &mut String, row: &MessageDef, message_name: &str, lookups: &LookupNames) {
+    let name = &row.type_name;
+    let _ = writeln!(
+        out,
+        "/// Repeating fields {} of {}",
+        row.schema.name, message_name
+    );
+    write_struct(out, row, lookups);
+
+    let _ = writeln!(out, "impl {} {{", name);
+    let _ = writeln!(out, "    /// Size of one repetition in bits");
+    let _ = writeln!(
+        out,
+        "    pub const BIT_LEN: usize = {};",
+        row.schema.bit_len()
+    );
+    for (field, _) in row.fields.iter().filter(|(field, _)| is_numeric(field)) {
+        let _ = writeln!(out, "    {}", spec_def(row, field));
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "    /// Decode the repetition starting at bit shift");
+    let _ = writeln!(
+        out,
+        "    pub fn decode_at(bitfield: &::bitfield::BitField, shift: usize) -> ::bitfield::Result<Self> {{"
+    );
+    for (field, ident) in &row.fields {
+        let _ = writeln!(
+            out,
+            "        let {} = {};",
+            ident,
+            decode_expr(row, field, true, lookups)
+        );
+    }
+    let _ = writeln!(out, "        Ok({} {{", name);
+    for (_, ident) in &row.fields {
+        let _ = writeln!(out, "            {},", ident);
+    }
+    let _ = writeln!(out, "        }})\n    }}\n");
+    let _ = writeln!(out, "    /// Encode the repetition starting at bit shift");
+    let _ = writeln!(
+        out,
+        "    pub fn encode_at(&self, bitfield: &mut ::bitfield::MutableBitField, shift: usize) -> ::bitfield::Result<()> {{"
+    );
+    for field in &row.schema.fields {
+        let _ = writeln!(out, "        {};", encode_expr(row, field, true));
+    }
+    let _ = writeln!(out, "        Ok(())\n    }}");
+    write_indirect(out, row, lookups);
+    let _ = writeln!(out, "}}");
+}
+
+// methods naming the values of indirect lookup fields, the key field must be an unscaled
+// number or lookup of the same struct
+fn write_indirect(out: &mut String, message: &MessageDef, lookups: &LookupNames) {
+    for (field, ident) in &message.fields {
+        let lookup = match (&field.lookup, &field.indirect_lookup) {
+            (None, Some(lookup)) if field.field_type == FieldType::Lookup => lookup,
+            _ => continue,
+        };
+        let (key, key_ident) = match message
+            .fields
+            .iter()
+            .find(|(key, _)| key.name == lookup.key)
+        {
+            Some(key) => key,
+            None => continue,
+        };
+        let key_expr = match key.field_type {
+            FieldType::Lookup if lookup_type(key, lookups).is_some() => format!(
+                "self.{}.map(|value| ::bitfield::BitLookup::to_raw(&value))?",
+                key_ident
+            ),
+            FieldType::Unsigned | FieldType::Lookup if !key.is_scaled() => {
+                format!("self.{}? as u64", key_ident)
+            }
+            _ => continue,
+        };
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "    /// Name of {} by {}, lookup {}",
+            ident, key_ident, lookup.name
+        );
+        let _ = writeln!(
+            out,
+            "    pub fn {}_name(&self) -> Option<&'static str> {{",
+            ident
+        );
+        let _ = writeln!(
+            out,
+            "        match ({}, self.{}? as u64) {{",
+            key_expr, ident
+        );
+        for ((key, value), name) in &lookup.values {
+            let _ = writeln!(out, "            ({}, {}) => Some({:?}),", key, value, name);
+        }
+        let _ = writeln!(out, "            _ => None,");
+        let _ = writeln!(out, "        }}\n    }}");
+    }
+}
+
+fn write_struct(out: &mut String, message: &MessageDef, lookups: &LookupNames) {
+    let _ = writeln!(out, "#[derive(Debug, Clone, PartialEq)]");
+    let _ = writeln!(out, "pub struct {} {{", message.type_name);
+    for (field, ident) in &message.fields {
+        match &field.unit {
+            Some(unit) => {
+                let _ = writeln!(out, "    /// {}, {}", doc_text(&field.name), doc_text(unit));
+            }
+            None => {
+                let _ = writeln!(out, "    /// {}", doc_text(&field.name));
+            }
+        }
+        let _ = writeln!(out, "    pub {}: {},", ident, rust_type(field, lookups));
+    }
+    if let Some(group) = &message.group {
+        match &group.count {
+            Some((count, _)) => {
+                let _ = writeln!(
+                    out,
+                    "    /// Repeating fields, their number is written to {}",
+                    doc_text(&count.name)
+                );
+            }
+            None => {
+                let _ = writeln!(out, "    /// Repeating fields up to the end of the payload");
+            }
+        }
+        let _ = writeln!(
+            out,
+            "    pub {}: Vec<{}>,",
+            group.ident, group.row.type_name
+        );
+    }
+    let _ = writeln!(out, "}}\n");
+}
+
+// the names of the fields of a struct, reserved fields are left out
+fn visible_fields(fields: &[FieldDescriptor]) -> Vec<(&FieldDescriptor, String)> {
+    let mut names = BTreeSet::new();
+    fields
+        .iter()
+        .filter(|field| field.field_type != FieldType::Reserved)
+        .map(|field| (field, unique(&mut names, snake_case(&field.name))))
+        .collect()
+}
+
+// the fields of the repeating group of schema starting at bit 0, None if the layout is not
+// supported: a single trailing group of fixed size fields with an unsigned count field
+fn row_schema(schema: &MessageSchema) -> Option<MessageSchema> {
+    let group = match schema.groups.as_slice() {
+        [group] if group.size > 0 && group.first + group.size == schema.fields.len() => group,
+        _ => return None,
+    };
+    let fields = group.fields(schema);
+    let base = fields[0].start;
+    if fields
+        .iter()
+        .any(|field| field.is_variable() || field.start < base)
+    {
+        return None;
+    }
+    if let Some(name) = &group.count_field {
+        let count = schema.fields[..group.first]
+            .iter()
+            .find(|field| field.name == *name)?;
+        if count.field_type != FieldType::Unsigned || count.is_scaled() {
+            return None;
+        }
+    }
+    let mut row = MessageSchema::new(schema.id, &group.name);
+    row.fields = fields
+        .iter()
+        .map(|field| FieldDescriptor {
+            start: field.start - base,
+            end: field.end - base,
+            ..field.clone()
+        })
+        .collect();
+    Some(row)
+}
+
+fn field_name<'a>(message: &'a MessageDef, field: &FieldDescriptor) -> &'a str {
+    let count = message
+        .group
+        .as_ref()
+        .and_then(|group| group.count.as_ref());
+    message
+        .fields
+        .iter()
+        .chain(count)
+        .find(|(entry, _)| std::ptr::eq(*entry, field))
+        .map(|(_, ident)| ident.as_str())
+        .unwrap_or_default()
+}
+
+fn field_ident(message: &MessageDef, field: &FieldDescriptor) -> String {
+    format!("self.{}", field_name(message, field))
+}
+
+fn rust_type(field: &FieldDescriptor, lookups: &LookupNames) -> String {
+    match field.field_type {
+        FieldType::String | FieldType::StringLz | FieldType::StringLau => "String".to_owned(),
+        FieldType::Binary | FieldType::Reserved => "Vec<u8>".to_owned(),
+        FieldType::Bool => "Option<bool>".to_owned(),
+        FieldType::Float => "Option<f32>".to_owned(),
+        FieldType::BitLookup => match lookup_type(field, lookups) {
+            Some(type_name) => type_name.to_owned(),
+            None => int_type(field.size(), false).to_owned(),
+        },
+        FieldType::Lookup if field.lookup.is_some() => {
+            format!(
+                "Option<{}>",
+                lookup_type(field, lookups).unwrap_or_default()
+            )
+        }
+        _ if field.is_scaled() || field.field_type == FieldType::Time => "Option<f64>".to_owned(),
+        _ => format!(
+            "Option<{}>",
+            int_type(field.size(), field.field_type == FieldType::Signed)
+        ),
+    }
+}
+
+// the type generated for the lookup of field
+fn lookup_type<'a>(field: &FieldDescriptor, lookups: &'a LookupNames) -> Option<&'a str> {
+    let lookup = field.lookup.as_ref()?;
+    lookups
+        .get(&(
+            field.field_type == FieldType::BitLookup,
+            lookup.name.clone(),
+        ))
+        .map(|name| name.as_str())
+}
+
+fn int_type(size: usize, signed: bool) -> &'static str {
+    match (size, signed) {
+        (0..=8, false) => "u8",
+        (9..=16, false) => "u16",
+        (17..=32, false) => "u32",
+        (_, false) => "u64",
+        (0..=8, true) => "i8",
+        (9..=16, true) => "i16",
+        (17..=32, true) => "i32",
+        (_, true) => "i64",
+    }
+}
+
+// bit position, moved by the extra bits of preceding variable length fields
+fn position(bit: usize, moved: bool) -> String {
+    if moved {
+        format!("{} + shift", bit)
+    } else {
+        bit.to_string()
+    }
+}
+
+fn endianness(field: &FieldDescriptor) -> String {
+    format!("::bitfield::Endianness::{:?}", field.endianness)
+}
+
+// whether the field is read through a FieldSpec constant
+fn is_numeric(field: &FieldDescriptor) -> bool {
+    !matches!(
+        field.field_type,
+        FieldType::String
+            | FieldType::StringLz
+            | FieldType::StringLau
+            | FieldType::Binary
+            | FieldType::Reserved
+            | FieldType::Float
+    )
+}
+
+fn spec_const(message: &MessageDef, field: &FieldDescriptor) -> String {
+    format!("{}_FIELD", field_name(message, field).to_ascii_uppercase())
+}
+
+fn spec_def(message: &MessageDef, field: &FieldDescriptor) -> String {
+    let spec = field.spec();
+    format!(
+        "const {}: ::bitfield::FieldSpec = ::bitfield::FieldSpec {{\n        \
+         start: {},\n        end: {},\n        endianness: {},\n        signed: {},\n        \
+         resolution: {:?},\n        offset: {:?},\n        na: {},\n    }};",
+        spec_const(message, field),
+        spec.start,
+        spec.end,
+        endianness(field),
+        spec.signed,
+        spec.resolution,
+        spec.offset,
+        spec.na
+    )
+}
+
+fn spec_expr(message: &MessageDef, field: &FieldDescriptor, moved: bool) -> String {
+    if moved {
+        format!("Self::{}.moved(shift)", spec_const(message, field))
+    } else {
+        format!("Self::{}", spec_const(message, field))
+    }
+}
+
+fn descriptor_expr(field: &FieldDescriptor, moved: bool) -> String {
+    format!(
+        "::bitfield::FieldDescriptor {{ endianness: {}, ..::bitfield::FieldDescriptor::new({:?}, {}, {}, ::bitfield::FieldType::{:?}) }}",
+        endianness(field),
+        field.name,
+        position(field.start, moved),
+        position(field.end, moved),
+        field.field_type
+    )
+}
+
+fn decode_expr(
+    message: &MessageDef,
+    field: &FieldDescriptor,
+    moved: bool,
+    lookups: &LookupNames,
+) -> String {
+    let spec = spec_expr(message, field, moved);
+    match field.field_type {
+        FieldType::String | FieldType::StringLz | FieldType::StringLau => format!(
+            "match ::bitfield::dynamic::decode_field(&{}, bitfield)? {{ \
+             ::bitfield::Value::String(text) => text, _ => String::new() }}",
+            descriptor_expr(field, moved)
+        ),
+        FieldType::Binary | FieldType::Reserved => format!(
+            "match ::bitfield::dynamic::decode_field(&{}, bitfield)? {{ \
+             ::bitfield::Value::Bytes(bytes) => bytes, _ => Vec::new() }}",
+            descriptor_expr(field, moved)
+        ),
+        FieldType::Bool => format!("{}.get_i64(bitfield)?.map(|value| value != 0)", spec),
+        FieldType::Float => format!(
+            "Some(f32::from_bits(bitfield.get_u64({}, {}, {})? as u32)).filter(|value| !value.is_nan())",
+            position(field.start, moved),
+            position(field.end, moved),
+            endianness(field)
+        ),
+        FieldType::BitLookup if field.lookup.is_some() => format!(
+            "<{} as ::bitfield::BitLookup>::from_raw({}.get_raw(bitfield)?)?",
+            lookup_type(field, lookups).unwrap_or_default(),
+            spec
+        ),
+        FieldType::BitLookup => format!(
+            "{}.get_raw(bitfield)? as {}",
+            spec,
+            int_type(field.size(), false)
+        ),
+        FieldType::Lookup if field.lookup.is_some() => format!(
+            "match {}.get_i64(bitfield)? {{ Some(value) => \
+             Some(<{} as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None }}",
+            spec,
+            lookup_type(field, lookups).unwrap_or_default()
+        ),
+        _ if field.is_scaled() || field.field_type == FieldType::Time => {
+            format!("{}.get_f64(bitfield)?", spec)
+        }
+        _ => format!(
+            "{}.get_i64(bitfield)?.map(|value| value as {})",
+            spec,
+            int_type(field.size(), field.field_type == FieldType::Signed)
+        ),
+    }
+}
+
+fn encode_expr(message: &MessageDef, field: &FieldDescriptor, moved: bool) -> String {
+    let spec = spec_expr(message, field, moved);
+    let ident = field_ident(message, field);
+    match field.field_type {
+        FieldType::Reserved => format!(
+            "::bitfield::dynamic::encode_field(&{}, &::bitfield::Value::NotAvailable, bitfield)?",
+            descriptor_expr(field, moved)
+        ),
+        FieldType::String | FieldType::StringLz | FieldType::StringLau => format!(
+            "::bitfield::dynamic::encode_field(&{}, &::bitfield::Value::String({}.clone()), bitfield)?",
+            descriptor_expr(field, moved),
+            ident
+        ),
+        FieldType::Binary => format!(
+            "::bitfield::dynamic::encode_field(&{}, &::bitfield::Value::Bytes({}.clone()), bitfield)?",
+            descriptor_expr(field, moved),
+            ident
+        ),
+        FieldType::Bool => format!(
+            "{}.set_i64(bitfield, {}.map(|value| value as i64))?",
+            spec, ident
+        ),
+        FieldType::Float => format!(
+            "bitfield.set_u64({}.map_or(0xFFFF_FFFF, |value| value.to_bits() as u64), {}, {}, {})?",
+            ident,
+            position(field.start, moved),
+            position(field.end, moved),
+            endianness(field)
+        ),
+        FieldType::BitLookup if field.lookup.is_some() => format!(
+            "{}.set_i64(bitfield, Some(::bitfield::BitLookup::to_raw(&{}) as i64))?",
+            spec, ident
+        ),
+        FieldType::BitLookup => format!("{}.set_i64(bitfield, Some({} as i64))?", spec, ident),
+        FieldType::Lookup if field.lookup.is_some() => format!(
+            "{}.set_i64(bitfield, {}.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?",
+            spec, ident
+        ),
+        _ if field.is_scaled() || field.field_type == FieldType::Time => {
+            format!("{}.set_f64(bitfield, {})?", spec, ident)
+        }
+        _ => format!(
+            "{}.set_i64(bitfield, {}.map(|value| value as i64))?",
+            spec, ident
+        ),
+    }
+}
+
+// extra bits of a variable length field read from the payload
+fn decode_shift(field: &FieldDescriptor, moved: bool) -> String {
+    let length = format!(
+        "bitfield.get_u8({}, {})? as usize",
+        position(field.start, moved),
+        position(field.start + 7, moved)
+    );
+    match field.field_type {
+        FieldType::StringLz => format!("({}) * 8", length),
+        _ => format!("({}).max(2) * 8 - 16", length),
+    }
+}
+
+// extra bits of a variable length field holding ident
+fn encode_shift(field: &FieldDescriptor, ident: &str) -> String {
+    match field.field_type {
+        FieldType::StringLz => format!("({}.len() + 1) * 8", ident),
+        _ => format!("{}.len() * 8", ident),
+    }
+}
+
+fn doc_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+// make name unique within names by appending a number
+fn unique(names: &mut BTreeSet<String>, name: String) -> String {
+    let mut candidate = name.clone();
+    let mut index = 2;
+    while names.contains(&candidate) {
+        candidate = format!("{}{}", name, index);
+        index += 1;
+    }
+    names.insert(candidate.clone());
+    candidate
+}
+
+/// Convert a canboat id or name to a type name: "ENGINE_INSTANCE" and "engineInstance"
+/// become "EngineInstance"
+pub fn camel_case(text: &str) -> String {
+    let mut result = String::new();
+    for word in text.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            result.push(first.to_ascii_uppercase());
+            let rest: String = chars.collect();
+            // acronyms are written as words
+            if word.chars().all(|c| !c.is_ascii_lowercase()) {
+                result.push_str(&rest.to_ascii_lowercase());
+            } else {
+                result.push_str(&rest);
+            }
+        }
+    }
+    result
+}
+
+/// Convert a canboat id to a field name: "tiltTrim" becomes "tilt_trim"
+pub fn snake_case(text: &str) -> String {
+    let mut result = String::new();
+    let mut previous: Option<char> = None;
+    for c in text.chars() {
+        if c.is_ascii_uppercase() {
+            if matches!(previous, Some(p) if p.is_ascii_lowercase() || p.is_ascii_digit()) {
+                result.push('_');
+            }
+            result.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            result.push(c);
+        } else if !result.is_empty() && !result.ends_with('_') {
+            result.push('_');
+        }
+        previous = Some(c);
+    }
+    let result = result.trim_end_matches('_').to_owned();
+    if result.is_empty() || result.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field_{}", result)
+    } else if KEYWORDS.contains(&result.as_str()) {
+        format!("{}_", result)
+    } else {
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_JSON: &str = include_str!("../data/canboat/pgns-sample.json");
+    const GENERATED: &str = include_str!("../tests/generated/pgns_sample.rs");
+
+    #[test]
+    fn test_names() {
+        assert_eq!(camel_case("ENGINE_INSTANCE"), "EngineInstance");
+        assert_eq!(
+            camel_case("engineParametersRapidUpdate"),
+            "EngineParametersRapidUpdate"
+        );
+        assert_eq!(
+            camel_case("Inter/Intranetwork Device"),
+            "InterIntranetworkDevice"
+        );
+        assert_eq!(camel_case("GPS"), "Gps");
+        assert_eq!(snake_case("tiltTrim"), "tilt_trim");
+        assert_eq!(snake_case("nmea2000Version"), "nmea2000_version");
+        assert_eq!(snake_case("type"), "type_");
+        assert_eq!(snake_case("2ndValue"), "field_2nd_value");
+    }
+
+    #[test]
+    fn test_generate() {
+        let database = PgnDatabase::from_json(SAMPLE_JSON).unwrap();
+        let source = CodeGenerator::new(&database).generate().unwrap();
+        // tests/codegen.rs compiles and runs the generated code, regenerate it with
+        // cargo run --bin bitfield-codegen -- data/canboat/pgns-sample.json tests/generated/pgns_sample.rs
+        assert_eq!(source, GENERATED);
+
+        let source = CodeGenerator::new(&database)
+            .with_pgns(&[127488])
+            .generate()
+            .unwrap();
+        assert!(source.contains("pub struct EngineParametersRapidUpdate {"));
+        assert!(source.contains("pub enum EngineInstance {"));
+        assert!(!source.contains("SystemTime"));
+    }
+}
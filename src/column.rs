@@ -0,0 +1,114 @@
+use crate::{BitField, FieldSpec};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+// Decode one field from many payloads. Payloads that are too short to hold the field
+// and "not available" values end up as NaN / None so the column stays aligned with the input.
+
+/// Decode the scaled value of a field from each payload and append it to column
+pub fn decode_f64_column<I>(field: &FieldSpec, payloads: I, column: &mut Vec<f64>)
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let payloads = payloads.into_iter();
+    column.reserve(payloads.size_hint().0);
+    column.extend(payloads.map(|payload| decode_f64(field, payload.as_ref())));
+}
+
+/// Decode the unscaled value of a field from each payload and append it to column
+pub fn decode_i64_column<I>(field: &FieldSpec, payloads: I, column: &mut Vec<Option<i64>>)
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let payloads = payloads.into_iter();
+    column.reserve(payloads.size_hint().0);
+    column.extend(payloads.map(|payload| decode_i64(field, payload.as_ref())));
+}
+
+/// Parallel version of decode_f64_column
+#[cfg(feature = "rayon")]
+pub fn par_decode_f64_column<P>(field: &FieldSpec, payloads: &[P], column: &mut Vec<f64>)
+where
+    P: AsRef<[u8]> + Sync,
+{
+    column.par_extend(
+        payloads
+            .par_iter()
+            .map(|payload| decode_f64(field, payload.as_ref())),
+    );
+}
+
+/// Parallel version of decode_i64_column
+#[cfg(feature = "rayon")]
+pub fn par_decode_i64_column<P>(field: &FieldSpec, payloads: &[P], column: &mut Vec<Option<i64>>)
+where
+    P: AsRef<[u8]> + Sync,
+{
+    column.par_extend(
+        payloads
+            .par_iter()
+            .map(|payload| decode_i64(field, payload.as_ref())),
+    );
+}
+
+fn decode_f64(field: &FieldSpec, payload: &[u8]) -> f64 {
+    match field.get_f64(&BitField::new(payload)) {
+        Ok(Some(value)) => value,
+        _ => f64::NAN,
+    }
+}
+
+fn decode_i64(field: &FieldSpec, payload: &[u8]) -> Option<i64> {
+    field.get_i64(&BitField::new(payload)).unwrap_or(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Endianness;
+
+    fn payloads() -> Vec<Vec<u8>> {
+        vec![
+            vec![0x00, 0x10, 0x27, 0xFF],
+            vec![0x00, 0xFF, 0xFF, 0xFF],
+            vec![0x00, 0x20],
+            vec![0x00, 0x40, 0x1F, 0x00],
+        ]
+    }
+
+    #[test]
+    fn test_decode_column() {
+        let rpm = FieldSpec {
+            resolution: 0.25,
+            ..FieldSpec::new(8, 23, Endianness::Little)
+        };
+        let mut column = Vec::new();
+        decode_f64_column(&rpm, payloads(), &mut column);
+        assert_eq!(column.len(), 4);
+        assert_eq!(column[0], 2500.0);
+        assert!(column[1].is_nan());
+        assert!(column[2].is_nan());
+        assert_eq!(column[3], 2000.0);
+
+        let mut column = vec![None];
+        decode_i64_column(&rpm, payloads().iter().map(|p| p.as_slice()), &mut column);
+        assert_eq!(column, vec![None, Some(10000), None, None, Some(8000)]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_decode_column() {
+        let rpm = FieldSpec::new(8, 23, Endianness::Little);
+        let mut column = Vec::new();
+        par_decode_i64_column(&rpm, &payloads(), &mut column);
+        assert_eq!(column, vec![Some(10000), None, None, Some(8000)]);
+        let mut sequential = Vec::new();
+        decode_f64_column(&rpm, payloads(), &mut sequential);
+        let mut column = Vec::new();
+        par_decode_f64_column(&rpm, &payloads(), &mut column);
+        assert_eq!(column[0], sequential[0]);
+        assert_eq!(column[3], sequential[3]);
+    }
+}
@@ -0,0 +1,1294 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::schema::{FieldDescriptor, FieldType, MessageSchema, RepeatingGroup, Variants};
+use crate::{BitField, Endianness, MutableBitField};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Formatter};
+
+/// A decoded field value
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Value {
+    Bool(bool),
+    Unsigned(u64),
+    Signed(i64),
+    /// scaled value
+    Float(f64),
+    /// lookup value and its name if known
+    Lookup(u64, Option<String>),
+    /// flag bits and the names of the known set flags
+    BitLookup(u64, Vec<String>),
+    /// days since 1970-01-01
+    Date(u32),
+    /// seconds since midnight
+    Time(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    /// the rows of a repeating group
+    Group(Vec<Vec<DecodedField>>),
+    /// the name and fields of the selected variant layout, unknown variants decode to Bytes
+    Variant(String, Vec<DecodedField>),
+    /// the "not available" marker was set
+    NotAvailable,
+}
+
+impl Value {
+    /// Get the numeric value, None for text, bytes and not available
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Bool(value) => Some(*value as u8 as f64),
+            Value::Unsigned(value) => Some(*value as f64),
+            Value::Signed(value) => Some(*value as f64),
+            Value::Float(value) => Some(*value),
+            Value::Lookup(value, _) | Value::BitLookup(value, _) => Some(*value as f64),
+            Value::Date(value) => Some(*value as f64),
+            Value::Time(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Get the integer value, None for scaled values, text, bytes and not available
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Bool(value) => Some(*value as i64),
+            Value::Unsigned(value) => Some(*value as i64),
+            Value::Signed(value) => Some(*value),
+            Value::Lookup(value, _) | Value::BitLookup(value, _) => Some(*value as i64),
+            Value::Date(value) => Some(*value as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            Value::Lookup(_, Some(name)) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Get the name and fields of a variant
+    pub fn as_variant(&self) -> Option<(&str, &[DecodedField])> {
+        match self {
+            Value::Variant(name, fields) => Some((name, fields)),
+            _ => None,
+        }
+    }
+
+    /// Get the rows of a repeating group
+    pub fn as_group(&self) -> Option<&[Vec<DecodedField>]> {
+        match self {
+            Value::Group(rows) => Some(rows),
+            _ => None,
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        *self != Value::NotAvailable
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Unsigned(value) => write!(f, "{}", value),
+            Value::Signed(value) => write!(f, "{}", value),
+            Value::Float(value) => write!(f, "{}", value),
+            Value::Lookup(_, Some(name)) => write!(f, "{}", name),
+            Value::Lookup(value, None) => write!(f, "{}", value),
+            Value::BitLookup(value, names) if names.is_empty() => write!(f, "{:#X}", value),
+            Value::BitLookup(_, names) => write!(f, "{}", names.join(", ")),
+            Value::Date(value) => {
+                let (year, month, day) = civil_from_days(*value as i64);
+                write!(f, "{:04}-{:02}-{:02}", year, month, day)
+            }
+            Value::Time(value) => {
+                let whole = *value as u64;
+                write!(
+                    f,
+                    "{:02}:{:02}:{:02}",
+                    whole / 3600,
+                    whole / 60 % 60,
+                    whole % 60
+                )?;
+                let fraction = value - whole as f64;
+                if fraction > 0.0 {
+                    write!(f, ".{:04}", (fraction * 10000.0).round() as u64)?;
+                }
+                Ok(())
+            }
+            Value::String(value) => write!(f, "{}", value),
+            Value::Bytes(value) => {
+                for (index, byte) in value.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{:02X}", byte)?;
+                }
+                Ok(())
+            }
+            Value::Group(rows) => {
+                write!(f, "[")?;
+                for (index, row) in rows.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write_fields(f, row)?;
+                }
+                write!(f, "]")
+            }
+            Value::Variant(name, fields) => {
+                write!(f, "{}", name)?;
+                write_fields(f, fields)
+            }
+            Value::NotAvailable => write!(f, "NA"),
+        }
+    }
+}
+
+// fields as (name: value, ...)
+fn write_fields(f: &mut Formatter<'_>, fields: &[DecodedField]) -> fmt::Result {
+    write!(f, "(")?;
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}: {}", field.name, field.value)?;
+    }
+    write!(f, ")")
+}
+
+/// A decoded field with its name and unit
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecodedField {
+    pub name: String,
+    pub value: Value,
+    pub unit: Option<String>,
+}
+
+impl DecodedField {
+    pub fn new(name: &str, value: Value) -> DecodedField {
+        DecodedField {
+            name: name.to_owned(),
+            value,
+            unit: None,
+        }
+    }
+}
+
+/// The fields of a message decoded through a schema, in schema order
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecodedMessage {
+    pub id: u32,
+    pub name: String,
+    pub fields: Vec<DecodedField>,
+}
+
+impl DecodedMessage {
+    pub fn new(id: u32, name: &str) -> DecodedMessage {
+        DecodedMessage {
+            id,
+            name: name.to_owned(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Append a field value, returns self for chaining
+    pub fn with_value(mut self, name: &str, value: Value) -> DecodedMessage {
+        self.fields.push(DecodedField::new(name, value));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        find_value(&self.fields, name)
+    }
+}
+
+/// Decode all fields of schema from bitfield, reserved fields are skipped.
+/// Fields following a variable length field are moved by its actual size. Repeating groups
+/// decode to a Value::Group named after the group, repeated as often as their count field
+/// says or, without a count field, as long as the payload holds another repetition.
+/// Variants decode to a Value::Variant, or the remaining bytes for unknown discriminators.
+/// Indirect lookup fields are named by the value of their key field
+pub fn decode_dynamic(schema: &MessageSchema, bitfield: &BitField) -> Result<DecodedMessage> {
+    let mut message = DecodedMessage::new(schema.id, &schema.name);
+    let mut shift = 0;
+    let mut index = 0;
+    while index < schema.fields.len() {
+        let group = match schema.group_at(index) {
+            Some(group) => group,
+            None => {
+                shift = decode_fields(
+                    schema,
+                    &schema.fields[index..=index],
+                    bitfield,
+                    shift,
+                    &mut message.fields,
+                )?;
+                index += 1;
+                continue;
+            }
+        };
+        let fields = group.fields(schema);
+        let size = group_size(schema, group)?;
+        let count = group.count_field.as_ref().map(|name| {
+            message
+                .get(name)
+                .and_then(Value::as_i64)
+                .unwrap_or(0)
+                .max(0) as usize
+        });
+        let mut rows = Vec::new();
+        loop {
+            let done = match count {
+                Some(count) => rows.len() >= count,
+                None => fields[0].start as isize + shift + size > bitfield.bit_len() as isize,
+            };
+            if done {
+                break;
+            }
+            let mut row = Vec::new();
+            shift = decode_fields(schema, fields, bitfield, shift, &mut row)? + size;
+            name_indirect(fields, &mut row);
+            rows.push(row);
+        }
+        shift -= size;
+        message
+            .fields
+            .push(DecodedField::new(&group.name, Value::Group(rows)));
+        index += fields.len();
+    }
+    name_indirect(&schema.fields, &mut message.fields);
+    if let Some(variants) = &schema.variants {
+        let value = decode_variant(schema, variants, &message.fields, bitfield, shift)?;
+        message
+            .fields
+            .push(DecodedField::new(&variants.name, value));
+    }
+    Ok(message)
+}
+
+// decode the variant selected by the discriminators in fields, unknown variants keep the
+// remaining payload as bytes
+fn decode_variant(
+    schema: &MessageSchema,
+    variants: &Variants,
+    fields: &[DecodedField],
+    bitfield: &BitField,
+    shift: isize,
+) -> Result<Value> {
+    let values: Option<Vec<u64>> = variants
+        .discriminators
+        .iter()
+        .map(|name| {
+            find_value(fields, name)
+                .and_then(Value::as_i64)
+                .map(|value| value as u64)
+        })
+        .collect();
+    if let Some(case) = values.and_then(|values| variants.select(&values)) {
+        let mut out = Vec::new();
+        decode_fields(schema, &case.fields, bitfield, shift, &mut out)?;
+        name_indirect(&case.fields, &mut out);
+        return Ok(Value::Variant(case.name.clone(), out));
+    }
+    let start = (variants.start as isize + shift) as usize;
+    if start >= bitfield.bit_len() {
+        return Ok(Value::Bytes(Vec::new()));
+    }
+    get_bytes(
+        &raw_field(schema, variants, start, bitfield.bit_len() - 1),
+        bitfield,
+    )
+    .map(Value::Bytes)
+}
+
+// the bits of an unknown variant in the bit order of the first discriminator
+fn raw_field(
+    schema: &MessageSchema,
+    variants: &Variants,
+    start: usize,
+    end: usize,
+) -> FieldDescriptor {
+    let endianness = variants
+        .discriminators
+        .first()
+        .and_then(|name| schema.get_field(name))
+        .map_or(Endianness::Big, |field| field.endianness);
+    FieldDescriptor {
+        endianness,
+        ..FieldDescriptor::new(&variants.name, start, end, FieldType::Binary)
+    }
+}
+
+// decode fields moved by shift into out, returns the shift after the fields
+fn decode_fields(
+    schema: &MessageSchema,
+    fields: &[FieldDescriptor],
+    bitfield: &BitField,
+    mut shift: isize,
+    out: &mut Vec<DecodedField>,
+) -> Result<isize> {
+    for field in fields {
+        let field = moved(field, shift);
+        if field.is_variable() {
+            shift += variable_size(&field, bitfield)? as isize - field.size() as isize;
+        }
+        if field.field_type == FieldType::Reserved {
+            continue;
+        }
+        let value = decode_field(&field, bitfield).map_err(|why| {
+            Error::with_all(
+                why.kind(),
+                &format!(
+                    "decode_dynamic: failed to decode field {} of {}",
+                    field.name, schema.name
+                ),
+                Box::new(why),
+            )
+        })?;
+        out.push(DecodedField {
+            name: field.name.clone(),
+            value,
+            unit: field.unit.clone(),
+        });
+    }
+    Ok(shift)
+}
+
+/// Decode a single field
+pub fn decode_field(field: &FieldDescriptor, bitfield: &BitField) -> Result<Value> {
+    field.check()?;
+    let spec = field.spec();
+    let value = match field.field_type {
+        FieldType::String => Value::String(decode_string(&get_bytes(field, bitfield)?)),
+        FieldType::StringLz | FieldType::StringLau => decode_variable_string(field, bitfield)?,
+        FieldType::Float => {
+            let value =
+                f32::from_bits(bitfield.get_u64(field.start, field.end, field.endianness)? as u32);
+            if value.is_nan() {
+                Value::NotAvailable
+            } else {
+                Value::Float(value as f64)
+            }
+        }
+        FieldType::Time => match spec.get_f64(bitfield)? {
+            Some(value) => Value::Time(value),
+            None => Value::NotAvailable,
+        },
+        FieldType::Date => match spec.get_i64(bitfield)? {
+            Some(value) => Value::Date(value as u32),
+            None => Value::NotAvailable,
+        },
+        FieldType::BitLookup => {
+            let value = spec.get_raw(bitfield)?;
+            let names = match &field.lookup {
+                Some(lookup) => lookup
+                    .flag_names(value)
+                    .into_iter()
+                    .map(|name| name.to_owned())
+                    .collect(),
+                None => Vec::new(),
+            };
+            Value::BitLookup(value, names)
+        }
+        FieldType::Binary | FieldType::Reserved => Value::Bytes(get_bytes(field, bitfield)?),
+        _ if field.is_scaled() => match spec.get_f64(bitfield)? {
+            Some(value) => Value::Float(value),
+            None => Value::NotAvailable,
+        },
+        FieldType::Bool => match spec.get_i64(bitfield)? {
+            Some(value) => Value::Bool(value != 0),
+            None => Value::NotAvailable,
+        },
+        FieldType::Signed => match spec.get_i64(bitfield)? {
+            Some(value) => Value::Signed(value),
+            None => Value::NotAvailable,
+        },
+        FieldType::Unsigned => match spec.get_i64(bitfield)? {
+            Some(value) => Value::Unsigned(value as u64),
+            None => Value::NotAvailable,
+        },
+        FieldType::Lookup => match spec.get_i64(bitfield)? {
+            Some(value) => {
+                let value = value as u64;
+                let name = field
+                    .lookup
+                    .as_ref()
+                    .and_then(|lookup| lookup.name_of(value))
+                    .map(|name| name.to_owned());
+                Value::Lookup(value, name)
+            }
+            None => Value::NotAvailable,
+        },
+    };
+    Ok(value)
+}
+
+/// Encode the fields of message into bitfield using schema. Fields missing from the message
+/// are written as "not available", reserved fields as all 1 bits. The count fields of
+/// repeating groups are set to the number of rows given for the group, the discriminators of
+/// a known variant to its values. Lookup fields accept their number or name, bit lookups
+/// the raw bits combined with the bits of the given flag names
+pub fn encode_dynamic(
+    schema: &MessageSchema,
+    message: &DecodedMessage,
+    bitfield: &mut MutableBitField,
+) -> Result<()> {
+    encode_message(schema, message, Some(bitfield)).map(|_| ())
+}
+
+/// Encode a message into a new payload sized for its variable length fields, groups and
+/// variant
+pub fn encode_dynamic_to_vec(schema: &MessageSchema, message: &DecodedMessage) -> Result<Vec<u8>> {
+    let bits = encode_message(schema, message, None)?;
+    let mut data = vec![0xFF; bits.div_ceil(8)];
+    encode_dynamic(schema, message, &mut MutableBitField::new(&mut data))?;
+    Ok(data)
+}
+
+// encode message, returns the payload size in bits, without a bitfield only the size is
+// computed
+fn encode_message(
+    schema: &MessageSchema,
+    message: &DecodedMessage,
+    mut bitfield: Option<&mut MutableBitField>,
+) -> Result<usize> {
+    let no_rows = Vec::new();
+    let rows_of = |group: &RepeatingGroup| match message.get(&group.name) {
+        Some(Value::Group(rows)) => rows,
+        _ => &no_rows,
+    };
+    let mut overrides: Vec<(&str, Value)> = Vec::new();
+    for group in &schema.groups {
+        if let Some(name) = group.count_field.as_deref() {
+            let rows = rows_of(group).len();
+            // the count must neither be truncated nor read back as "not available"
+            let fits = match schema.get_field(name) {
+                Some(field) => i64::try_from(rows)
+                    .ok()
+                    .and_then(|rows| field.spec().check_raw(rows).ok())
+                    .is_some(),
+                None => true,
+            };
+            if !fits {
+                return Err(Error::with_context(
+                    ErrorKind::OutOfRange,
+                    &format!(
+                        "encode_dynamic: {} rows of {} do not fit count field {}",
+                        rows, group.name, name
+                    ),
+                ));
+            }
+            overrides.push((name, Value::Unsigned(rows as u64)));
+        }
+    }
+    // a known variant sets its discriminators
+    let variant = match (
+        &schema.variants,
+        schema
+            .variants
+            .as_ref()
+            .and_then(|variants| message.get(&variants.name)),
+    ) {
+        (Some(variants), Some(Value::Variant(name, fields))) => {
+            let case = variants.get(name).ok_or_else(|| {
+                Error::with_context(
+                    ErrorKind::NotFound,
+                    &format!(
+                        "encode_dynamic: unknown variant {} of {}",
+                        name, schema.name
+                    ),
+                )
+            })?;
+            for (discriminator, value) in variants.discriminators.iter().zip(&case.values) {
+                overrides.push((discriminator, Value::Unsigned(*value)));
+            }
+            Some((case, fields))
+        }
+        _ => None,
+    };
+
+    let mut shift = 0;
+    let mut index = 0;
+    while index < schema.fields.len() {
+        match schema.group_at(index) {
+            Some(group) => {
+                let fields = group.fields(schema);
+                let size = group_size(schema, group)?;
+                for row in rows_of(group) {
+                    shift =
+                        encode_fields(schema, fields, row, &[], shift, bitfield.as_deref_mut())?
+                            + size;
+                }
+                shift -= size;
+                index += fields.len();
+            }
+            None => {
+                shift = encode_fields(
+                    schema,
+                    &schema.fields[index..=index],
+                    &message.fields,
+                    &overrides,
+                    shift,
+                    bitfield.as_deref_mut(),
+                )?;
+                index += 1;
+            }
+        }
+    }
+
+    let mut end = (schema.bit_len() as isize + shift).max(0) as usize;
+    if let Some(variants) = &schema.variants {
+        let start = (variants.start as isize + shift).max(0) as usize;
+        match (variant, message.get(&variants.name)) {
+            (Some((case, fields)), _) => {
+                let after = encode_fields(
+                    schema,
+                    &case.fields,
+                    fields,
+                    &[],
+                    shift,
+                    bitfield.as_deref_mut(),
+                )?;
+                let last = case
+                    .fields
+                    .iter()
+                    .map(|field| field.end + 1)
+                    .max()
+                    .unwrap_or(0);
+                end = end.max((last as isize + after).max(0) as usize);
+            }
+            (None, Some(Value::Bytes(bytes))) if !bytes.is_empty() => {
+                let field = raw_field(schema, variants, start, start + bytes.len() * 8 - 1);
+                if let Some(bitfield) = bitfield {
+                    set_bytes(&field, bytes, 0xFF, bitfield)?;
+                }
+                end = end.max(field.end + 1);
+            }
+            _ => (),
+        }
+    }
+    Ok(end)
+}
+
+// encode fields moved by shift with values taken from values or overrides,
+// returns the shift after the fields
+fn encode_fields(
+    schema: &MessageSchema,
+    fields: &[FieldDescriptor],
+    values: &[DecodedField],
+    overrides: &[(&str, Value)],
+    mut shift: isize,
+    mut bitfield: Option<&mut MutableBitField>,
+) -> Result<isize> {
+    for field in fields {
+        let field = moved(field, shift);
+        let value = match field.field_type {
+            FieldType::Reserved => None,
+            _ => field_value(&field.name, values, overrides),
+        }
+        .unwrap_or(&Value::NotAvailable);
+        // names of indirect lookups depend on the key field
+        let resolved;
+        let value = match (&field.indirect_lookup, value) {
+            (Some(lookup), Value::String(name)) if field.lookup.is_none() => {
+                let key = field_value(&lookup.key, values, overrides).and_then(|key| match key {
+                    Value::String(key) => schema
+                        .get_field(&lookup.key)
+                        .and_then(|field| field.lookup.as_ref())
+                        .and_then(|lookup| lookup.value_of(key)),
+                    _ => key.as_i64().map(|key| key as u64),
+                });
+                match key.and_then(|key| lookup.value_of(key, name)) {
+                    Some(raw) => {
+                        resolved = Value::Lookup(raw, Some(name.clone()));
+                        &resolved
+                    }
+                    None => {
+                        return Err(Error::with_context(
+                            ErrorKind::NotFound,
+                            &format!(
+                                "encode_dynamic: unknown name {} for {} of {}",
+                                name, field.name, schema.name
+                            ),
+                        ))
+                    }
+                }
+            }
+            _ => value,
+        };
+        if field.is_variable() {
+            shift += encoded_size(&field, value) as isize - field.size() as isize;
+        }
+        if let Some(bitfield) = bitfield.as_deref_mut() {
+            encode_field(&field, value, bitfield).map_err(|why| {
+                Error::with_all(
+                    why.kind(),
+                    &format!(
+                        "encode_dynamic: failed to encode field {} of {}",
+                        field.name, schema.name
+                    ),
+                    Box::new(why),
+                )
+            })?;
+        }
+    }
+    Ok(shift)
+}
+
+/// Encode a single field, numeric values that do not fit the field or equal its "not
+/// available" marker are rejected with OutOfRange
+pub fn encode_field(
+    field: &FieldDescriptor,
+    value: &Value,
+    bitfield: &mut MutableBitField,
+) -> Result<()> {
+    field.check()?;
+    let spec = field.spec();
+    match (field.field_type, value) {
+        (FieldType::Reserved, _) => set_bytes(field, &[], 0xFF, bitfield),
+        (FieldType::String, Value::String(text)) => {
+            set_bytes(field, text.as_bytes(), 0xFF, bitfield)
+        }
+        (FieldType::String, Value::NotAvailable) => set_bytes(field, &[], 0xFF, bitfield),
+        (FieldType::Binary, Value::Bytes(bytes)) => set_bytes(field, bytes, 0xFF, bitfield),
+        (FieldType::Binary, Value::NotAvailable) => set_bytes(field, &[], 0xFF, bitfield),
+        (FieldType::StringLz, Value::String(text)) => {
+            let mut bytes = vec![length_byte(field, text.len() + 1)?];
+            bytes.extend_from_slice(text.as_bytes());
+            bytes.push(0);
+            set_variable_bytes(field, &bytes, bitfield)
+        }
+        (FieldType::StringLz, Value::NotAvailable) => set_variable_bytes(field, &[1, 0], bitfield),
+        (FieldType::StringLau, Value::String(text)) => {
+            let mut bytes = vec![length_byte(field, text.len() + 2)?, 1];
+            bytes.extend_from_slice(text.as_bytes());
+            set_variable_bytes(field, &bytes, bitfield)
+        }
+        (FieldType::StringLau, Value::NotAvailable) => set_variable_bytes(field, &[2, 1], bitfield),
+        (FieldType::String, _)
+        | (FieldType::Binary, _)
+        | (FieldType::StringLz, _)
+        | (FieldType::StringLau, _) => Err(Error::with_context(
+            ErrorKind::InvParam,
+            &format!("encode_field: invalid value {:?} for {}", value, field.name),
+        )),
+        (FieldType::Float, Value::NotAvailable) => {
+            bitfield.set_u64(0xFFFF_FFFF, field.start, field.end, field.endianness)
+        }
+        (FieldType::Float, _) => match value.as_f64() {
+            Some(value) => bitfield.set_u64(
+                (value as f32).to_bits() as u64,
+                field.start,
+                field.end,
+                field.endianness,
+            ),
+            None => Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("encode_field: invalid value {:?} for {}", value, field.name),
+            )),
+        },
+        (_, Value::NotAvailable) => spec.set_i64(bitfield, None),
+        (FieldType::Lookup, Value::Lookup(raw, _)) => spec.set_i64(bitfield, Some(*raw as i64)),
+        (FieldType::BitLookup, Value::BitLookup(raw, names)) => {
+            match field
+                .lookup
+                .as_ref()
+                .and_then(|lookup| lookup.flags_of(names))
+            {
+                Some(flags) => spec.set_i64(bitfield, Some((raw | flags) as i64)),
+                None if names.is_empty() => spec.set_i64(bitfield, Some(*raw as i64)),
+                None => Err(Error::with_context(
+                    ErrorKind::NotFound,
+                    &format!(
+                        "encode_field: unknown flag in {:?} for {}",
+                        names, field.name
+                    ),
+                )),
+            }
+        }
+        (FieldType::Lookup, Value::String(name)) => {
+            match field
+                .lookup
+                .as_ref()
+                .and_then(|lookup| lookup.value_of(name))
+            {
+                Some(raw) => spec.set_i64(bitfield, Some(raw as i64)),
+                None => Err(Error::with_context(
+                    ErrorKind::NotFound,
+                    &format!("encode_field: unknown name {} for {}", name, field.name),
+                )),
+            }
+        }
+        _ if field.is_scaled() => match value.as_f64() {
+            Some(value) => spec.set_f64(bitfield, Some(value)),
+            None => Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("encode_field: invalid value {:?} for {}", value, field.name),
+            )),
+        },
+        _ => match value.as_i64() {
+            Some(value) => spec.set_i64(bitfield, Some(value)),
+            None => Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("encode_field: invalid value {:?} for {}", value, field.name),
+            )),
+        },
+    }
+}
+
+// the field moved behind the extra bits of preceding variable length fields and groups
+fn moved(field: &FieldDescriptor, shift: isize) -> Cow<'_, FieldDescriptor> {
+    if shift == 0 {
+        Cow::Borrowed(field)
+    } else {
+        Cow::Owned(FieldDescriptor {
+            start: (field.start as isize + shift) as usize,
+            end: (field.end as isize + shift) as usize,
+            ..field.clone()
+        })
+    }
+}
+
+// declared size of one repetition of group in bits
+fn group_size(schema: &MessageSchema, group: &RepeatingGroup) -> Result<isize> {
+    match group.bit_len(schema) {
+        0 => Err(Error::with_context(
+            ErrorKind::InvParam,
+            &format!(
+                "group_size: group {} of {} has no fields",
+                group.name, schema.name
+            ),
+        )),
+        size => Ok(size as isize),
+    }
+}
+
+// the value of a field to encode, overrides take precedence
+fn field_value<'a>(
+    name: &str,
+    values: &'a [DecodedField],
+    overrides: &'a [(&str, Value)],
+) -> Option<&'a Value> {
+    overrides
+        .iter()
+        .find(|(field, _)| *field == name)
+        .map(|(_, value)| value)
+        .or_else(|| find_value(values, name))
+}
+
+// name the decoded values of indirect lookup fields by their key fields, the key field can
+// follow the field
+fn name_indirect(fields: &[FieldDescriptor], out: &mut [DecodedField]) {
+    for field in fields {
+        let lookup = match (&field.lookup, &field.indirect_lookup) {
+            (None, Some(lookup)) => lookup,
+            _ => continue,
+        };
+        let key = match find_value(out, &lookup.key).and_then(Value::as_i64) {
+            Some(key) => key as u64,
+            None => continue,
+        };
+        if let Some(DecodedField {
+            value: Value::Lookup(value, name @ None),
+            ..
+        }) = out.iter_mut().find(|entry| entry.name == field.name)
+        {
+            *name = lookup.name_of(key, *value).map(|name| name.to_owned());
+        }
+    }
+}
+
+fn find_value<'a>(fields: &'a [DecodedField], name: &str) -> Option<&'a Value> {
+    fields
+        .iter()
+        .find(|field| field.name == name)
+        .map(|field| &field.value)
+}
+
+// size in bits of a variable length field in the payload
+fn variable_size(field: &FieldDescriptor, bitfield: &BitField) -> Result<usize> {
+    let length = bitfield.get_u8(field.start, field.start + 7)? as usize;
+    Ok(match field.field_type {
+        FieldType::StringLz => (length + 1) * 8,
+        _ => length.max(2) * 8,
+    })
+}
+
+// size in bits of a variable length field holding value
+fn encoded_size(field: &FieldDescriptor, value: &Value) -> usize {
+    let length = match value {
+        Value::String(text) => text.len(),
+        _ => 0,
+    };
+    match field.field_type {
+        FieldType::StringLz => (length + 2) * 8,
+        FieldType::StringLau => (length + 2) * 8,
+        _ => field.size(),
+    }
+}
+
+fn decode_variable_string(field: &FieldDescriptor, bitfield: &BitField) -> Result<Value> {
+    let size = variable_size(field, bitfield)?;
+    let bytes = get_bytes(
+        &FieldDescriptor {
+            end: field.start + size - 1,
+            ..field.clone()
+        },
+        bitfield,
+    )?;
+    if field.field_type == FieldType::StringLz {
+        return Ok(Value::String(decode_string(&bytes[1..])));
+    }
+    let text = bytes.get(2..).unwrap_or(&[]);
+    match bytes.get(1) {
+        Some(0) => {
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|unit| u16::from_le_bytes([unit[0], unit[1]]))
+                .take_while(|unit| *unit != 0)
+                .collect();
+            Ok(Value::String(String::from_utf16_lossy(&units)))
+        }
+        _ => Ok(Value::String(decode_string(text))),
+    }
+}
+
+// the length byte of a variable length string, fails for texts that are too long
+fn length_byte(field: &FieldDescriptor, length: usize) -> Result<u8> {
+    u8::try_from(length).map_err(|_| {
+        Error::with_context(
+            ErrorKind::InvParam,
+            &format!("length_byte: text of field {} is too long", field.name),
+        )
+    })
+}
+
+// write the complete bytes of a variable length field starting at its first bit
+fn set_variable_bytes(
+    field: &FieldDescriptor,
+    bytes: &[u8],
+    bitfield: &mut MutableBitField,
+) -> Result<()> {
+    set_bytes(
+        &FieldDescriptor {
+            end: field.start + bytes.len() * 8 - 1,
+            ..field.clone()
+        },
+        bytes,
+        0xFF,
+        bitfield,
+    )
+}
+
+// year, month, day of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+// bytes of a field, a trailing partial byte is returned right aligned
+fn get_bytes(field: &FieldDescriptor, bitfield: &BitField) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(field.size().div_ceil(8));
+    let mut curr = field.start;
+    while curr <= field.end {
+        let last = (curr + 7).min(field.end);
+        bytes.push(match field.endianness {
+            Endianness::Intel => bitfield.get_u64(curr, last, Endianness::Intel)? as u8,
+            _ => bitfield.get_u8(curr, last)?,
+        });
+        curr = last + 1;
+    }
+    Ok(bytes)
+}
+
+// write bytes into a field, the remaining space is filled with padding
+fn set_bytes(
+    field: &FieldDescriptor,
+    bytes: &[u8],
+    padding: u8,
+    bitfield: &mut MutableBitField,
+) -> Result<()> {
+    if bytes.len() > field.size().div_ceil(8) {
+        return Err(Error::with_context(
+            ErrorKind::InvParam,
+            &format!(
+                "set_bytes: {} bytes do not fit into field {} of {} bits",
+                bytes.len(),
+                field.name,
+                field.size()
+            ),
+        ));
+    }
+    let mut curr = field.start;
+    let mut index = 0;
+    while curr <= field.end {
+        let last = (curr + 7).min(field.end);
+        let byte = *bytes.get(index).unwrap_or(&padding);
+        match field.endianness {
+            Endianness::Intel => {
+                bitfield.set_u64_intel(byte as u64 & ((1 << (last - curr + 1)) - 1), curr, last)?
+            }
+            _ => bitfield.set_u8(byte, curr, last)?,
+        }
+        curr = last + 1;
+        index += 1;
+    }
+    Ok(())
+}
+
+fn decode_string(bytes: &[u8]) -> String {
+    let end = bytes
+        .iter()
+        .rposition(|byte| !matches!(byte, 0x00 | 0xFF | b'@' | b' '))
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{IndirectLookup, Lookup, Variant};
+
+    fn schema() -> MessageSchema {
+        MessageSchema {
+            length: Some(8),
+            ..MessageSchema::new(130306, "Wind Data")
+        }
+        .with_field(FieldDescriptor::new("sid", 0, 7, FieldType::Unsigned))
+        .with_field(FieldDescriptor {
+            resolution: 0.01,
+            unit: Some("m/s".to_owned()),
+            ..FieldDescriptor::new("speed", 8, 23, FieldType::Unsigned)
+        })
+        .with_field(FieldDescriptor {
+            resolution: 0.0001,
+            unit: Some("rad".to_owned()),
+            ..FieldDescriptor::new("angle", 24, 39, FieldType::Unsigned)
+        })
+        .with_field(FieldDescriptor {
+            lookup: Some(Lookup::from_pairs(
+                "WIND_REFERENCE",
+                &[(0, "True (ground referenced to North)"), (2, "Apparent")],
+            )),
+            ..FieldDescriptor::new("reference", 45, 47, FieldType::Lookup)
+        })
+        .with_field(FieldDescriptor::new(
+            "reserved",
+            40,
+            44,
+            FieldType::Reserved,
+        ))
+        .with_field(FieldDescriptor::new("tag", 48, 63, FieldType::String))
+    }
+
+    #[test]
+    fn test_decode_dynamic() {
+        let data = [0x01, 0xE8, 0x03, 0xFF, 0xFF, 0xFA, b'A', 0xFF];
+        let message = decode_dynamic(&schema(), &BitField::new(&data)).unwrap();
+        assert_eq!(message.fields.len(), 5);
+        assert_eq!(message.fields[0].value, Value::Unsigned(1));
+        assert_eq!(message.fields[1].value, Value::Float(10.0));
+        assert_eq!(message.fields[1].unit.as_deref(), Some("m/s"));
+        assert_eq!(message.get("angle"), Some(&Value::NotAvailable));
+        assert_eq!(
+            message.get("reference"),
+            Some(&Value::Lookup(2, Some("Apparent".to_owned())))
+        );
+        assert_eq!(message.get("tag").unwrap().as_str(), Some("A"));
+        assert!(decode_dynamic(&schema(), &BitField::new(&data[..4])).is_err());
+    }
+
+    #[test]
+    fn test_encode_dynamic() {
+        let message = DecodedMessage::new(130306, "Wind Data")
+            .with_value("sid", Value::Unsigned(1))
+            .with_value("speed", Value::Float(10.0))
+            .with_value("reference", Value::String("Apparent".to_owned()))
+            .with_value("tag", Value::String("A".to_owned()));
+        let data = encode_dynamic_to_vec(&schema(), &message).unwrap();
+        assert_eq!(data, [0x01, 0xE8, 0x03, 0xFF, 0xFF, 0xFA, b'A', 0xFF]);
+        let decoded = decode_dynamic(&schema(), &BitField::new(&data)).unwrap();
+        let encoded = encode_dynamic_to_vec(&schema(), &decoded).unwrap();
+        assert_eq!(encoded, data);
+
+        let message = message.with_value("angle", Value::String("north".to_owned()));
+        assert!(encode_dynamic_to_vec(&schema(), &message).is_err());
+    }
+
+    #[test]
+    fn test_encode_out_of_range() {
+        let mut data = [0xFF; 8];
+        let mut bitfield = MutableBitField::new(&mut data);
+        let byte = FieldDescriptor::new("byte", 0, 7, FieldType::Unsigned);
+        let signed = FieldDescriptor::new("signed", 8, 15, FieldType::Signed);
+        let lookup = FieldDescriptor::new("lookup", 16, 18, FieldType::Lookup);
+        let flags = FieldDescriptor::new("flags", 24, 27, FieldType::BitLookup);
+        let scaled = FieldDescriptor {
+            resolution: 0.01,
+            ..FieldDescriptor::new("scaled", 32, 47, FieldType::Unsigned)
+        };
+        let rejected = [
+            (&byte, Value::Unsigned(300)),
+            (&byte, Value::Unsigned(255)),
+            (&byte, Value::Signed(-1)),
+            (&signed, Value::Signed(-129)),
+            (&signed, Value::Signed(127)),
+            (&lookup, Value::Lookup(7, None)),
+            (&lookup, Value::Lookup(8, None)),
+            (&flags, Value::BitLookup(16, Vec::new())),
+            (&scaled, Value::Float(655.35)),
+            (&scaled, Value::Float(-0.01)),
+        ];
+        for (field, value) in rejected.iter() {
+            let result = encode_field(field, value, &mut bitfield);
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::OutOfRange);
+        }
+        encode_field(&byte, &Value::Unsigned(254), &mut bitfield).unwrap();
+        encode_field(&signed, &Value::Signed(-128), &mut bitfield).unwrap();
+        encode_field(&lookup, &Value::Lookup(6, None), &mut bitfield).unwrap();
+        encode_field(&flags, &Value::BitLookup(15, Vec::new()), &mut bitfield).unwrap();
+        encode_field(&scaled, &Value::Float(655.34), &mut bitfield).unwrap();
+        assert_eq!(data[..6], [0xFE, 0x80, 0xDF, 0xFF, 0xFE, 0xFF]);
+    }
+
+    #[test]
+    fn test_encode_long_strings() {
+        // the length byte counts the text plus the terminator or the encoding byte
+        let mut data = [0xFF; 300];
+        for (field_type, extra) in [(FieldType::StringLz, 1), (FieldType::StringLau, 2)].iter() {
+            let field = FieldDescriptor::new("text", 0, 7, *field_type);
+            let longest = 255 - extra;
+            let text = Value::String("x".repeat(longest));
+            encode_field(&field, &text, &mut MutableBitField::new(&mut data)).unwrap();
+            assert_eq!(data[0], 255);
+            assert_eq!(decode_field(&field, &BitField::new(&data)).unwrap(), text);
+            for len in longest + 1..=256 {
+                let text = Value::String("x".repeat(len));
+                let result = encode_field(&field, &text, &mut MutableBitField::new(&mut data));
+                assert_eq!(result.unwrap_err().kind(), ErrorKind::InvParam);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookups() {
+        // the key of the indirect lookup follows it
+        let schema = MessageSchema::new(60928, "lookups")
+            .with_field(FieldDescriptor {
+                endianness: Endianness::Intel,
+                indirect_lookup: Some(IndirectLookup::from_triples(
+                    "DEVICE_FUNCTION",
+                    "class",
+                    &[(130, 25, "PC Gateway"), (140, 25, "Autopilot")],
+                )),
+                ..FieldDescriptor::new("function", 0, 7, FieldType::Lookup)
+            })
+            .with_field(FieldDescriptor {
+                endianness: Endianness::Intel,
+                lookup: Some(Lookup::from_pairs(
+                    "DEVICE_CLASS",
+                    &[(130, "Communication"), (140, "Steering")],
+                )),
+                ..FieldDescriptor::new("class", 8, 15, FieldType::Lookup)
+            })
+            .with_field(FieldDescriptor {
+                endianness: Endianness::Intel,
+                lookup: Some(Lookup::from_pairs(
+                    "ENGINE_STATUS",
+                    &[(0, "Check Engine"), (2, "Low Oil")],
+                )),
+                ..FieldDescriptor::new("status", 16, 23, FieldType::BitLookup)
+            });
+        let data = [25, 130, 0x85];
+        let message = decode_dynamic(&schema, &BitField::new(&data)).unwrap();
+        assert_eq!(
+            message.get("function"),
+            Some(&Value::Lookup(25, Some("PC Gateway".to_owned())))
+        );
+        assert_eq!(
+            message.get("class").unwrap().as_str(),
+            Some("Communication")
+        );
+        // unknown bits are kept in the value
+        assert_eq!(
+            message.get("status"),
+            Some(&Value::BitLookup(
+                0x85,
+                vec!["Check Engine".to_owned(), "Low Oil".to_owned()]
+            ))
+        );
+        assert_eq!(encode_dynamic_to_vec(&schema, &message).unwrap(), data);
+
+        // names are accepted on encode
+        let message = DecodedMessage::new(60928, "lookups")
+            .with_value("function", Value::String("PC Gateway".to_owned()))
+            .with_value("class", Value::String("Communication".to_owned()))
+            .with_value(
+                "status",
+                Value::BitLookup(0x80, vec!["Low Oil".to_owned(), "Check Engine".to_owned()]),
+            );
+        assert_eq!(encode_dynamic_to_vec(&schema, &message).unwrap(), data);
+        let message = DecodedMessage::new(60928, "lookups")
+            .with_value("function", Value::String("PC Gateway".to_owned()))
+            .with_value("class", Value::Unsigned(140));
+        assert_eq!(
+            encode_dynamic_to_vec(&schema, &message).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        let message = DecodedMessage::new(60928, "lookups")
+            .with_value("status", Value::BitLookup(0, vec!["Fire".to_owned()]));
+        assert_eq!(
+            encode_dynamic_to_vec(&schema, &message).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+
+        // unknown values stay numeric
+        let message = decode_dynamic(&schema, &BitField::new(&[60, 140, 0x00])).unwrap();
+        assert_eq!(message.get("function"), Some(&Value::Lookup(60, None)));
+        assert_eq!(message.get("status").unwrap().to_string(), "0x0");
+    }
+
+    #[test]
+    fn test_repeating_groups() {
+        // a count, a group of two fields and a trailing field
+        let schema = MessageSchema::new(1, "groups")
+            .with_field(FieldDescriptor::new("count", 0, 7, FieldType::Unsigned))
+            .with_field(FieldDescriptor::new("id", 8, 15, FieldType::Unsigned))
+            .with_field(FieldDescriptor::new("name", 16, 23, FieldType::StringLz))
+            .with_field(FieldDescriptor::new("end", 24, 31, FieldType::Unsigned))
+            .with_group(RepeatingGroup {
+                count_field: Some("count".to_owned()),
+                ..RepeatingGroup::new("items", 1, 2)
+            });
+        let data = [0x02, 0x01, 0x02, b'A', 0x00, 0x02, 0x01, 0x00, 0x09];
+        let message = decode_dynamic(&schema, &BitField::new(&data)).unwrap();
+        let rows = message.get("items").unwrap().as_group().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][0].value, Value::Unsigned(1));
+        assert_eq!(rows[0][1].value.as_str(), Some("A"));
+        assert_eq!(rows[1][1].value.as_str(), Some(""));
+        assert_eq!(message.get("end"), Some(&Value::Unsigned(9)));
+        assert_eq!(
+            message.get("items").unwrap().to_string(),
+            "[(id: 1, name: A), (id: 2, name: )]"
+        );
+
+        // the count is taken from the rows, the payload shrinks for fewer rows
+        let mut message = message;
+        message.fields[0].value = Value::Unsigned(7);
+        assert_eq!(encode_dynamic_to_vec(&schema, &message).unwrap(), data);
+        message.fields[1].value = Value::Group(Vec::new());
+        assert_eq!(
+            encode_dynamic_to_vec(&schema, &message).unwrap(),
+            [0x00, 0x09]
+        );
+        assert!(decode_dynamic(&schema, &BitField::new(&data[..4])).is_err());
+        // 255 would read as "not available", 256 does not fit
+        for count in [255, 256].iter() {
+            let row = vec![
+                DecodedField::new("id", Value::Unsigned(1)),
+                DecodedField::new("name", Value::String(String::new())),
+            ];
+            message.fields[1].value = Value::Group(vec![row; *count]);
+            assert_eq!(
+                encode_dynamic_to_vec(&schema, &message).unwrap_err().kind(),
+                ErrorKind::OutOfRange
+            );
+        }
+
+        // without a count field the group fills the payload
+        let schema = MessageSchema::new(126464, "pgnList")
+            .with_field(FieldDescriptor::new("function", 0, 7, FieldType::Unsigned))
+            .with_field(FieldDescriptor::new("pgn", 8, 31, FieldType::Unsigned))
+            .with_group(RepeatingGroup::new("pgns", 1, 1));
+        let data = [0x00, 0x00, 0xEE, 0x00, 0x00, 0xEA, 0x00, 0x00];
+        let message = decode_dynamic(&schema, &BitField::new(&data)).unwrap();
+        let rows = message.get("pgns").unwrap().as_group().unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1][0].value, Value::Unsigned(0xEA00));
+        assert_eq!(encode_dynamic_to_vec(&schema, &message).unwrap(), data[..7]);
+    }
+
+    #[test]
+    fn test_variants() {
+        let intel = |name: &str, start: usize, end: usize| FieldDescriptor {
+            endianness: Endianness::Intel,
+            ..FieldDescriptor::new(name, start, end, FieldType::Unsigned)
+        };
+        // a proprietary message switching on manufacturer and industry
+        let schema = MessageSchema::new(65280, "proprietary")
+            .with_field(intel("manufacturerCode", 0, 10))
+            .with_field(FieldDescriptor {
+                endianness: Endianness::Intel,
+                ..FieldDescriptor::new("reserved", 11, 12, FieldType::Reserved)
+            })
+            .with_field(intel("industryCode", 13, 15))
+            .with_variants(
+                Variants::new("data", &["manufacturerCode", "industryCode"], 16)
+                    .with_variant(
+                        Variant::new("maretron", &[137, 4])
+                            .with_field(intel("command", 16, 23))
+                            .with_field(intel("level", 24, 39)),
+                    )
+                    .with_variant(
+                        Variant::new("garmin", &[229, 4]).with_field(intel("mode", 16, 23)),
+                    ),
+            );
+        let data = [0x89, 0x98, 0x02, 0x10, 0x27];
+        let message = decode_dynamic(&schema, &BitField::new(&data)).unwrap();
+        let (name, fields) = message.get("data").unwrap().as_variant().unwrap();
+        assert_eq!(name, "maretron");
+        assert_eq!(fields[1].value, Value::Unsigned(10000));
+        assert_eq!(
+            message.get("data").unwrap().to_string(),
+            "maretron(command: 2, level: 10000)"
+        );
+        assert_eq!(encode_dynamic_to_vec(&schema, &message).unwrap(), data);
+
+        // unknown discriminators keep the raw bytes
+        let data = [0x13, 0x99, 0x01, 0x02, 0x03];
+        let message = decode_dynamic(&schema, &BitField::new(&data)).unwrap();
+        assert_eq!(message.get("manufacturerCode"), Some(&Value::Unsigned(275)));
+        assert_eq!(
+            message.get("data"),
+            Some(&Value::Bytes(vec![0x01, 0x02, 0x03]))
+        );
+        assert_eq!(encode_dynamic_to_vec(&schema, &message).unwrap(), data);
+
+        // encoding a variant sets the discriminators
+        let message = DecodedMessage::new(65280, "proprietary").with_value(
+            "data",
+            Value::Variant(
+                "garmin".to_owned(),
+                vec![DecodedField::new("mode", Value::Unsigned(3))],
+            ),
+        );
+        assert_eq!(
+            encode_dynamic_to_vec(&schema, &message).unwrap(),
+            [0xE5, 0x98, 0x03]
+        );
+        let message = message.with_value("data", Value::Variant("navico".to_owned(), Vec::new()));
+        let message = DecodedMessage {
+            fields: message.fields[1..].to_vec(),
+            ..message
+        };
+        assert_eq!(
+            encode_dynamic_to_vec(&schema, &message).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+    }
+}
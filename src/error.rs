@@ -1,4 +1,4 @@
-#![allow(dead_code)]
+#![allow(dead_code, clippy::single_match, clippy::to_string_in_format_args)]
 
 use std::error;
 use std::fmt::{self, Display, Formatter};
@@ -14,6 +14,7 @@ pub enum ErrorKind {
     OutOfRange,
     InvRange,
     NotImpl,
+    InvFormat,
 }
 
 impl Display for ErrorKind {
@@ -26,6 +27,7 @@ impl Display for ErrorKind {
             Self::OutOfRange => "An offset was out of range",
             Self::InvRange => "Invalid range",
             Self::NotImpl => "Not yet implemented",
+            Self::InvFormat => "Invalid data format",
         };
         write!(f, "{}", output)
     }
@@ -0,0 +1,697 @@
+use crate::can::{CanFrame, N2kHeader, BROADCAST};
+use crate::clock::Clock;
+use crate::error::{Error, ErrorKind, Result};
+use crate::fast_packet::Packet;
+use crate::iso_tp::{control_frame, data_frame, AbortReason, Timeouts, TpEvent, MAX_TP_LEN};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+// ISO 11783-3 extended transport protocol for addressed messages of 1786 bytes up to 2^24 - 1
+// packets. It works like the RTS/CTS mode of the transport protocol (see iso_tp) but packet
+// numbers have 24 bits: before each window of up to 255 data packets (ETP.DT, PGN 50944) the
+// sender names the packet offset the sequence numbers of the window count from with a DPO
+// frame. ETP.CM frames (PGN 51200) carry the control byte first and the transferred PGN in
+// bytes 5 to 7:
+//
+//   20   RTS   size (4)
+//   21   CTS   packets, next packet (3)
+//   22   DPO   packets, packet offset (3)
+//   23   EOMA  size (4)
+//   255  abort reason, 0xFF, 0xFF, 0xFF
+
+/// Connection management PGN
+pub const ETP_CM: u32 = 51200;
+/// Data transfer PGN
+pub const ETP_DT: u32 = 50944;
+/// Largest extended transport protocol payload
+pub const MAX_ETP_LEN: usize = 0xFF_FFFF * 7;
+/// Number of retransmit requests before a session is aborted
+const MAX_RETRANSMITS: u8 = 2;
+
+const RTS: u8 = 20;
+const CTS: u8 = 21;
+const DPO: u8 = 22;
+const EOMA: u8 = 23;
+const ABORT: u8 = 255;
+
+fn u24(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0])
+}
+
+fn u24_bytes(value: u32) -> [u8; 3] {
+    let bytes = value.to_le_bytes();
+    [bytes[0], bytes[1], bytes[2]]
+}
+
+#[derive(Debug)]
+struct RxSession {
+    header: N2kHeader,
+    size: usize,
+    packets: u32,
+    /// next packet number, counting from 1
+    next: u32,
+    /// packets granted by the last CTS
+    granted: u8,
+    /// offset and packet count of the current window once its DPO arrived
+    dpo: Option<(u32, u8)>,
+    data: Vec<u8>,
+    deadline: Duration,
+}
+
+#[derive(Debug)]
+struct TxSession {
+    pgn: u32,
+    data: Vec<u8>,
+    packets: u32,
+    /// packets sent so far
+    sent: u32,
+    retransmits: u8,
+    /// T3 while waiting for CTS or EOMA, T4 while the receiver holds the session
+    deadline: Duration,
+}
+
+/// Extended transport protocol sessions of one node
+///
+/// Used like `IsoTransport`: received frames are passed to `push`, frames to send are taken
+/// with `take_frames` and `poll` handles timeouts. The session events are the ones of the
+/// transport protocol.
+#[derive(Debug)]
+pub struct ExtendedTransport<C: Clock> {
+    clock: C,
+    address: u8,
+    timeouts: Timeouts,
+    window: u8,
+    /// largest accepted payload
+    max_len: usize,
+    /// by source
+    rx: BTreeMap<u8, RxSession>,
+    /// by destination
+    tx: BTreeMap<u8, TxSession>,
+    frames: Vec<CanFrame>,
+    events: Vec<TpEvent>,
+}
+
+impl<C: Clock> ExtendedTransport<C> {
+    /// Create the sessions of the node at address, granting up to 255 packets per CTS
+    pub fn new(address: u8, clock: C) -> ExtendedTransport<C> {
+        ExtendedTransport {
+            clock,
+            address,
+            timeouts: Timeouts::default(),
+            window: 255,
+            max_len: MAX_ETP_LEN,
+            rx: BTreeMap::new(),
+            tx: BTreeMap::new(),
+            frames: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Set the timeouts, returns self for chaining
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> ExtendedTransport<C> {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Set the packets granted per CTS, returns self for chaining
+    pub fn with_window(mut self, window: u8) -> ExtendedTransport<C> {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Set the largest payload accepted from peers, larger RTS are aborted with Resources,
+    /// returns self for chaining
+    pub fn with_max_len(mut self, max_len: usize) -> ExtendedTransport<C> {
+        self.max_len = max_len;
+        self
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    pub fn set_address(&mut self, address: u8) {
+        self.address = address;
+    }
+
+    /// Whether no session is open
+    pub fn is_idle(&self) -> bool {
+        self.rx.is_empty() && self.tx.is_empty()
+    }
+
+    /// Take the frames to send
+    pub fn take_frames(&mut self) -> Vec<CanFrame> {
+        std::mem::take(&mut self.frames)
+    }
+
+    /// Take the events so far
+    pub fn take_events(&mut self) -> Vec<TpEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Start sending data to destination
+    pub fn send(&mut self, pgn: u32, destination: u8, data: &[u8]) -> Result<()> {
+        if data.len() <= MAX_TP_LEN || data.len() > MAX_ETP_LEN {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "ExtendedTransport::send: invalid payload length {}",
+                    data.len()
+                ),
+            ));
+        }
+        if destination == BROADCAST || self.tx.contains_key(&destination) {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "ExtendedTransport::send: can not open a session to {}",
+                    destination
+                ),
+            ));
+        }
+        let size = (data.len() as u32).to_le_bytes();
+        self.cm(destination, [RTS, size[0], size[1], size[2], size[3]], pgn)?;
+        self.tx.insert(
+            destination,
+            TxSession {
+                pgn,
+                data: data.to_vec(),
+                packets: data.len().div_ceil(7) as u32,
+                sent: 0,
+                retransmits: 0,
+                deadline: self.clock.now() + self.timeouts.t3,
+            },
+        );
+        Ok(())
+    }
+
+    /// Abort the session sending to destination
+    pub fn abort(&mut self, destination: u8, reason: AbortReason) -> Result<()> {
+        if let Some(session) = self.tx.remove(&destination) {
+            self.abort_session(session.pgn, self.address, destination, reason)?;
+        }
+        Ok(())
+    }
+
+    /// Handle a received frame, returns the message it completes
+    pub fn push(&mut self, frame: &CanFrame) -> Result<Option<Packet>> {
+        self.poll()?;
+        let header = match frame.header() {
+            Ok(header) => header,
+            Err(_) => return Ok(None),
+        };
+        let data = frame.data();
+        if header.destination != self.address || data.len() != 8 {
+            return Ok(None);
+        }
+        match header.pgn {
+            ETP_CM => {
+                self.handle_cm(&header, data)?;
+                Ok(None)
+            }
+            ETP_DT => self.handle_dt(header.source, data),
+            _ => Ok(None),
+        }
+    }
+
+    /// Abort timed out sessions
+    pub fn poll(&mut self) -> Result<()> {
+        let now = self.clock.now();
+        let expired: Vec<(u8, u32)> = self
+            .tx
+            .iter()
+            .filter(|(_, session)| session.deadline < now)
+            .map(|(destination, session)| (*destination, session.pgn))
+            .collect();
+        for (destination, pgn) in expired {
+            self.tx.remove(&destination);
+            self.abort_session(pgn, self.address, destination, AbortReason::Timeout)?;
+        }
+        let expired: Vec<(u8, u32)> = self
+            .rx
+            .iter()
+            .filter(|(_, session)| session.deadline < now)
+            .map(|(source, session)| (*source, session.header.pgn))
+            .collect();
+        for (source, pgn) in expired {
+            self.rx.remove(&source);
+            self.abort_session(pgn, source, self.address, AbortReason::Timeout)?;
+        }
+        Ok(())
+    }
+
+    fn handle_cm(&mut self, header: &N2kHeader, data: &[u8]) -> Result<()> {
+        let now = self.clock.now();
+        let source = header.source;
+        let pgn = u24(&data[5..]);
+        match data[0] {
+            RTS => {
+                let size = u32::from_le_bytes([data[1], data[2], data[3], data[4]]) as usize;
+                if size <= MAX_TP_LEN || size > MAX_ETP_LEN.min(self.max_len) {
+                    return self.abort_session(pgn, source, self.address, AbortReason::Resources);
+                }
+                // a repeated RTS restarts the session, a new PGN has to wait
+                if self
+                    .rx
+                    .get(&source)
+                    .is_some_and(|session| session.header.pgn != pgn)
+                {
+                    return self.cm(
+                        source,
+                        [
+                            ABORT,
+                            AbortReason::AlreadyInSession.into(),
+                            0xFF,
+                            0xFF,
+                            0xFF,
+                        ],
+                        pgn,
+                    );
+                }
+                let mut session = RxSession {
+                    header: N2kHeader {
+                        priority: header.priority,
+                        pgn,
+                        source,
+                        destination: self.address,
+                    },
+                    size,
+                    packets: size.div_ceil(7) as u32,
+                    next: 1,
+                    granted: 0,
+                    dpo: None,
+                    // grows by one window per CTS, the size is only a claim of the peer
+                    data: Vec::new(),
+                    deadline: now,
+                };
+                self.grant(source, &mut session)?;
+                self.rx.insert(source, session);
+            }
+            DPO => {
+                let session = match self.rx.get_mut(&source) {
+                    Some(session) => session,
+                    None => return Ok(()),
+                };
+                let (count, offset) = (data[1], u24(&data[2..5]));
+                let reason = if session.header.pgn != pgn {
+                    Some(AbortReason::BadDpoPgn)
+                } else if session.dpo.is_some() {
+                    Some(AbortReason::UnexpectedDpo)
+                } else if count == 0 || count > session.granted {
+                    Some(AbortReason::DpoExceedsCts)
+                } else if offset + 1 != session.next {
+                    Some(AbortReason::BadDpoOffset)
+                } else {
+                    None
+                };
+                if let Some(reason) = reason {
+                    let pgn = session.header.pgn;
+                    self.rx.remove(&source);
+                    return self.abort_session(pgn, source, self.address, reason);
+                }
+                session.dpo = Some((offset, count));
+                session.deadline = now + self.timeouts.t1;
+            }
+            CTS => {
+                let session = match self.tx.get_mut(&source) {
+                    Some(session) => session,
+                    None => return Ok(()),
+                };
+                let (count, next) = (data[1], u24(&data[2..5]));
+                let reason = if session.pgn != pgn {
+                    Some(AbortReason::UnexpectedCtsPgn)
+                } else if count > 0 && (next == 0 || next + count as u32 - 1 > session.packets) {
+                    Some(AbortReason::CtsExceedsSize)
+                } else if count > 0 && next <= session.sent {
+                    session.retransmits += 1;
+                    if session.retransmits > MAX_RETRANSMITS {
+                        Some(AbortReason::MaxRetransmits)
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                };
+                if let Some(reason) = reason {
+                    let pgn = session.pgn;
+                    self.tx.remove(&source);
+                    return self.abort_session(pgn, self.address, source, reason);
+                }
+                if count == 0 {
+                    session.deadline = now + self.timeouts.t4;
+                    return Ok(());
+                }
+                session.deadline = now + self.timeouts.t3;
+                session.sent = session.sent.max(next + count as u32 - 1);
+                let offset = u24_bytes(next - 1);
+                self.cm(source, [DPO, count, offset[0], offset[1], offset[2]], pgn)?;
+                let session = &self.tx[&source];
+                for sequence in 1..=count {
+                    let start = (next + sequence as u32 - 2) as usize * 7;
+                    let frame = data_frame(
+                        ETP_DT,
+                        self.address,
+                        source,
+                        sequence,
+                        &session.data[start..],
+                    )?;
+                    self.frames.push(frame);
+                }
+            }
+            EOMA if self
+                .tx
+                .get(&source)
+                .is_some_and(|session| session.pgn == pgn) =>
+            {
+                self.tx.remove(&source);
+                self.events.push(TpEvent::Sent {
+                    pgn,
+                    destination: source,
+                });
+            }
+            ABORT => {
+                let reason = AbortReason::from(data[1]);
+                if self
+                    .rx
+                    .get(&source)
+                    .is_some_and(|session| session.header.pgn == pgn)
+                {
+                    self.rx.remove(&source);
+                    self.events.push(TpEvent::Aborted {
+                        pgn,
+                        source,
+                        destination: self.address,
+                        reason,
+                        local: false,
+                    });
+                }
+                if self
+                    .tx
+                    .get(&source)
+                    .is_some_and(|session| session.pgn == pgn)
+                {
+                    self.tx.remove(&source);
+                    self.events.push(TpEvent::Aborted {
+                        pgn,
+                        source: self.address,
+                        destination: source,
+                        reason,
+                        local: false,
+                    });
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn handle_dt(&mut self, source: u8, data: &[u8]) -> Result<Option<Packet>> {
+        let now = self.clock.now();
+        let session = match self.rx.get_mut(&source) {
+            Some(session) => session,
+            None => return Ok(None),
+        };
+        let pgn = session.header.pgn;
+        let (offset, count) = match session.dpo {
+            Some(dpo) => dpo,
+            None => {
+                self.rx.remove(&source);
+                return self
+                    .abort_session(pgn, source, self.address, AbortReason::UnexpectedData)
+                    .map(|_| None);
+            }
+        };
+        let sequence = data[0];
+        let expected = session.next - offset;
+        if sequence as u32 != expected || sequence > count {
+            let reason = if (sequence as u32) < expected {
+                AbortReason::DuplicateSequence
+            } else {
+                AbortReason::BadSequence
+            };
+            self.rx.remove(&source);
+            return self
+                .abort_session(pgn, source, self.address, reason)
+                .map(|_| None);
+        }
+        session.data.extend_from_slice(&data[1..]);
+        session.next += 1;
+        session.deadline = now + self.timeouts.t1;
+        if session.next > session.packets {
+            let mut session = self.rx.remove(&source).unwrap();
+            session.data.truncate(session.size);
+            let size = (session.size as u32).to_le_bytes();
+            self.cm(source, [EOMA, size[0], size[1], size[2], size[3]], pgn)?;
+            return Ok(Some(Packet {
+                header: session.header,
+                data: session.data,
+                timestamp: now,
+            }));
+        }
+        if sequence == count {
+            let mut session = self.rx.remove(&source).unwrap();
+            self.grant(source, &mut session)?;
+            self.rx.insert(source, session);
+        }
+        Ok(None)
+    }
+
+    /// Send the CTS for the next window of a receive session
+    fn grant(&mut self, source: u8, session: &mut RxSession) -> Result<()> {
+        let remaining = session.packets - session.next + 1;
+        session.granted = remaining.min(self.window as u32) as u8;
+        session.data.reserve(session.granted as usize * 7);
+        session.dpo = None;
+        session.deadline = self.clock.now() + self.timeouts.t2;
+        let next = u24_bytes(session.next);
+        self.cm(
+            source,
+            [CTS, session.granted, next[0], next[1], next[2]],
+            session.header.pgn,
+        )
+    }
+
+    /// Report an aborted session and tell the peer
+    fn abort_session(
+        &mut self,
+        pgn: u32,
+        source: u8,
+        destination: u8,
+        reason: AbortReason,
+    ) -> Result<()> {
+        self.events.push(TpEvent::Aborted {
+            pgn,
+            source,
+            destination,
+            reason,
+            local: true,
+        });
+        let peer = if source == self.address {
+            destination
+        } else {
+            source
+        };
+        self.cm(peer, [ABORT, reason.into(), 0xFF, 0xFF, 0xFF], pgn)
+    }
+
+    fn cm(&mut self, destination: u8, control: [u8; 5], pgn: u32) -> Result<()> {
+        let frame = control_frame(ETP_CM, self.address, destination, control, pgn)?;
+        self.frames.push(frame);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    /// A peer that sends hand made frames to the node at 0x20 and collects its answers
+    struct Peer {
+        node: ExtendedTransport<ManualClock>,
+    }
+
+    impl Peer {
+        fn new(clock: &ManualClock) -> Peer {
+            Peer {
+                node: ExtendedTransport::new(0x20, clock.clone()).with_window(4),
+            }
+        }
+
+        fn cm(&mut self, control: [u8; 5]) -> Vec<CanFrame> {
+            let frame = control_frame(ETP_CM, 0x10, 0x20, control, 0xEF00).unwrap();
+            assert!(self.node.push(&frame).unwrap().is_none());
+            self.node.take_frames()
+        }
+
+        fn dt(&mut self, sequence: u8) -> Option<Packet> {
+            let frame = data_frame(ETP_DT, 0x10, 0x20, sequence, &[sequence; 7]).unwrap();
+            self.node.push(&frame).unwrap()
+        }
+
+        fn aborted(&mut self) -> AbortReason {
+            match self.node.take_events()[..] {
+                [TpEvent::Aborted { reason, .. }] => reason,
+                ref events => panic!("unexpected {:?}", events),
+            }
+        }
+    }
+
+    #[test]
+    fn test_etp() {
+        let clock = ManualClock::new();
+        let mut sender = ExtendedTransport::new(0x10, clock.clone());
+        let mut receiver = ExtendedTransport::new(0x20, clock.clone()).with_window(100);
+        let data: Vec<u8> = (0..2000).map(|i| i as u8).collect();
+        sender.send(0xEF00, 0x20, &data).unwrap();
+        assert!(sender.send(0xEF00, 0x20, &data).is_err());
+        assert!(sender.send(0xEF00, 0x21, &data[..1785]).is_err());
+        let rts = sender.take_frames();
+        assert_eq!(rts[0].data(), [20, 0xD0, 0x07, 0, 0, 0x00, 0xEF, 0x00]);
+        assert_eq!(rts[0].header().unwrap().pgn, ETP_CM);
+
+        // 286 packets in windows of 100, 100 and 86
+        let mut frames = rts;
+        let mut packets = Vec::new();
+        let mut dpos = Vec::new();
+        while !frames.is_empty() {
+            for frame in &frames {
+                packets.extend(receiver.push(frame).unwrap());
+            }
+            clock.advance(ms(10));
+            for frame in receiver.take_frames() {
+                sender.push(&frame).unwrap();
+            }
+            frames = sender.take_frames();
+            dpos.extend(
+                frames
+                    .iter()
+                    .filter(|frame| frame.header().unwrap().pgn == ETP_CM)
+                    .map(|frame| frame.data()[..5].to_vec()),
+            );
+        }
+        assert_eq!(
+            dpos,
+            vec![
+                vec![22, 100, 0, 0, 0],
+                vec![22, 100, 100, 0, 0],
+                vec![22, 86, 200, 0, 0]
+            ]
+        );
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].data, data);
+        assert_eq!(packets[0].header.pgn, 0xEF00);
+        assert_eq!(
+            sender.take_events(),
+            vec![TpEvent::Sent {
+                pgn: 0xEF00,
+                destination: 0x20
+            }]
+        );
+        assert!(sender.is_idle() && receiver.is_idle());
+    }
+
+    #[test]
+    fn test_etp_peer() {
+        let clock = ManualClock::new();
+        let mut peer = Peer::new(&clock);
+        let rts = [20, 0x00, 0x08, 0, 0];
+
+        // a window of 4 packets from packet 1, then packet 5
+        let cts = peer.cm(rts);
+        assert_eq!(cts[0].data(), [21, 4, 1, 0, 0, 0x00, 0xEF, 0x00]);
+        assert!(peer.cm([22, 4, 0, 0, 0]).is_empty());
+        for sequence in 1..=3 {
+            assert!(peer.dt(sequence).is_none());
+        }
+        assert!(peer.dt(4).is_none());
+        let cts = peer.node.take_frames();
+        assert_eq!(cts[0].data()[..5], [21, 4, 5, 0, 0]);
+
+        // a wrong offset
+        let abort = peer.cm([22, 4, 0, 0, 0]);
+        assert_eq!(abort[0].data()[..2], [255, 12]);
+        assert_eq!(peer.aborted(), AbortReason::BadDpoOffset);
+
+        // more packets than granted, data before the DPO, a bad sequence
+        peer.cm(rts);
+        peer.cm([22, 5, 0, 0, 0]);
+        assert_eq!(peer.aborted(), AbortReason::DpoExceedsCts);
+        peer.cm(rts);
+        peer.dt(1);
+        assert_eq!(peer.aborted(), AbortReason::UnexpectedData);
+        peer.cm(rts);
+        peer.cm([22, 4, 0, 0, 0]);
+        peer.dt(2);
+        assert_eq!(peer.aborted(), AbortReason::BadSequence);
+        assert_eq!(peer.node.take_frames()[0].data()[..2], [255, 7]);
+
+        // too small for ETP, too large for the node
+        assert_eq!(peer.cm([20, 0xF9, 0x06, 0, 0])[0].data()[..2], [255, 2]);
+        assert_eq!(peer.aborted(), AbortReason::Resources);
+        peer.node.max_len = 4096;
+        assert_eq!(peer.cm([20, 0x01, 0x10, 0, 0])[0].data()[..2], [255, 2]);
+        assert_eq!(peer.aborted(), AbortReason::Resources);
+        assert_eq!(peer.cm([20, 0x00, 0x10, 0, 0])[0].data()[0], 21);
+        peer.cm([255, 2, 0xFF, 0xFF, 0xFF]);
+        peer.node.take_events();
+
+        // the largest size only reserves the granted window
+        peer.node.max_len = MAX_ETP_LEN;
+        peer.cm([20, 0xF9, 0xFF, 0xFF, 0x06]);
+        assert!(peer.node.rx[&0x10].data.capacity() < 64);
+        peer.cm([255, 2, 0xFF, 0xFF, 0xFF]);
+        peer.node.take_events();
+
+        // T2 and T1
+        peer.cm(rts);
+        clock.advance(ms(1300));
+        peer.node.poll().unwrap();
+        assert_eq!(peer.aborted(), AbortReason::Timeout);
+        peer.node.take_frames();
+        peer.cm(rts);
+        peer.cm([22, 4, 0, 0, 0]);
+        peer.dt(1);
+        clock.advance(ms(800));
+        peer.node.poll().unwrap();
+        assert_eq!(peer.aborted(), AbortReason::Timeout);
+        assert_eq!(peer.node.take_frames()[0].data()[..2], [255, 3]);
+
+        // the peer aborts
+        peer.cm(rts);
+        peer.cm([255, 2, 0xFF, 0xFF, 0xFF]);
+        assert!(peer.node.is_idle());
+        assert_eq!(peer.node.take_events().len(), 1);
+
+        // as receiver of our data: CTS beyond the size, hold and T4
+        let mut node = ExtendedTransport::new(0x20, clock.clone());
+        node.send(0xEF00, 0x10, &[0; 2000]).unwrap();
+        node.take_frames();
+        let cts = control_frame(ETP_CM, 0x10, 0x20, [21, 10, 0x18, 0x01, 0], 0xEF00);
+        node.push(&cts.unwrap()).unwrap();
+        assert!(matches!(
+            node.take_events()[..],
+            [TpEvent::Aborted {
+                reason: AbortReason::CtsExceedsSize,
+                ..
+            }]
+        ));
+        node.send(0xEF00, 0x10, &[0; 2000]).unwrap();
+        let hold = control_frame(ETP_CM, 0x10, 0x20, [21, 0, 1, 0, 0], 0xEF00).unwrap();
+        node.push(&hold).unwrap();
+        clock.advance(ms(1100));
+        node.poll().unwrap();
+        assert!(matches!(
+            node.take_events()[..],
+            [TpEvent::Aborted {
+                reason: AbortReason::Timeout,
+                local: true,
+                ..
+            }]
+        ));
+    }
+}
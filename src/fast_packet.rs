@@ -0,0 +1,589 @@
+use crate::bitfield::BitField;
+use crate::can::{CanFrame, N2kHeader};
+use crate::error::{Error, ErrorKind, Result};
+use crate::schema::{MessageSchema, Transport};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+// NMEA 2000 fast packets carry up to 223 bytes in up to 32 frames. The first byte of every
+// frame holds a 3 bit sequence counter and a 5 bit frame counter, the first frame carries the
+// total length in its second byte followed by 6 data bytes, all other frames carry 7 data
+// bytes:
+//
+//     frame 0:  SSS00000 LLLLLLLL D0 D1 D2 D3 D4 D5
+//     frame n:  SSSnnnnn D D D D D D D
+//
+// Frames of one packet are collected per source, PGN and sequence counter, so packets of
+// different sources or consecutive packets of one source can interleave. On transmit the last
+// frame is padded with 0xFF and the sequence counter advances per source and PGN.
+
+/// Largest fast packet payload
+pub const MAX_FAST_PACKET_LEN: usize = 223;
+/// Data bytes in the first frame
+const FIRST_FRAME_LEN: usize = 6;
+/// Data bytes in the following frames
+const FRAME_LEN: usize = 7;
+
+/// Get the number of frames needed for a payload of len bytes
+pub fn frame_count(len: usize) -> usize {
+    if len <= FIRST_FRAME_LEN {
+        1
+    } else {
+        1 + (len - FIRST_FRAME_LEN).div_ceil(FRAME_LEN)
+    }
+}
+
+/// The set of PGNs sent as fast packets
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FastPacketRegistry {
+    pgns: BTreeSet<u32>,
+}
+
+impl FastPacketRegistry {
+    /// Create an empty registry
+    pub fn new() -> FastPacketRegistry {
+        FastPacketRegistry::default()
+    }
+
+    /// Create a registry of the common NMEA 2000 fast packet PGNs including the proprietary
+    /// ranges 126720 and 130816 - 131071
+    pub fn nmea2000() -> FastPacketRegistry {
+        let mut registry = FastPacketRegistry::new();
+        for pgn in [
+            126208, 126464, 126720, 126983, 126984, 126985, 126986, 126987, 126988, 126996, 126998,
+            127233, 127237, 127489, 127496, 127497, 127498, 127503, 127504, 127506, 127507, 127509,
+            127510, 127511, 127512, 127513, 127514, 128275, 128520, 129029, 129038, 129039, 129040,
+            129041, 129044, 129045, 129284, 129285, 129301, 129302, 129538, 129540, 129541, 129542,
+            129545, 129547, 129549, 129551, 129556, 129792, 129793, 129794, 129795, 129796, 129797,
+            129798, 129799, 129800, 129801, 129802, 129803, 129804, 129805, 129806, 129807, 129808,
+            129809, 129810, 130060, 130061, 130064, 130065, 130066, 130067, 130068, 130069, 130070,
+            130071, 130072, 130073, 130074, 130320, 130321, 130322, 130323, 130324, 130567, 130577,
+            130578,
+        ] {
+            registry.insert(pgn);
+        }
+        for pgn in 130816..=131071 {
+            registry.insert(pgn);
+        }
+        registry
+    }
+
+    /// Create a registry of the schemas with fast packet transport
+    pub fn from_schemas<'a, I: IntoIterator<Item = &'a MessageSchema>>(
+        schemas: I,
+    ) -> FastPacketRegistry {
+        schemas
+            .into_iter()
+            .filter(|schema| schema.transport == Transport::FastPacket)
+            .map(|schema| schema.id)
+            .collect()
+    }
+
+    /// Add a PGN, returns self for chaining
+    pub fn with_pgn(mut self, pgn: u32) -> FastPacketRegistry {
+        self.insert(pgn);
+        self
+    }
+
+    pub fn insert(&mut self, pgn: u32) {
+        self.pgns.insert(pgn);
+    }
+
+    pub fn remove(&mut self, pgn: u32) {
+        self.pgns.remove(&pgn);
+    }
+
+    pub fn contains(&self, pgn: u32) -> bool {
+        self.pgns.contains(&pgn)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pgns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pgns.is_empty()
+    }
+}
+
+impl std::iter::FromIterator<u32> for FastPacketRegistry {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> FastPacketRegistry {
+        FastPacketRegistry {
+            pgns: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// A complete message payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    pub header: N2kHeader,
+    pub data: Vec<u8>,
+    /// time of the last frame
+    pub timestamp: Duration,
+}
+
+impl Packet {
+    /// Get a `BitField` over the payload
+    pub fn bitfield(&self) -> BitField<'_> {
+        BitField::new(&self.data)
+    }
+}
+
+/// A discarded fast packet session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionError {
+    /// frames were missing when the session timed out or was replaced by a new first frame,
+    /// length is None if the first frame was not received
+    Incomplete {
+        header: N2kHeader,
+        sequence: u8,
+        length: Option<usize>,
+        missing: Vec<u8>,
+    },
+    /// the frames contradict each other or the length
+    Corrupt {
+        header: N2kHeader,
+        sequence: u8,
+        reason: String,
+    },
+}
+
+#[derive(Debug)]
+struct Session {
+    header: N2kHeader,
+    length: Option<usize>,
+    frames: BTreeMap<u8, Vec<u8>>,
+    updated: Duration,
+}
+
+impl Session {
+    fn missing(&self) -> Vec<u8> {
+        let count = match self.length {
+            Some(length) => frame_count(length) as u8,
+            None => self.frames.keys().next_back().map_or(1, |last| last + 1),
+        };
+        (0..count)
+            .filter(|index| !self.frames.contains_key(index))
+            .collect()
+    }
+}
+
+/// Reassembles fast packets from CAN frames
+///
+/// Frames of PGNs not in the registry are passed through as single frame packets, frames
+/// that are not extended data frames are ignored. Sessions that see no frame for the
+/// timeout are dropped, dropped and corrupt sessions are collected until taken with
+/// `take_errors`.
+#[derive(Debug)]
+pub struct Reassembler {
+    registry: FastPacketRegistry,
+    timeout: Duration,
+    sessions: BTreeMap<(u8, u32, u8), Session>,
+    errors: Vec<SessionError>,
+}
+
+impl Reassembler {
+    /// Create a reassembler with a timeout of 750 ms
+    pub fn new(registry: FastPacketRegistry) -> Reassembler {
+        Reassembler {
+            registry,
+            timeout: Duration::from_millis(750),
+            sessions: BTreeMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Set the timeout, returns self for chaining
+    pub fn with_timeout(mut self, timeout: Duration) -> Reassembler {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn registry(&self) -> &FastPacketRegistry {
+        &self.registry
+    }
+
+    /// Get the number of open sessions
+    pub fn pending(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Take the sessions discarded so far
+    pub fn take_errors(&mut self) -> Vec<SessionError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    /// Add a frame received at now, returns the packet it completes
+    pub fn push(&mut self, frame: &CanFrame, now: Duration) -> Option<Packet> {
+        self.expire(now);
+        let header = frame.header().ok()?;
+        let data = frame.data();
+        if !self.registry.contains(header.pgn) {
+            return Some(Packet {
+                header,
+                data: data.to_vec(),
+                timestamp: now,
+            });
+        }
+        let (sequence, index) = (*data.first()? >> 5, *data.first()? & 0x1F);
+        let key = (header.source, header.pgn, sequence);
+
+        if index == 0 {
+            let length = match data.get(1) {
+                Some(length) if (1..=MAX_FAST_PACKET_LEN).contains(&(*length as usize)) => {
+                    *length as usize
+                }
+                _ => {
+                    self.errors.push(SessionError::Corrupt {
+                        header,
+                        sequence,
+                        reason: format!("invalid length in {:?}", data),
+                    });
+                    return None;
+                }
+            };
+            // a different first frame starts a new packet, the same one is a duplicate
+            if let Some(session) = self.sessions.get(&key) {
+                if session.length == Some(length) && session.frames[&0] == data[2..] {
+                    return None;
+                }
+                if session.length.is_some() {
+                    let session = self.sessions.remove(&key).unwrap();
+                    self.errors.push(SessionError::Incomplete {
+                        header: session.header,
+                        sequence,
+                        missing: session.missing(),
+                        length: session.length,
+                    });
+                }
+            }
+            let session = self.sessions.entry(key).or_insert_with(|| Session {
+                header,
+                length: None,
+                frames: BTreeMap::new(),
+                updated: now,
+            });
+            session.length = Some(length);
+            session.frames.insert(0, data[2..].to_vec());
+            session.updated = now;
+        } else {
+            let chunk = data[1..].to_vec();
+            let session = self.sessions.entry(key).or_insert_with(|| Session {
+                header,
+                length: None,
+                frames: BTreeMap::new(),
+                updated: now,
+            });
+            session.updated = now;
+            if let Some(previous) = session.frames.get(&index) {
+                if *previous != chunk {
+                    let session = self.sessions.remove(&key).unwrap();
+                    self.errors.push(SessionError::Corrupt {
+                        header: session.header,
+                        sequence,
+                        reason: format!("frame {} received twice with different data", index),
+                    });
+                }
+                return None;
+            }
+            session.frames.insert(index, chunk);
+        }
+        self.complete(key, now)
+    }
+
+    /// Drop the sessions that timed out at now
+    pub fn expire(&mut self, now: Duration) {
+        let timeout = self.timeout;
+        let expired: Vec<_> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| now.saturating_sub(session.updated) > timeout)
+            .map(|(key, _)| *key)
+            .collect();
+        for key in expired {
+            let session = self.sessions.remove(&key).unwrap();
+            self.errors.push(SessionError::Incomplete {
+                header: session.header,
+                sequence: key.2,
+                missing: session.missing(),
+                length: session.length,
+            });
+        }
+    }
+
+    fn complete(&mut self, key: (u8, u32, u8), now: Duration) -> Option<Packet> {
+        let session = self.sessions.get(&key)?;
+        let length = session.length?;
+        let count = frame_count(length);
+        let corrupt = if let Some(index) = session.frames.keys().find(|i| **i as usize >= count) {
+            Some(format!("frame {} beyond length {}", index, length))
+        } else if session.frames.len() < count {
+            return None;
+        } else {
+            session
+                .frames
+                .iter()
+                .find(|(index, chunk)| {
+                    let full = if **index == 0 {
+                        FIRST_FRAME_LEN
+                    } else {
+                        FRAME_LEN
+                    };
+                    (**index as usize) < count - 1 && chunk.len() < full
+                })
+                .map(|(index, _)| format!("frame {} is short", index))
+        };
+        let session = self.sessions.remove(&key).unwrap();
+        if let Some(reason) = corrupt {
+            self.errors.push(SessionError::Corrupt {
+                header: session.header,
+                sequence: key.2,
+                reason,
+            });
+            return None;
+        }
+        let mut data: Vec<u8> = session.frames.into_values().flatten().collect();
+        if data.len() < length {
+            self.errors.push(SessionError::Corrupt {
+                header: session.header,
+                sequence: key.2,
+                reason: format!("{} of {} bytes received", data.len(), length),
+            });
+            return None;
+        }
+        data.truncate(length);
+        Some(Packet {
+            header: session.header,
+            data,
+            timestamp: now,
+        })
+    }
+}
+
+/// Split a payload into fast packet frames with the given sequence counter
+pub fn fragment(header: &N2kHeader, sequence: u8, data: &[u8]) -> Result<Vec<CanFrame>> {
+    if data.is_empty() || data.len() > MAX_FAST_PACKET_LEN {
+        return Err(Error::with_context(
+            ErrorKind::InvParam,
+            &format!("fragment: invalid payload length {}", data.len()),
+        ));
+    }
+    if sequence > 7 {
+        return Err(Error::with_context(
+            ErrorKind::OutOfRange,
+            &format!("fragment: invalid sequence counter {}", sequence),
+        ));
+    }
+    let id = header.to_can_id()?;
+    let split = data.len().min(FIRST_FRAME_LEN);
+    let chunks = std::iter::once(&data[..split]).chain(data[split..].chunks(FRAME_LEN));
+    chunks
+        .enumerate()
+        .map(|(index, chunk)| {
+            let mut frame = [0xFF; 8];
+            frame[0] = sequence << 5 | index as u8;
+            let offset = if index == 0 {
+                frame[1] = data.len() as u8;
+                2
+            } else {
+                1
+            };
+            frame[offset..offset + chunk.len()].copy_from_slice(chunk);
+            CanFrame::extended(id, &frame)
+        })
+        .collect()
+}
+
+/// Splits payloads into fast packet frames, counting the sequence per source and PGN
+#[derive(Debug, Default)]
+pub struct Fragmenter {
+    sequences: BTreeMap<(u8, u32), u8>,
+}
+
+impl Fragmenter {
+    pub fn new() -> Fragmenter {
+        Fragmenter::default()
+    }
+
+    /// Split a payload using the next sequence counter of the header's source and PGN
+    pub fn fragment(&mut self, header: &N2kHeader, data: &[u8]) -> Result<Vec<CanFrame>> {
+        let sequence = self
+            .sequences
+            .get(&(header.source, header.pgn))
+            .copied()
+            .unwrap_or(0);
+        let frames = fragment(header, sequence, data)?;
+        self.sequences
+            .insert((header.source, header.pgn), (sequence + 1) & 0x07);
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(source: u8, pgn: u32, data: &[u8]) -> CanFrame {
+        let id = N2kHeader::new(3, pgn, source).to_can_id().unwrap();
+        CanFrame::extended(id, data).unwrap()
+    }
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    // 129029 GNSS position data split in 3 frames with sequence 2
+    fn frames(source: u8) -> Vec<CanFrame> {
+        vec![
+            frame(source, 129029, &[0x40, 0x14, 1, 2, 3, 4, 5, 6]),
+            frame(source, 129029, &[0x41, 7, 8, 9, 10, 11, 12, 13]),
+            frame(source, 129029, &[0x42, 14, 15, 16, 17, 18, 19, 20]),
+        ]
+    }
+
+    #[test]
+    fn test_reassemble() {
+        let registry = FastPacketRegistry::nmea2000();
+        assert!(registry.contains(129029));
+        assert!(registry.contains(130900));
+        assert!(!registry.contains(129025));
+        assert_eq!(frame_count(6), 1);
+        assert_eq!(frame_count(20), 3);
+        assert_eq!(frame_count(223), 32);
+
+        let mut reassembler = Reassembler::new(registry);
+        // single frame PGNs pass through
+        let packet = reassembler.push(&frame(1, 129025, &[0; 8]), ms(0)).unwrap();
+        assert_eq!(packet.data, [0; 8]);
+        // remote frames are no data frames
+        let remote = CanFrame::remote(0x09F8_0101, true, 8).unwrap();
+        assert_eq!(reassembler.push(&remote, ms(0)), None);
+
+        // interleaved sources, out of order frames
+        let (a, b) = (frames(1), frames(2));
+        assert_eq!(reassembler.push(&a[0], ms(1)), None);
+        assert_eq!(reassembler.push(&b[2], ms(2)), None);
+        assert_eq!(reassembler.push(&a[1], ms(3)), None);
+        assert_eq!(reassembler.push(&b[0], ms(4)), None);
+        assert_eq!(reassembler.pending(), 2);
+        let packet = reassembler.push(&a[2], ms(5)).unwrap();
+        assert_eq!(packet.header, N2kHeader::new(3, 129029, 1));
+        assert_eq!(packet.data, (1..=20).collect::<Vec<u8>>());
+        assert_eq!(packet.timestamp, ms(5));
+        assert_eq!(packet.bitfield().bit_len(), 160);
+        // a repeated frame is ignored
+        assert_eq!(reassembler.push(&b[0], ms(6)), None);
+        let packet = reassembler.push(&b[1], ms(7)).unwrap();
+        assert_eq!(packet.header.source, 2);
+        assert_eq!(reassembler.pending(), 0);
+        assert!(reassembler.take_errors().is_empty());
+    }
+
+    #[test]
+    fn test_reassemble_errors() {
+        let mut reassembler =
+            Reassembler::new(FastPacketRegistry::new().with_pgn(129029)).with_timeout(ms(100));
+        let a = frames(1);
+        let header = N2kHeader::new(3, 129029, 1);
+
+        // missing frame, then timeout
+        reassembler.push(&a[0], ms(0));
+        reassembler.push(&a[2], ms(50));
+        reassembler.expire(ms(200));
+        assert_eq!(
+            reassembler.take_errors(),
+            vec![SessionError::Incomplete {
+                header,
+                sequence: 2,
+                length: Some(20),
+                missing: vec![1],
+            }]
+        );
+
+        // a new first frame replaces the session
+        reassembler.push(&frame(1, 129029, &[0x40, 0x14, 0, 0, 0, 0, 0, 0]), ms(300));
+        reassembler.push(&a[0], ms(310));
+        assert_eq!(reassembler.take_errors().len(), 1);
+        reassembler.push(&a[1], ms(320));
+        assert!(reassembler.push(&a[2], ms(330)).is_some());
+
+        // first frame lost
+        reassembler.push(&a[1], ms(400));
+        reassembler.push(&a[2], ms(410));
+        reassembler.expire(ms(600));
+        assert_eq!(
+            reassembler.take_errors(),
+            vec![SessionError::Incomplete {
+                header,
+                sequence: 2,
+                length: None,
+                missing: vec![0],
+            }]
+        );
+
+        // conflicting frames, frames beyond the length and bad lengths
+        reassembler.push(&a[1], ms(700));
+        reassembler.push(&frame(1, 129029, &[0x41, 0, 0, 0, 0, 0, 0, 0]), ms(701));
+        reassembler.push(&frame(1, 129029, &[0x41, 7, 8, 9, 10, 11, 12, 13]), ms(702));
+        reassembler.push(&frame(1, 129029, &[0x40, 0x06, 1, 2, 3, 4, 5, 6]), ms(702));
+        reassembler.push(&frame(1, 129029, &[0x20, 0xE0, 0, 0, 0, 0, 0, 0]), ms(703));
+        reassembler.push(&frame(1, 129029, &[0x60, 0x0D, 1, 2, 3]), ms(704));
+        reassembler.push(&frame(1, 129029, &[0x61, 4, 5, 6, 7, 8, 9, 10]), ms(705));
+        let errors = reassembler.take_errors();
+        assert_eq!(errors.len(), 4);
+        for (error, reason) in errors.iter().zip(&[
+            "frame 1 received twice with different data",
+            "frame 1 beyond length 6",
+            "invalid length in [32, 224, 0, 0, 0, 0, 0, 0]",
+            "frame 0 is short",
+        ]) {
+            match error {
+                SessionError::Corrupt { reason: r, .. } => assert_eq!(r, reason),
+                _ => panic!("unexpected {:?}", error),
+            }
+        }
+        assert_eq!(reassembler.pending(), 0);
+    }
+
+    #[test]
+    fn test_fragment() {
+        let header = N2kHeader::new(6, 126996, 0x23);
+        let data: Vec<u8> = (1..=20).collect();
+        let frames = fragment(&header, 5, &data).unwrap();
+        assert_eq!(
+            frames.iter().map(CanFrame::data).collect::<Vec<_>>(),
+            vec![
+                &[0xA0, 20, 1, 2, 3, 4, 5, 6][..],
+                &[0xA1, 7, 8, 9, 10, 11, 12, 13],
+                &[0xA2, 14, 15, 16, 17, 18, 19, 20],
+            ]
+        );
+        assert_eq!(frames[0].header().unwrap(), header);
+        let frames = fragment(&header, 0, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        assert_eq!(frames[1].data(), [0x01, 7, 8, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        let frames = fragment(&header, 0, &[1]).unwrap();
+        assert_eq!(frames[0].data(), [0x00, 1, 1, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(fragment(&header, 0, &[0; 223]).unwrap().len(), 32);
+        assert!(fragment(&header, 0, &[0; 224]).is_err());
+        assert!(fragment(&header, 0, &[]).is_err());
+        assert!(fragment(&header, 8, &[0]).is_err());
+
+        // the sequence rotates per source and PGN and the frames reassemble
+        let mut fragmenter = Fragmenter::new();
+        let mut reassembler = Reassembler::new(FastPacketRegistry::new().with_pgn(126996));
+        let data = [0x55; 134];
+        for sequence in (0..8).chain(0..2) {
+            let frames = fragmenter.fragment(&header, &data).unwrap();
+            assert_eq!(frames[0].data()[0], sequence << 5);
+            let packets: Vec<_> = frames
+                .iter()
+                .filter_map(|frame| reassembler.push(frame, ms(0)))
+                .collect();
+            assert_eq!(packets.len(), 1);
+            assert_eq!(packets[0].data, data);
+        }
+        let other = N2kHeader::new(6, 126996, 0x24);
+        assert_eq!(fragmenter.fragment(&other, &data).unwrap()[0].data()[0], 0);
+    }
+}
@@ -0,0 +1,249 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::{BitField, Endianness, MutableBitField};
+use std::convert::TryFrom;
+
+/// Position and encoding of a numeric field within a payload
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FieldSpec {
+    /// first bit of the field
+    pub start: usize,
+    /// last bit of the field, inclusive
+    pub end: usize,
+    pub endianness: Endianness,
+    pub signed: bool,
+    /// scale applied to the raw value
+    pub resolution: f64,
+    /// offset added to the scaled value
+    pub offset: f64,
+    /// the largest raw value (all bits set for unsigned fields) means "not available"
+    pub na: bool,
+}
+
+impl FieldSpec {
+    /// Create an unsigned field with resolution 1 and "not available" handling
+    pub fn new(start: usize, end: usize, endianness: Endianness) -> FieldSpec {
+        FieldSpec {
+            start,
+            end,
+            endianness,
+            signed: false,
+            resolution: 1.0,
+            offset: 0.0,
+            na: true,
+        }
+    }
+
+    /// Create a signed field with resolution 1 and "not available" handling
+    pub fn new_signed(start: usize, end: usize, endianness: Endianness) -> FieldSpec {
+        FieldSpec {
+            signed: true,
+            ..FieldSpec::new(start, end, endianness)
+        }
+    }
+
+    /// Get the field moved towards the end of the payload by bits, for fields following
+    /// variable length data
+    pub fn moved(&self, bits: usize) -> FieldSpec {
+        FieldSpec {
+            start: self.start + bits,
+            end: self.end + bits,
+            ..*self
+        }
+    }
+
+    /// Get the field size in bits
+    pub fn size(&self) -> usize {
+        self.end.saturating_sub(self.start) + 1
+    }
+
+    /// Check that the field is a valid range of at most 64 bits
+    pub fn check(&self) -> Result<()> {
+        if self.end < self.start || self.end - self.start > 63 {
+            Err(Error::with_context(
+                ErrorKind::InvRange,
+                &format!(
+                    "FieldSpec: invalid field range {}..={}",
+                    self.start, self.end
+                ),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The largest raw value, used as "not available" marker
+    pub fn na_value(&self) -> u64 {
+        let size = if self.signed {
+            self.size() - 1
+        } else {
+            self.size()
+        };
+        if size >= 64 {
+            u64::MAX
+        } else {
+            (1u64 << size) - 1
+        }
+    }
+
+    /// Check whether a raw value is the "not available" marker
+    pub fn is_na(&self, raw: u64) -> bool {
+        self.na && self.size() > 1 && raw == self.na_value()
+    }
+
+    /// Get the raw, unscaled bits of the field
+    pub fn get_raw(&self, bitfield: &BitField) -> Result<u64> {
+        bitfield.get_u64(self.start, self.end, self.endianness)
+    }
+
+    /// Get the unscaled value, None if not available
+    pub fn get_i64(&self, bitfield: &BitField) -> Result<Option<i64>> {
+        let raw = self.get_raw(bitfield)?;
+        if self.is_na(raw) {
+            Ok(None)
+        } else if self.signed {
+            // sign extend from the field size instead of reading the field again
+            let shift = 64 - self.size();
+            Ok(Some(((raw << shift) as i64) >> shift))
+        } else {
+            Ok(Some(raw as i64))
+        }
+    }
+
+    /// Get the scaled value, None if not available
+    pub fn get_f64(&self, bitfield: &BitField) -> Result<Option<f64>> {
+        Ok(self
+            .get_i64(bitfield)?
+            .map(|value| value as f64 * self.resolution + self.offset))
+    }
+
+    /// Check that an unscaled value fits the field and is not the "not available" marker.
+    /// Values of 64 bit unsigned fields are taken as u64
+    pub fn check_raw(&self, value: i64) -> Result<()> {
+        let size = self.size() as u32;
+        let (value, min, max) = if self.signed {
+            (
+                value as i128,
+                -(1i128 << (size - 1)),
+                (1i128 << (size - 1)) - 1,
+            )
+        } else if size == 64 {
+            (value as u64 as i128, 0, u64::MAX as i128)
+        } else {
+            (value as i128, 0, (1i128 << size) - 1)
+        };
+        if value < min || value > max || (self.na && size > 1 && value == max) {
+            return Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!(
+                    "FieldSpec::check_raw: {} does not fit field {}..={}",
+                    value, self.start, self.end
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Set the unscaled value, None writes the "not available" marker. Values that do not
+    /// fit the field are rejected, see check_raw
+    pub fn set_i64(&self, bitfield: &mut MutableBitField, value: Option<i64>) -> Result<()> {
+        match value {
+            Some(value) => {
+                self.check_raw(value)?;
+                bitfield.set_i64(value, self.start, self.end, self.endianness)
+            }
+            None => bitfield.set_u64(self.na_value(), self.start, self.end, self.endianness),
+        }
+    }
+
+    /// Set the scaled value, rounded to the resolution, None writes the "not available" marker
+    pub fn set_f64(&self, bitfield: &mut MutableBitField, value: Option<f64>) -> Result<()> {
+        let raw = match value {
+            Some(value) => {
+                let raw = ((value - self.offset) / self.resolution).round();
+                // the cast saturates, keep values beyond i64 out of range
+                if !raw.is_finite() || raw < i64::MIN as f64 || raw >= i64::MAX as f64 {
+                    return Err(Error::with_context(
+                        ErrorKind::OutOfRange,
+                        &format!(
+                            "FieldSpec::set_f64: {} does not fit field {}..={}",
+                            value, self.start, self.end
+                        ),
+                    ));
+                }
+                Some(raw as i64)
+            }
+            None => None,
+        };
+        self.set_i64(bitfield, raw)
+    }
+
+    /// Set the number of rows of a repeating group, fails if the count does not fit the field
+    pub fn set_count(&self, bitfield: &mut MutableBitField, count: usize) -> Result<()> {
+        match i64::try_from(count) {
+            Ok(count) => self.set_i64(bitfield, Some(count)),
+            Err(_) => Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!("FieldSpec::set_count: {} rows", count),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_spec() {
+        const BYTES: [u8; 6] = [0x01, 0x34, 0x12, 0xFF, 0xFF, 0x9C];
+        let bitfield = BitField::new(&BYTES);
+        let rpm = FieldSpec {
+            resolution: 0.25,
+            ..FieldSpec::new(8, 23, Endianness::Little)
+        };
+        assert_eq!(rpm.get_raw(&bitfield).unwrap(), 0x1234);
+        assert_eq!(rpm.get_f64(&bitfield).unwrap(), Some(1165.0));
+        let na = FieldSpec::new(24, 39, Endianness::Little);
+        assert_eq!(na.get_i64(&bitfield).unwrap(), None);
+        let temp = FieldSpec::new_signed(40, 47, Endianness::Little);
+        assert_eq!(temp.get_i64(&bitfield).unwrap(), Some(-100));
+        assert_eq!(temp.na_value(), 0x7F);
+        let wide = FieldSpec::new_signed(0, 47, Endianness::Little);
+        assert_eq!(wide.get_i64(&bitfield).unwrap(), Some(-0x6300_00ED_CBFF));
+        let nibble = FieldSpec::new_signed(44, 47, Endianness::Intel);
+        assert_eq!(nibble.get_i64(&bitfield).unwrap(), Some(-7));
+
+        let mut bytes: [u8; 6] = [0; 6];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        rpm.set_f64(&mut bitfield, Some(1165.1)).unwrap();
+        na.set_i64(&mut bitfield, None).unwrap();
+        temp.set_i64(&mut bitfield, Some(-100)).unwrap();
+        assert_eq!(bytes, [0x00, 0x34, 0x12, 0xFF, 0xFF, 0x9C]);
+
+        // values are not truncated to the field, the "not available" marker is no value
+        let byte = FieldSpec::new(0, 7, Endianness::Little);
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        assert_eq!(
+            byte.set_i64(&mut bitfield, Some(300)).unwrap_err().kind(),
+            ErrorKind::OutOfRange
+        );
+        assert!(byte.set_i64(&mut bitfield, Some(255)).is_err());
+        assert!(byte.set_i64(&mut bitfield, Some(-1)).is_err());
+        byte.set_i64(&mut bitfield, Some(254)).unwrap();
+        assert!(temp.set_i64(&mut bitfield, Some(-129)).is_err());
+        assert!(temp.set_i64(&mut bitfield, Some(127)).is_err());
+        temp.set_i64(&mut bitfield, Some(-128)).unwrap();
+        assert!(rpm.set_f64(&mut bitfield, Some(16383.75)).is_err());
+        assert!(rpm.set_f64(&mut bitfield, Some(f64::NAN)).is_err());
+        rpm.set_f64(&mut bitfield, Some(16383.5)).unwrap();
+        assert!(byte.set_count(&mut bitfield, 255).is_err());
+        let raw = FieldSpec {
+            na: false,
+            ..FieldSpec::new(0, 63, Endianness::Little)
+        };
+        let mut bytes = [0; 8];
+        raw.set_i64(&mut MutableBitField::new(&mut bytes), Some(-1))
+            .unwrap();
+        assert_eq!(bytes, [0xFF; 8]);
+    }
+}
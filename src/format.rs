@@ -0,0 +1,347 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::{BitField, MutableBitField};
+use std::fmt::{self, Binary, Debug, Display, Formatter, LowerHex, UpperHex};
+use std::str::FromStr;
+
+// Formatting:
+//   {}    upper case hex bytes:  09 F8 01
+//   {:#}  bit index ruler with binary and hex rows
+//   {:x}  lower case hex bytes, {:#x} with 0x prefix per byte, {:X} / {:#X} upper case
+//   {:b}  binary bytes: 00001001 11111000, {:#b} with 0b prefix per byte
+
+/// Parse hex bytes like "09 F8 01 FF", "09:f8:01:ff", "0x09 0xF8" or "09F801FF"
+pub fn parse_hex(text: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for token in tokens(text) {
+        let digits: Vec<char> = strip_prefix(token, "0x")
+            .chars()
+            .filter(|c| *c != '_')
+            .collect();
+        if digits.is_empty() || digits.len() % 2 != 0 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("parse_hex: invalid number of hex digits in '{}'", token),
+            ));
+        }
+        for pair in digits.chunks(2) {
+            let mut byte = 0u8;
+            for digit in pair {
+                match digit.to_digit(16) {
+                    Some(value) => byte = byte << 4 | value as u8,
+                    None => {
+                        return Err(Error::with_context(
+                            ErrorKind::InvParam,
+                            &format!("parse_hex: invalid hex digit '{}' in '{}'", digit, token),
+                        ))
+                    }
+                }
+            }
+            bytes.push(byte);
+        }
+    }
+    Ok(bytes)
+}
+
+/// Parse binary bytes like "0b1010_1100 0b00001111" or "10101100 00001111",
+/// the total number of bits must be a multiple of 8
+pub fn parse_bin(text: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut byte = 0u8;
+    let mut count = 0;
+    for token in tokens(text) {
+        for digit in strip_prefix(token, "0b").chars().filter(|c| *c != '_') {
+            let bit = match digit {
+                '0' => 0,
+                '1' => 1,
+                _ => {
+                    return Err(Error::with_context(
+                        ErrorKind::InvParam,
+                        &format!("parse_bin: invalid binary digit '{}' in '{}'", digit, token),
+                    ))
+                }
+            };
+            byte = byte << 1 | bit;
+            count += 1;
+            if count % 8 == 0 {
+                bytes.push(byte);
+                byte = 0;
+            }
+        }
+    }
+    if count % 8 != 0 {
+        Err(Error::with_context(
+            ErrorKind::InvParam,
+            &format!("parse_bin: {} bits is not a multiple of 8", count),
+        ))
+    } else {
+        Ok(bytes)
+    }
+}
+
+fn tokens(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| c.is_whitespace() || c == ':' || c == ',' || c == '-')
+        .filter(|token| !token.is_empty())
+}
+
+// case insensitive, like the digits
+fn has_prefix(token: &str, prefix: &str) -> bool {
+    token
+        .get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+fn strip_prefix<'t>(token: &'t str, prefix: &str) -> &'t str {
+    if has_prefix(token, prefix) {
+        &token[prefix.len()..]
+    } else {
+        token
+    }
+}
+
+impl BitField<'static> {
+    /// Create a bitfield from hex bytes, see parse_hex
+    pub fn from_hex(text: &str) -> Result<BitField<'static>> {
+        Ok(BitField::from_vec(parse_hex(text)?))
+    }
+
+    /// Create a bitfield from binary bytes, see parse_bin
+    pub fn from_bin(text: &str) -> Result<BitField<'static>> {
+        Ok(BitField::from_vec(parse_bin(text)?))
+    }
+}
+
+impl FromStr for BitField<'static> {
+    type Err = Error;
+
+    /// Parse binary if the text starts with 0b, hex otherwise
+    fn from_str(text: &str) -> Result<Self> {
+        if has_prefix(text.trim_start(), "0b") {
+            BitField::from_bin(text)
+        } else {
+            BitField::from_hex(text)
+        }
+    }
+}
+
+#[doc(hidden)]
+pub trait BitsLiteral {
+    fn into_bytes(self) -> Vec<u8>;
+}
+
+impl BitsLiteral for &str {
+    fn into_bytes(self) -> Vec<u8> {
+        match BitField::from_str(self) {
+            Ok(bitfield) => bitfield.as_bytes().to_vec(),
+            Err(why) => panic!("bits!: invalid literal '{}': {}", self, why),
+        }
+    }
+}
+
+impl BitsLiteral for u8 {
+    fn into_bytes(self) -> Vec<u8> {
+        vec![self]
+    }
+}
+
+/// Create an owned BitField from bytes or from a hex / binary string, panics on invalid strings
+///
+/// ```
+/// use bitfield::bits;
+///
+/// assert_eq!(bits![0x09, 0xF8].as_bytes(), &[0x09, 0xF8]);
+/// assert_eq!(bits!["09 F8"].as_bytes(), &[0x09, 0xF8]);
+/// assert_eq!(bits!["0b0000_1001 0b1111_1000"].as_bytes(), &[0x09, 0xF8]);
+/// ```
+#[macro_export]
+macro_rules! bits {
+    ($literal:literal) => {
+        $crate::BitField::from_vec($crate::format::BitsLiteral::into_bytes($literal))
+    };
+    ($($byte:expr),* $(,)?) => {
+        $crate::BitField::from_vec(vec![$($byte),*])
+    };
+}
+
+fn write_bytes(
+    f: &mut Formatter<'_>,
+    bytes: &[u8],
+    write_byte: impl Fn(&mut Formatter<'_>, u8) -> fmt::Result,
+) -> fmt::Result {
+    for (index, byte) in bytes.iter().enumerate() {
+        if index > 0 {
+            write!(f, " ")?;
+        }
+        write_byte(f, *byte)?;
+    }
+    Ok(())
+}
+
+fn fmt_display(bytes: &[u8], f: &mut Formatter<'_>) -> fmt::Result {
+    if f.alternate() {
+        write!(f, "bit ")?;
+        for index in 0..bytes.len() {
+            write!(f, " {:<8}", index * 8)?;
+        }
+        write!(f, "\nbin ")?;
+        for byte in bytes {
+            write!(f, " {:08b}", byte)?;
+        }
+        write!(f, "\nhex ")?;
+        for byte in bytes {
+            write!(f, " {:<8}", format!("{:02X}", byte))?;
+        }
+        Ok(())
+    } else {
+        write_bytes(f, bytes, |f, byte| write!(f, "{:02X}", byte))
+    }
+}
+
+fn fmt_lower_hex(bytes: &[u8], f: &mut Formatter<'_>) -> fmt::Result {
+    if f.alternate() {
+        write_bytes(f, bytes, |f, byte| write!(f, "0x{:02x}", byte))
+    } else {
+        write_bytes(f, bytes, |f, byte| write!(f, "{:02x}", byte))
+    }
+}
+
+fn fmt_upper_hex(bytes: &[u8], f: &mut Formatter<'_>) -> fmt::Result {
+    if f.alternate() {
+        write_bytes(f, bytes, |f, byte| write!(f, "0x{:02X}", byte))
+    } else {
+        write_bytes(f, bytes, |f, byte| write!(f, "{:02X}", byte))
+    }
+}
+
+fn fmt_binary(bytes: &[u8], f: &mut Formatter<'_>) -> fmt::Result {
+    if f.alternate() {
+        write_bytes(f, bytes, |f, byte| write!(f, "0b{:08b}", byte))
+    } else {
+        write_bytes(f, bytes, |f, byte| write!(f, "{:08b}", byte))
+    }
+}
+
+struct HexBytes<'b>(&'b [u8]);
+
+impl<'b> Debug for HexBytes<'b> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "[")?;
+        write_bytes(f, self.0, |f, byte| write!(f, "{:02X}", byte))?;
+        write!(f, "]")
+    }
+}
+
+macro_rules! impl_fmt {
+    ($type:ident) => {
+        impl<'a> Debug for $type<'a> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($type))
+                    .field("bits", &self.bit_len())
+                    .field("data", &HexBytes(self.as_bytes()))
+                    .finish()
+            }
+        }
+
+        impl<'a> Display for $type<'a> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                fmt_display(self.as_bytes(), f)
+            }
+        }
+
+        impl<'a> LowerHex for $type<'a> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                fmt_lower_hex(self.as_bytes(), f)
+            }
+        }
+
+        impl<'a> UpperHex for $type<'a> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                fmt_upper_hex(self.as_bytes(), f)
+            }
+        }
+
+        impl<'a> Binary for $type<'a> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                fmt_binary(self.as_bytes(), f)
+            }
+        }
+    };
+}
+
+impl_fmt!(BitField);
+impl_fmt!(MutableBitField);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse_hex("09 F8 01 FF").unwrap(),
+            vec![0x09, 0xF8, 0x01, 0xFF]
+        );
+        assert_eq!(
+            parse_hex("09:f8:01:ff").unwrap(),
+            vec![0x09, 0xF8, 0x01, 0xFF]
+        );
+        assert_eq!(parse_hex("0x09F8, 0x01").unwrap(), vec![0x09, 0xF8, 0x01]);
+        assert_eq!(parse_hex("").unwrap(), Vec::<u8>::new());
+        assert!(parse_hex("09 F").is_err());
+        assert!(parse_hex("0G").is_err());
+
+        assert_eq!(
+            parse_bin("0b1010_1100 0b0000_1111").unwrap(),
+            vec![0b10101100, 0b00001111]
+        );
+        assert_eq!(parse_bin("1010 1100").unwrap(), vec![0b10101100]);
+        assert!(parse_bin("0b1010").is_err());
+        assert!(parse_bin("0b1012_1100").is_err());
+
+        let bitfield: BitField = "0b1010_1100".parse().unwrap();
+        assert_eq!(bitfield.as_bytes(), &[0b10101100]);
+        let bitfield: BitField = " 0B1010_1100".parse().unwrap();
+        assert_eq!(bitfield.as_bytes(), &[0b10101100]);
+        let bitfield: BitField = "AC 0F".parse().unwrap();
+        assert_eq!(bitfield.get_u16_be(0, 15).unwrap(), 0xAC0F);
+    }
+
+    #[test]
+    fn test_bits_macro() {
+        assert_eq!(bits![].bit_len(), 0);
+        assert_eq!(bits![0x09].as_bytes(), &[0x09]);
+        assert_eq!(bits![0x09, 0xF8,].as_bytes(), &[0x09, 0xF8]);
+        assert_eq!(bits!["09 F8"].as_bytes(), &[0x09, 0xF8]);
+        assert_eq!(bits!["0b00001001"].as_bytes(), &[0x09]);
+    }
+
+    #[test]
+    fn test_format() {
+        let bitfield = bits![0x09, 0xF8, 0x01];
+        assert_eq!(format!("{}", bitfield), "09 F8 01");
+        assert_eq!(format!("{:x}", bitfield), "09 f8 01");
+        assert_eq!(format!("{:#X}", bitfield), "0x09 0xF8 0x01");
+        assert_eq!(format!("{:b}", bitfield), "00001001 11111000 00000001");
+        assert_eq!(
+            format!("{:#b}", bitfield),
+            "0b00001001 0b11111000 0b00000001"
+        );
+        assert_eq!(
+            format!("{:?}", bitfield),
+            "BitField { bits: 24, data: [09 F8 01] }"
+        );
+        assert_eq!(
+            format!("{:#}", bitfield),
+            "bit  0        8        16      \n\
+             bin  00001001 11111000 00000001\n\
+             hex  09       F8       01      "
+        );
+
+        let mut bytes = [0xAB];
+        let bitfield = MutableBitField::new(&mut bytes);
+        assert_eq!(
+            format!("{:?}", bitfield),
+            "MutableBitField { bits: 8, data: [AB] }"
+        );
+        assert_eq!(format!("{:b}", bitfield), "10101011");
+    }
+}
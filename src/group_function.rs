@@ -0,0 +1,817 @@
+use crate::bitfield::{BitField, Endianness};
+use crate::can::is_proprietary;
+use crate::codec::BitLookup;
+use crate::dynamic::{decode_field, encode_field, Value};
+use crate::error::{Error, ErrorKind, Result};
+use crate::mutable_bitfield::MutableBitField;
+use crate::schema::{FieldDescriptor, MessageSchema};
+
+// NMEA 2000 group function PGN 126208 requests, commands and reads or writes fields of
+// another PGN. The first byte selects the function, followed by the target PGN in 3 bytes:
+//
+//   0  request             interval (4), interval offset (2), count, pairs
+//   1  command             priority (4 bits) + reserved (4 bits), count, pairs
+//   2  acknowledge         PGN error (4 bits) + interval error (4 bits), count,
+//                          parameter errors (4 bits each)
+//   3  read fields         [proprietary id (2)], tag, selection count, field count,
+//                          selection pairs, field numbers
+//   4  read fields reply   [proprietary id (2)], tag, selection count, pair count,
+//   5  write fields        selection pairs, pairs
+//   6  write fields reply
+//
+// A pair is the 1 based number of a field of the target PGN followed by the value in the
+// field's size rounded up to whole bytes, unused bits are 1. The proprietary id (manufacturer
+// code, 2 reserved bits, industry code) is only present for proprietary target PGNs.
+
+/// Group function PGN
+pub const GROUP_FUNCTION: u32 = 126208;
+
+/// A field number and value of the target PGN
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    /// 1 based index into the fields of the target schema
+    pub field: u8,
+    pub value: Value,
+}
+
+impl Parameter {
+    pub fn new(field: u8, value: Value) -> Parameter {
+        Parameter { field, value }
+    }
+
+    /// Create a parameter for the field called name of schema
+    pub fn by_name(schema: &MessageSchema, name: &str, value: Value) -> Result<Parameter> {
+        match schema.fields.iter().position(|field| field.name == name) {
+            Some(index) if index < 255 => Ok(Parameter::new(index as u8 + 1, value)),
+            _ => Err(Error::with_context(
+                ErrorKind::NotFound,
+                &format!("Parameter::by_name: no field {} in {}", name, schema.name),
+            )),
+        }
+    }
+}
+
+/// Manufacturer and industry of a proprietary target PGN
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProprietaryId {
+    /// 11 bits
+    pub manufacturer_code: u16,
+    /// 3 bits, 4 is marine
+    pub industry_code: u8,
+}
+
+macro_rules! error_code {
+    ($(#[$doc:meta])* $type:ident {
+        $($variant:ident = $value:literal, $name:literal),* $(,)?
+    }) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $type {
+            $($variant,)*
+            Other(u8),
+        }
+
+        impl BitLookup for $type {
+            fn from_raw(raw: u64) -> Result<$type> {
+                Ok(match raw {
+                    $($value => $type::$variant,)*
+                    raw => $type::Other(raw as u8),
+                })
+            }
+
+            fn to_raw(&self) -> u64 {
+                match self {
+                    $($type::$variant => $value,)*
+                    $type::Other(raw) => *raw as u64,
+                }
+            }
+
+            fn name(&self) -> Option<&'static str> {
+                match self {
+                    $($type::$variant => Some($name),)*
+                    $type::Other(_) => None,
+                }
+            }
+        }
+    };
+}
+
+error_code! {
+    /// Acknowledge result for the target PGN
+    PgnErrorCode {
+        Acknowledge = 0, "Acknowledge",
+        PgnNotSupported = 1, "PGN not supported",
+        PgnNotAvailable = 2, "PGN not available",
+        AccessDenied = 3, "Access denied",
+        NotSupported = 4, "Not supported",
+        TagNotSupported = 5, "Tag not supported",
+        ReadWriteNotSupported = 6, "Read or Write not supported",
+    }
+}
+
+error_code! {
+    /// Acknowledge result for the requested interval or priority
+    IntervalErrorCode {
+        Acknowledge = 0, "Acknowledge",
+        NotSupported = 1, "Transmit Interval/Priority not supported",
+        BelowMinimum = 2, "Transmit Interval < Minimum",
+        AccessDenied = 3, "Access denied",
+        RequestNotSupported = 4, "Request not supported",
+    }
+}
+
+error_code! {
+    /// Acknowledge result per parameter
+    ParameterErrorCode {
+        Acknowledge = 0, "Acknowledge",
+        InvalidParameter = 1, "Invalid Request or Parameter not supported",
+        TemporarilyUnavailable = 2, "Temporarily unavailable",
+        OutOfRange = 3, "Parameter out of range",
+        AccessDenied = 4, "Access denied",
+        NotSupported = 5, "Not supported",
+        ReadWriteNotSupported = 6, "Read or Write not supported",
+    }
+}
+
+/// A decoded group function
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupFunction {
+    /// request pgn, optionally changing its interval in ms and offset in 10 ms, parameters
+    /// select the instance
+    Request {
+        pgn: u32,
+        interval: Option<u32>,
+        offset: Option<u16>,
+        parameters: Vec<Parameter>,
+    },
+    /// command fields of pgn, optionally changing its priority
+    Command {
+        pgn: u32,
+        priority: Option<u8>,
+        parameters: Vec<Parameter>,
+    },
+    Acknowledge {
+        pgn: u32,
+        pgn_error: PgnErrorCode,
+        interval_error: IntervalErrorCode,
+        parameter_errors: Vec<ParameterErrorCode>,
+    },
+    ReadFields {
+        pgn: u32,
+        proprietary: Option<ProprietaryId>,
+        tag: u8,
+        selection: Vec<Parameter>,
+        fields: Vec<u8>,
+    },
+    ReadFieldsReply {
+        pgn: u32,
+        proprietary: Option<ProprietaryId>,
+        tag: u8,
+        selection: Vec<Parameter>,
+        parameters: Vec<Parameter>,
+    },
+    WriteFields {
+        pgn: u32,
+        proprietary: Option<ProprietaryId>,
+        tag: u8,
+        selection: Vec<Parameter>,
+        parameters: Vec<Parameter>,
+    },
+    WriteFieldsReply {
+        pgn: u32,
+        proprietary: Option<ProprietaryId>,
+        tag: u8,
+        selection: Vec<Parameter>,
+        parameters: Vec<Parameter>,
+    },
+}
+
+impl GroupFunction {
+    /// Get the function code
+    pub fn code(&self) -> u8 {
+        match self {
+            GroupFunction::Request { .. } => 0,
+            GroupFunction::Command { .. } => 1,
+            GroupFunction::Acknowledge { .. } => 2,
+            GroupFunction::ReadFields { .. } => 3,
+            GroupFunction::ReadFieldsReply { .. } => 4,
+            GroupFunction::WriteFields { .. } => 5,
+            GroupFunction::WriteFieldsReply { .. } => 6,
+        }
+    }
+
+    /// Get the target PGN
+    pub fn pgn(&self) -> u32 {
+        match self {
+            GroupFunction::Request { pgn, .. }
+            | GroupFunction::Command { pgn, .. }
+            | GroupFunction::Acknowledge { pgn, .. }
+            | GroupFunction::ReadFields { pgn, .. }
+            | GroupFunction::ReadFieldsReply { pgn, .. }
+            | GroupFunction::WriteFields { pgn, .. }
+            | GroupFunction::WriteFieldsReply { pgn, .. } => *pgn,
+        }
+    }
+
+    /// Encode the payload, schema describes the target PGN and is needed for parameters
+    pub fn encode(&self, schema: Option<&MessageSchema>) -> Result<Vec<u8>> {
+        let mut out = vec![self.code()];
+        out.extend_from_slice(&self.pgn().to_le_bytes()[..3]);
+        match self {
+            GroupFunction::Request {
+                interval,
+                offset,
+                parameters,
+                ..
+            } => {
+                out.extend_from_slice(&interval.unwrap_or(0xFFFF_FFFF).to_le_bytes());
+                out.extend_from_slice(&offset.unwrap_or(0xFFFF).to_le_bytes());
+                push_count(&mut out, parameters.len())?;
+                push_pairs(&mut out, schema, parameters)?;
+            }
+            GroupFunction::Command {
+                priority,
+                parameters,
+                ..
+            } => {
+                let priority = priority.unwrap_or(8);
+                if priority > 8 {
+                    return Err(Error::with_context(
+                        ErrorKind::OutOfRange,
+                        &format!("GroupFunction::encode: invalid priority {}", priority),
+                    ));
+                }
+                out.push(0xF0 | priority);
+                push_count(&mut out, parameters.len())?;
+                push_pairs(&mut out, schema, parameters)?;
+            }
+            GroupFunction::Acknowledge {
+                pgn_error,
+                interval_error,
+                parameter_errors,
+                ..
+            } => {
+                out.push((interval_error.to_raw() as u8) << 4 | (pgn_error.to_raw() as u8 & 0x0F));
+                push_count(&mut out, parameter_errors.len())?;
+                for pair in parameter_errors.chunks(2) {
+                    let high = pair.get(1).map_or(0x0F, |code| code.to_raw() as u8);
+                    out.push(high << 4 | (pair[0].to_raw() as u8 & 0x0F));
+                }
+            }
+            GroupFunction::ReadFields {
+                pgn,
+                proprietary,
+                tag,
+                selection,
+                fields,
+            } => {
+                push_proprietary(&mut out, *pgn, proprietary)?;
+                out.push(*tag);
+                push_count(&mut out, selection.len())?;
+                push_count(&mut out, fields.len())?;
+                push_pairs(&mut out, schema, selection)?;
+                out.extend_from_slice(fields);
+            }
+            GroupFunction::ReadFieldsReply {
+                pgn,
+                proprietary,
+                tag,
+                selection,
+                parameters,
+            }
+            | GroupFunction::WriteFields {
+                pgn,
+                proprietary,
+                tag,
+                selection,
+                parameters,
+            }
+            | GroupFunction::WriteFieldsReply {
+                pgn,
+                proprietary,
+                tag,
+                selection,
+                parameters,
+            } => {
+                push_proprietary(&mut out, *pgn, proprietary)?;
+                out.push(*tag);
+                push_count(&mut out, selection.len())?;
+                push_count(&mut out, parameters.len())?;
+                push_pairs(&mut out, schema, selection)?;
+                push_pairs(&mut out, schema, parameters)?;
+            }
+        }
+        Ok(out)
+    }
+
+    /// Decode a payload, schemas returns the schema of a target PGN, which is needed for
+    /// parameters
+    pub fn decode<'a, F>(data: &[u8], schemas: F) -> Result<GroupFunction>
+    where
+        F: Fn(u32) -> Option<&'a MessageSchema>,
+    {
+        let mut reader = Reader { data, pos: 0 };
+        let code = reader.u8()?;
+        let pgn = reader.uint(3)? as u32;
+        let schema = schemas(pgn);
+        let function = match code {
+            0 => {
+                let interval = reader.uint(4)? as u32;
+                let offset = reader.uint(2)? as u16;
+                let count = reader.u8()?;
+                GroupFunction::Request {
+                    pgn,
+                    interval: Some(interval).filter(|interval| *interval != 0xFFFF_FFFF),
+                    offset: Some(offset).filter(|offset| *offset != 0xFFFF),
+                    parameters: reader.pairs(schema, count)?,
+                }
+            }
+            1 => {
+                let priority = reader.u8()? & 0x0F;
+                let count = reader.u8()?;
+                GroupFunction::Command {
+                    pgn,
+                    priority: Some(priority).filter(|priority| *priority < 8),
+                    parameters: reader.pairs(schema, count)?,
+                }
+            }
+            2 => {
+                let errors = reader.u8()?;
+                let count = reader.u8()? as usize;
+                let bytes = reader.take(count.div_ceil(2))?;
+                let parameter_errors = (0..count)
+                    .map(|index| {
+                        ParameterErrorCode::from_raw(
+                            (bytes[index / 2] >> (index % 2 * 4) & 0x0F) as u64,
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                GroupFunction::Acknowledge {
+                    pgn,
+                    pgn_error: PgnErrorCode::from_raw((errors & 0x0F) as u64)?,
+                    interval_error: IntervalErrorCode::from_raw((errors >> 4) as u64)?,
+                    parameter_errors,
+                }
+            }
+            3..=6 => {
+                let proprietary = if is_proprietary(pgn) {
+                    let bitfield = BitField::new(reader.take(2)?);
+                    Some(ProprietaryId {
+                        manufacturer_code: bitfield.get_u64(0, 10, Endianness::Intel)? as u16,
+                        industry_code: bitfield.get_u64(13, 15, Endianness::Intel)? as u8,
+                    })
+                } else {
+                    None
+                };
+                let tag = reader.u8()?;
+                let selection_count = reader.u8()?;
+                let count = reader.u8()?;
+                let selection = reader.pairs(schema, selection_count)?;
+                if code == 3 {
+                    GroupFunction::ReadFields {
+                        pgn,
+                        proprietary,
+                        tag,
+                        selection,
+                        fields: reader.take(count as usize)?.to_vec(),
+                    }
+                } else {
+                    let parameters = reader.pairs(schema, count)?;
+                    match code {
+                        4 => GroupFunction::ReadFieldsReply {
+                            pgn,
+                            proprietary,
+                            tag,
+                            selection,
+                            parameters,
+                        },
+                        5 => GroupFunction::WriteFields {
+                            pgn,
+                            proprietary,
+                            tag,
+                            selection,
+                            parameters,
+                        },
+                        _ => GroupFunction::WriteFieldsReply {
+                            pgn,
+                            proprietary,
+                            tag,
+                            selection,
+                            parameters,
+                        },
+                    }
+                }
+            }
+            code => {
+                return Err(Error::with_context(
+                    ErrorKind::InvFormat,
+                    &format!("GroupFunction::decode: unknown function code {}", code),
+                ))
+            }
+        };
+        Ok(function)
+    }
+
+    /// Whether the acknowledgement reports no errors, None for other functions
+    pub fn is_acknowledged(&self) -> Option<bool> {
+        match self {
+            GroupFunction::Acknowledge {
+                pgn_error,
+                interval_error,
+                parameter_errors,
+                ..
+            } => Some(
+                *pgn_error == PgnErrorCode::Acknowledge
+                    && *interval_error == IntervalErrorCode::Acknowledge
+                    && parameter_errors
+                        .iter()
+                        .all(|error| *error == ParameterErrorCode::Acknowledge),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Get the field with number of schema, moved to the start of a pair value
+fn pair_field(schema: Option<&MessageSchema>, number: u8) -> Result<FieldDescriptor> {
+    let schema = schema.ok_or_else(|| {
+        Error::with_context(
+            ErrorKind::NotFound,
+            "pair_field: parameters need the schema of the target PGN",
+        )
+    })?;
+    let field = match (number as usize).checked_sub(1) {
+        Some(index) if index < schema.fields.len() => &schema.fields[index],
+        _ => {
+            return Err(Error::with_context(
+                ErrorKind::NotFound,
+                &format!("pair_field: no field {} in {}", number, schema.name),
+            ))
+        }
+    };
+    if field.is_variable() {
+        return Err(Error::with_context(
+            ErrorKind::NotImpl,
+            &format!("pair_field: variable length field {}", field.name),
+        ));
+    }
+    Ok(FieldDescriptor {
+        start: 0,
+        end: field.size() - 1,
+        ..field.clone()
+    })
+}
+
+fn push_count(out: &mut Vec<u8>, count: usize) -> Result<()> {
+    if count > 255 {
+        return Err(Error::with_context(
+            ErrorKind::OutOfRange,
+            &format!("push_count: {} entries exceed 255", count),
+        ));
+    }
+    out.push(count as u8);
+    Ok(())
+}
+
+fn push_pairs(
+    out: &mut Vec<u8>,
+    schema: Option<&MessageSchema>,
+    parameters: &[Parameter],
+) -> Result<()> {
+    for parameter in parameters {
+        let field = pair_field(schema, parameter.field)?;
+        let mut value = vec![0xFF; field.size().div_ceil(8)];
+        encode_field(
+            &field,
+            &parameter.value,
+            &mut MutableBitField::new(&mut value),
+        )?;
+        out.push(parameter.field);
+        out.extend_from_slice(&value);
+    }
+    Ok(())
+}
+
+fn push_proprietary(
+    out: &mut Vec<u8>,
+    pgn: u32,
+    proprietary: &Option<ProprietaryId>,
+) -> Result<()> {
+    match proprietary {
+        Some(id) if is_proprietary(pgn) => {
+            let mut bytes = [0xFF; 2];
+            let mut bitfield = MutableBitField::new(&mut bytes);
+            bitfield.set_u64(id.manufacturer_code as u64, 0, 10, Endianness::Intel)?;
+            bitfield.set_u64(id.industry_code as u64, 13, 15, Endianness::Intel)?;
+            out.extend_from_slice(&bytes);
+            Ok(())
+        }
+        None if !is_proprietary(pgn) => Ok(()),
+        _ => Err(Error::with_context(
+            ErrorKind::InvParam,
+            &format!(
+                "push_proprietary: proprietary id does not match PGN {}",
+                pgn
+            ),
+        )),
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.pos + len > self.data.len() {
+            return Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!(
+                    "Reader::take: {} bytes at {} exceed the payload of {}",
+                    len,
+                    self.pos,
+                    self.data.len()
+                ),
+            ));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Read a little endian unsigned integer of len bytes
+    fn uint(&mut self, len: usize) -> Result<u64> {
+        Ok(self
+            .take(len)?
+            .iter()
+            .rev()
+            .fold(0, |value, byte| value << 8 | *byte as u64))
+    }
+
+    fn pairs(&mut self, schema: Option<&MessageSchema>, count: u8) -> Result<Vec<Parameter>> {
+        (0..count)
+            .map(|_| {
+                let number = self.u8()?;
+                let field = pair_field(schema, number)?;
+                let bytes = self.take(field.size().div_ceil(8))?;
+                Ok(Parameter::new(
+                    number,
+                    decode_field(&field, &BitField::new(bytes))?,
+                ))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{FieldType, Lookup};
+
+    // 127245 rudder
+    fn rudder() -> MessageSchema {
+        let field = |name: &str, start, end, field_type| FieldDescriptor {
+            endianness: Endianness::Intel,
+            ..FieldDescriptor::new(name, start, end, field_type)
+        };
+        MessageSchema::new(127245, "Rudder")
+            .with_field(field("Instance", 0, 7, FieldType::Unsigned))
+            .with_field(FieldDescriptor {
+                lookup: Some(Lookup::from_pairs(
+                    "DIRECTION_RUDDER",
+                    &[
+                        (0, "No Order"),
+                        (1, "Move to starboard"),
+                        (2, "Move to port"),
+                    ],
+                )),
+                ..field("Direction Order", 8, 10, FieldType::Lookup)
+            })
+            .with_field(field("Reserved", 11, 15, FieldType::Reserved))
+            .with_field(FieldDescriptor {
+                resolution: 0.0001,
+                unit: Some("rad".to_string()),
+                ..field("Angle Order", 16, 31, FieldType::Signed)
+            })
+            .with_field(FieldDescriptor {
+                resolution: 0.0001,
+                ..field("Position", 32, 47, FieldType::Signed)
+            })
+    }
+
+    #[test]
+    fn test_command() {
+        let schema = rudder();
+        let command = GroupFunction::Command {
+            pgn: 127245,
+            priority: None,
+            parameters: vec![
+                Parameter::by_name(&schema, "Instance", Value::Unsigned(0)).unwrap(),
+                Parameter::by_name(
+                    &schema,
+                    "Direction Order",
+                    Value::String("Move to port".into()),
+                )
+                .unwrap(),
+                Parameter::by_name(&schema, "Angle Order", Value::Float(-0.1)).unwrap(),
+            ],
+        };
+        let data = command.encode(Some(&schema)).unwrap();
+        assert_eq!(
+            data,
+            [0x01, 0x0D, 0xF1, 0x01, 0xF8, 0x03, 0x01, 0x00, 0x02, 0xFA, 0x04, 0x18, 0xFC]
+        );
+        let decoded =
+            GroupFunction::decode(&data, |pgn| Some(&schema).filter(|_| pgn == 127245)).unwrap();
+        match &decoded {
+            GroupFunction::Command { parameters, .. } => {
+                assert_eq!(
+                    parameters[1].value,
+                    Value::Lookup(2, Some("Move to port".into()))
+                );
+                assert!(matches!(parameters[2].value, Value::Float(v) if (v + 0.1).abs() < 1e-9));
+            }
+            _ => panic!("unexpected {:?}", decoded),
+        }
+        assert_eq!(decoded.encode(Some(&schema)).unwrap(), data);
+        assert_eq!(
+            GroupFunction::decode(&data, |_| None).unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+        assert!(Parameter::by_name(&schema, "Rate", Value::NotAvailable).is_err());
+        assert_eq!(
+            Parameter::new(9, Value::Unsigned(0)),
+            Parameter {
+                field: 9,
+                value: Value::Unsigned(0)
+            }
+        );
+        assert!(GroupFunction::Command {
+            pgn: 127245,
+            priority: None,
+            parameters: vec![Parameter::new(9, Value::Unsigned(0))],
+        }
+        .encode(Some(&schema))
+        .is_err());
+    }
+
+    #[test]
+    fn test_request() {
+        let schema = rudder();
+        let request = GroupFunction::Request {
+            pgn: 127245,
+            interval: Some(1000),
+            offset: None,
+            parameters: vec![Parameter::new(1, Value::Unsigned(1))],
+        };
+        let data = request.encode(Some(&schema)).unwrap();
+        assert_eq!(
+            data,
+            [0x00, 0x0D, 0xF1, 0x01, 0xE8, 0x03, 0x00, 0x00, 0xFF, 0xFF, 0x01, 0x01, 0x01]
+        );
+        assert_eq!(
+            GroupFunction::decode(&data, |_| Some(&schema)).unwrap(),
+            request
+        );
+        // without parameters the schema is not needed
+        let request = GroupFunction::Request {
+            pgn: 126996,
+            interval: None,
+            offset: Some(100),
+            parameters: Vec::new(),
+        };
+        let data = request.encode(None).unwrap();
+        assert_eq!(GroupFunction::decode(&data, |_| None).unwrap(), request);
+        assert_eq!(request.code(), 0);
+        assert_eq!(request.pgn(), 126996);
+        assert!(GroupFunction::decode(&data[..6], |_| None).is_err());
+        assert_eq!(
+            GroupFunction::decode(&[7, 0, 0, 0], |_| None)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvFormat
+        );
+    }
+
+    #[test]
+    fn test_acknowledge() {
+        let data = [0x02, 0x0D, 0xF1, 0x01, 0x00, 0x03, 0x30, 0xF5];
+        let ack = GroupFunction::decode(&data, |_| None).unwrap();
+        assert_eq!(
+            ack,
+            GroupFunction::Acknowledge {
+                pgn: 127245,
+                pgn_error: PgnErrorCode::Acknowledge,
+                interval_error: IntervalErrorCode::Acknowledge,
+                parameter_errors: vec![
+                    ParameterErrorCode::Acknowledge,
+                    ParameterErrorCode::OutOfRange,
+                    ParameterErrorCode::NotSupported,
+                ],
+            }
+        );
+        assert_eq!(ack.is_acknowledged(), Some(false));
+        assert_eq!(ack.encode(None).unwrap(), data);
+        assert_eq!(
+            ParameterErrorCode::OutOfRange.name(),
+            Some("Parameter out of range")
+        );
+
+        let ack = GroupFunction::decode(&[0x02, 0x10, 0xF0, 0x01, 0x21, 0x00], |_| None).unwrap();
+        match ack {
+            GroupFunction::Acknowledge {
+                pgn_error,
+                interval_error,
+                ..
+            } => {
+                assert_eq!(pgn_error, PgnErrorCode::PgnNotSupported);
+                assert_eq!(interval_error, IntervalErrorCode::BelowMinimum);
+            }
+            _ => panic!("unexpected {:?}", ack),
+        }
+        assert_eq!(
+            GroupFunction::decode(&[0x02, 0x10, 0xF0, 0x01, 0x0E, 0x00], |_| None).unwrap(),
+            GroupFunction::Acknowledge {
+                pgn: 126992,
+                pgn_error: PgnErrorCode::Other(14),
+                interval_error: IntervalErrorCode::Acknowledge,
+                parameter_errors: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_read_write_fields() {
+        let schema = rudder();
+        let read = GroupFunction::ReadFields {
+            pgn: 127245,
+            proprietary: None,
+            tag: 7,
+            selection: vec![Parameter::new(1, Value::Unsigned(0))],
+            fields: vec![4, 5],
+        };
+        let data = read.encode(Some(&schema)).unwrap();
+        assert_eq!(
+            data,
+            [0x03, 0x0D, 0xF1, 0x01, 0x07, 0x01, 0x02, 0x01, 0x00, 0x04, 0x05]
+        );
+        assert_eq!(
+            GroupFunction::decode(&data, |_| Some(&schema)).unwrap(),
+            read
+        );
+
+        let reply = GroupFunction::ReadFieldsReply {
+            pgn: 127245,
+            proprietary: None,
+            tag: 7,
+            selection: vec![Parameter::new(1, Value::Unsigned(0))],
+            parameters: vec![Parameter::new(5, Value::NotAvailable)],
+        };
+        let data = reply.encode(Some(&schema)).unwrap();
+        assert_eq!(data[9..], [0x05, 0xFF, 0x7F]);
+        assert_eq!(
+            GroupFunction::decode(&data, |_| Some(&schema)).unwrap(),
+            reply
+        );
+
+        // proprietary target PGNs carry the manufacturer
+        let proprietary = MessageSchema::new(130850, "Simnet: Command")
+            .with_field(FieldDescriptor {
+                endianness: Endianness::Intel,
+                ..FieldDescriptor::new("Manufacturer Code", 0, 10, FieldType::Unsigned)
+            })
+            .with_field(FieldDescriptor {
+                endianness: Endianness::Intel,
+                ..FieldDescriptor::new("Mode", 24, 31, FieldType::Unsigned)
+            });
+        let write = GroupFunction::WriteFields {
+            pgn: 130850,
+            proprietary: Some(ProprietaryId {
+                manufacturer_code: 1857,
+                industry_code: 4,
+            }),
+            tag: 1,
+            selection: Vec::new(),
+            parameters: vec![Parameter::new(2, Value::Unsigned(10))],
+        };
+        let data = write.encode(Some(&proprietary)).unwrap();
+        assert_eq!(data[4..6], [0x41, 0x9F]);
+        assert_eq!(
+            GroupFunction::decode(&data, |_| Some(&proprietary)).unwrap(),
+            write
+        );
+        let reply = GroupFunction::WriteFieldsReply {
+            pgn: 130850,
+            proprietary: None,
+            tag: 1,
+            selection: Vec::new(),
+            parameters: Vec::new(),
+        };
+        assert_eq!(reply.encode(None).unwrap_err().kind(), ErrorKind::InvParam);
+    }
+}
@@ -0,0 +1,291 @@
+use crate::bitfield::{BitField, Endianness};
+use crate::can::{CanFrame, N2kHeader, BROADCAST};
+use crate::codec::{BitDecode, BitEncode};
+use crate::error::{Error, ErrorKind, Result};
+use crate::mutable_bitfield::MutableBitField;
+use crate::request::IsoRequest;
+use std::fmt::{self, Display, Formatter};
+
+// The ISO 11783-5 / J1939-81 NAME identifies a node and decides address conflicts: the lower
+// NAME wins. It is sent little endian in the address claim PGN 60928, bits counted from the
+// least significant bit:
+//
+//   bits 0..20   unique number
+//   bits 21..31  manufacturer code
+//   bits 32..34  device instance lower
+//   bits 35..39  device instance upper
+//   bits 40..47  device function
+//   bit  48      reserved
+//   bits 49..55  device class
+//   bits 56..59  system instance
+//   bits 60..62  industry group
+//   bit  63      arbitrary address capable
+
+/// Address claim PGN
+pub const ADDRESS_CLAIM: u32 = 60928;
+/// The source address of nodes without an address
+pub const NULL_ADDRESS: u8 = 254;
+
+/// A 64 bit ISO NAME, ordered by arbitration priority: the lower NAME has the higher priority
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IsoName(pub u64);
+
+/// The fields of an ISO NAME
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NameFields {
+    /// 21 bits
+    pub unique_number: u32,
+    /// 11 bits
+    pub manufacturer_code: u16,
+    /// 3 bits
+    pub device_instance_lower: u8,
+    /// 5 bits
+    pub device_instance_upper: u8,
+    pub device_function: u8,
+    /// 7 bits
+    pub device_class: u8,
+    /// 4 bits
+    pub system_instance: u8,
+    /// 3 bits, 4 is marine
+    pub industry_group: u8,
+    pub arbitrary_address_capable: bool,
+}
+
+impl NameFields {
+    /// Join the fields, fails if a value exceeds its field
+    pub fn to_name(&self) -> Result<IsoName> {
+        let fields: [(&str, u64, usize, usize); 8] = [
+            ("unique number", self.unique_number as u64, 0, 21),
+            ("manufacturer code", self.manufacturer_code as u64, 21, 11),
+            (
+                "device instance lower",
+                self.device_instance_lower as u64,
+                32,
+                3,
+            ),
+            (
+                "device instance upper",
+                self.device_instance_upper as u64,
+                35,
+                5,
+            ),
+            ("device function", self.device_function as u64, 40, 8),
+            ("device class", self.device_class as u64, 49, 7),
+            ("system instance", self.system_instance as u64, 56, 4),
+            ("industry group", self.industry_group as u64, 60, 3),
+        ];
+        let mut raw = (self.arbitrary_address_capable as u64) << 63;
+        for (name, value, start, width) in fields.iter() {
+            if *value >> width != 0 {
+                return Err(Error::with_context(
+                    ErrorKind::OutOfRange,
+                    &format!(
+                        "NameFields::to_name: {} {} exceeds {} bits",
+                        name, value, width
+                    ),
+                ));
+            }
+            raw |= value << start;
+        }
+        Ok(IsoName(raw))
+    }
+}
+
+impl IsoName {
+    fn bits(&self, start: usize, width: usize) -> u64 {
+        self.0 >> start & ((1 << width) - 1)
+    }
+
+    pub fn unique_number(&self) -> u32 {
+        self.bits(0, 21) as u32
+    }
+
+    pub fn manufacturer_code(&self) -> u16 {
+        self.bits(21, 11) as u16
+    }
+
+    pub fn device_instance_lower(&self) -> u8 {
+        self.bits(32, 3) as u8
+    }
+
+    pub fn device_instance_upper(&self) -> u8 {
+        self.bits(35, 5) as u8
+    }
+
+    /// Get the 8 bit device instance, upper and lower joined
+    pub fn device_instance(&self) -> u8 {
+        self.bits(32, 8) as u8
+    }
+
+    pub fn device_function(&self) -> u8 {
+        self.bits(40, 8) as u8
+    }
+
+    pub fn device_class(&self) -> u8 {
+        self.bits(49, 7) as u8
+    }
+
+    pub fn system_instance(&self) -> u8 {
+        self.bits(56, 4) as u8
+    }
+
+    pub fn industry_group(&self) -> u8 {
+        self.bits(60, 3) as u8
+    }
+
+    pub fn arbitrary_address_capable(&self) -> bool {
+        self.bits(63, 1) == 1
+    }
+
+    pub fn fields(&self) -> NameFields {
+        NameFields {
+            unique_number: self.unique_number(),
+            manufacturer_code: self.manufacturer_code(),
+            device_instance_lower: self.device_instance_lower(),
+            device_instance_upper: self.device_instance_upper(),
+            device_function: self.device_function(),
+            device_class: self.device_class(),
+            system_instance: self.system_instance(),
+            industry_group: self.industry_group(),
+            arbitrary_address_capable: self.arbitrary_address_capable(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 8] {
+        self.0.to_le_bytes()
+    }
+
+    /// Build the frame claiming address
+    pub fn address_claim(&self, address: u8) -> Result<CanFrame> {
+        let id = N2kHeader::new(6, ADDRESS_CLAIM, address)
+            .with_destination(BROADCAST)
+            .to_can_id()?;
+        CanFrame::extended(id, &self.to_bytes())
+    }
+
+    /// Build the frame telling that no address could be claimed
+    pub fn cannot_claim(&self) -> Result<CanFrame> {
+        self.address_claim(NULL_ADDRESS)
+    }
+}
+
+/// Build the request for the address claims of destination, BROADCAST for all nodes
+pub fn request_address_claim(source: u8, destination: u8) -> Result<CanFrame> {
+    IsoRequest::new(ADDRESS_CLAIM).to_frame(source, destination)
+}
+
+/// Get the claimed address and the NAME of an address claim frame
+pub fn parse_address_claim(frame: &CanFrame) -> Result<(u8, IsoName)> {
+    let header = frame.header()?;
+    if header.pgn != ADDRESS_CLAIM {
+        return Err(Error::with_context(
+            ErrorKind::InvParam,
+            &format!("parse_address_claim: unexpected PGN {}", header.pgn),
+        ));
+    }
+    Ok((header.source, IsoName::from_bytes(frame.data())?))
+}
+
+impl BitDecode for IsoName {
+    fn decode(bitfield: &BitField) -> Result<IsoName> {
+        Ok(IsoName(bitfield.get_u64(0, 63, Endianness::Intel)?))
+    }
+}
+
+impl BitEncode for IsoName {
+    const BIT_LEN: usize = 64;
+
+    fn encode(&self, bitfield: &mut MutableBitField) -> Result<()> {
+        bitfield.set_u64(self.0, 0, 63, Endianness::Intel)
+    }
+}
+
+impl From<u64> for IsoName {
+    fn from(raw: u64) -> IsoName {
+        IsoName(raw)
+    }
+}
+
+impl From<IsoName> for u64 {
+    fn from(name: IsoName) -> u64 {
+        name.0
+    }
+}
+
+impl Display for IsoName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016X}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name() {
+        // a Garmin (229) GPS, class 60 navigation, function 145 own ship position
+        let fields = NameFields {
+            unique_number: 0x1_2345,
+            manufacturer_code: 229,
+            device_instance_lower: 1,
+            device_instance_upper: 2,
+            device_function: 145,
+            device_class: 60,
+            system_instance: 0,
+            industry_group: 4,
+            arbitrary_address_capable: true,
+        };
+        let name = fields.to_name().unwrap();
+        assert_eq!(name, IsoName(0xC078_9111_1CA1_2345));
+        assert_eq!(name.fields(), fields);
+        assert_eq!(name.device_instance(), 0x11);
+        assert_eq!(name.to_string(), "C07891111CA12345");
+        assert_eq!(
+            NameFields {
+                device_class: 128,
+                ..fields
+            }
+            .to_name()
+            .unwrap_err()
+            .kind(),
+            ErrorKind::OutOfRange
+        );
+
+        let data = name.to_vec().unwrap();
+        assert_eq!(data, [0x45, 0x23, 0xA1, 0x1C, 0x11, 0x91, 0x78, 0xC0]);
+        assert_eq!(IsoName::from_bytes(&data).unwrap(), name);
+        let mut buffer = [0; 8];
+        name.encode(&mut MutableBitField::new(&mut buffer)).unwrap();
+        assert_eq!(
+            BitField::new(&buffer)
+                .get_u64(0, 20, Endianness::Intel)
+                .unwrap(),
+            0x1_2345
+        );
+
+        // the lower NAME wins
+        let other = NameFields {
+            unique_number: 0x1_2346,
+            ..fields
+        }
+        .to_name()
+        .unwrap();
+        assert!(name < other);
+        assert_eq!(std::cmp::min(other, name), name);
+    }
+
+    #[test]
+    fn test_address_claim() {
+        let name = IsoName(0xC078_9111_1CA1_2345);
+        let frame = name.address_claim(0x23).unwrap();
+        assert_eq!(frame.id(), 0x18EE_FF23);
+        assert_eq!(parse_address_claim(&frame).unwrap(), (0x23, name));
+        assert_eq!(name.cannot_claim().unwrap().id(), 0x18EE_FFFE);
+
+        let request = request_address_claim(NULL_ADDRESS, BROADCAST).unwrap();
+        assert_eq!(request.id(), 0x18EA_FFFE);
+        assert_eq!(request.data(), [0x00, 0xEE, 0x00]);
+        assert!(parse_address_claim(&request).is_err());
+    }
+}
@@ -0,0 +1,955 @@
+use crate::can::{CanFrame, N2kHeader, BROADCAST};
+use crate::clock::Clock;
+use crate::error::{Error, ErrorKind, Result};
+use crate::fast_packet::Packet;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+// ISO 11783-3 / J1939-21 transport protocol for messages of 9 to 1785 bytes. A session is
+// opened with a connection management frame (TP.CM, PGN 60416) and the payload follows in
+// data transfer frames (TP.DT, PGN 60160) of a sequence number and 7 bytes:
+//
+//   BAM      broadcast, the sender announces the message and sends all packets with a gap
+//   RTS/CTS  addressed, the receiver grants windows of packets with CTS and confirms the
+//            message with an end of message acknowledgement, either side can abort
+//
+// TP.CM frames carry the control byte first and the transferred PGN in bytes 5 to 7:
+//
+//   16   RTS   size (2), packets, max packets per CTS
+//   17   CTS   packets, next packet, 0xFF, 0xFF
+//   19   EOMA  size (2), packets, 0xFF
+//   32   BAM   size (2), packets, 0xFF
+//   255  abort reason, 0xFF, 0xFF, 0xFF
+//
+// One session is kept per source and destination, so a node can receive from several nodes
+// and send to several nodes at once.
+
+/// Connection management PGN
+pub const TP_CM: u32 = 60416;
+/// Data transfer PGN
+pub const TP_DT: u32 = 60160;
+/// Largest transport protocol payload
+pub const MAX_TP_LEN: usize = 1785;
+/// Number of retransmit requests before a session is aborted
+const MAX_RETRANSMITS: u8 = 2;
+
+const RTS: u8 = 16;
+const CTS: u8 = 17;
+const EOMA: u8 = 19;
+const BAM: u8 = 32;
+const ABORT: u8 = 255;
+
+/// Why a session was aborted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbortReason {
+    AlreadyInSession,
+    Resources,
+    Timeout,
+    CtsDuringTransfer,
+    MaxRetransmits,
+    UnexpectedData,
+    BadSequence,
+    DuplicateSequence,
+    /// ETP: a data packet offset was not expected
+    UnexpectedDpo,
+    /// ETP: the data packet offset names another PGN
+    BadDpoPgn,
+    /// ETP: the data packet offset covers more packets than the CTS
+    DpoExceedsCts,
+    /// ETP: the data packet offset does not follow the CTS
+    BadDpoOffset,
+    /// ETP: the CTS names another PGN
+    UnexpectedCtsPgn,
+    /// ETP: the CTS requests packets beyond the message
+    CtsExceedsSize,
+    Other(u8),
+}
+
+impl From<u8> for AbortReason {
+    fn from(value: u8) -> AbortReason {
+        match value {
+            1 => AbortReason::AlreadyInSession,
+            2 => AbortReason::Resources,
+            3 => AbortReason::Timeout,
+            4 => AbortReason::CtsDuringTransfer,
+            5 => AbortReason::MaxRetransmits,
+            6 => AbortReason::UnexpectedData,
+            7 => AbortReason::BadSequence,
+            8 => AbortReason::DuplicateSequence,
+            9 => AbortReason::UnexpectedDpo,
+            10 => AbortReason::BadDpoPgn,
+            11 => AbortReason::DpoExceedsCts,
+            12 => AbortReason::BadDpoOffset,
+            14 => AbortReason::UnexpectedCtsPgn,
+            15 => AbortReason::CtsExceedsSize,
+            value => AbortReason::Other(value),
+        }
+    }
+}
+
+impl From<AbortReason> for u8 {
+    fn from(reason: AbortReason) -> u8 {
+        match reason {
+            AbortReason::AlreadyInSession => 1,
+            AbortReason::Resources => 2,
+            AbortReason::Timeout => 3,
+            AbortReason::CtsDuringTransfer => 4,
+            AbortReason::MaxRetransmits => 5,
+            AbortReason::UnexpectedData => 6,
+            AbortReason::BadSequence => 7,
+            AbortReason::DuplicateSequence => 8,
+            AbortReason::UnexpectedDpo => 9,
+            AbortReason::BadDpoPgn => 10,
+            AbortReason::DpoExceedsCts => 11,
+            AbortReason::BadDpoOffset => 12,
+            AbortReason::UnexpectedCtsPgn => 14,
+            AbortReason::CtsExceedsSize => 15,
+            AbortReason::Other(value) => value,
+        }
+    }
+}
+
+/// The protocol timeouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timeouts {
+    /// receiver, between data packets
+    pub t1: Duration,
+    /// receiver, from CTS to the first data packet
+    pub t2: Duration,
+    /// sender, from RTS or the last packet of a window to CTS or EOMA
+    pub t3: Duration,
+    /// sender, from a hold CTS to the next CTS
+    pub t4: Duration,
+}
+
+impl Default for Timeouts {
+    fn default() -> Timeouts {
+        Timeouts {
+            t1: Duration::from_millis(750),
+            t2: Duration::from_millis(1250),
+            t3: Duration::from_millis(1250),
+            t4: Duration::from_millis(1050),
+        }
+    }
+}
+
+/// Session results other than received messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TpEvent {
+    /// all packets were broadcast or the receiver acknowledged the message
+    Sent { pgn: u32, destination: u8 },
+    /// a session ended early, local is true if this node aborted it
+    Aborted {
+        pgn: u32,
+        source: u8,
+        destination: u8,
+        reason: AbortReason,
+        local: bool,
+    },
+}
+
+/// Get the number of data packets for a payload of size bytes
+pub fn packet_count(size: usize) -> usize {
+    size.div_ceil(7)
+}
+
+#[derive(Debug)]
+struct RxSession {
+    header: N2kHeader,
+    size: usize,
+    packets: u8,
+    next: u8,
+    /// last packet of the current CTS window, None for BAM
+    window_end: Option<u8>,
+    /// packets per CTS the sender accepts, from its RTS
+    max_window: u8,
+    data: Vec<u8>,
+    deadline: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TxState {
+    Broadcast { next_at: Duration },
+    WaitCts,
+    Hold,
+}
+
+#[derive(Debug)]
+struct TxSession {
+    pgn: u32,
+    data: Vec<u8>,
+    packets: u8,
+    /// packets sent so far
+    sent: u8,
+    retransmits: u8,
+    state: TxState,
+    deadline: Duration,
+}
+
+/// Transport protocol sessions of one node
+///
+/// Received frames are passed to `push`, which returns completed messages. Frames to send are
+/// collected until taken with `take_frames`, `poll` sends due broadcast packets and handles
+/// timeouts and should be called regularly.
+#[derive(Debug)]
+pub struct IsoTransport<C: Clock> {
+    clock: C,
+    address: u8,
+    timeouts: Timeouts,
+    window: u8,
+    bam_gap: Duration,
+    /// by source and destination
+    rx: BTreeMap<(u8, u8), RxSession>,
+    /// by destination
+    tx: BTreeMap<u8, TxSession>,
+    frames: Vec<CanFrame>,
+    events: Vec<TpEvent>,
+}
+
+impl<C: Clock> IsoTransport<C> {
+    /// Create the sessions of the node at address, granting up to 16 packets per CTS and
+    /// broadcasting a packet every 50 ms
+    pub fn new(address: u8, clock: C) -> IsoTransport<C> {
+        IsoTransport {
+            clock,
+            address,
+            timeouts: Timeouts::default(),
+            window: 16,
+            bam_gap: Duration::from_millis(50),
+            rx: BTreeMap::new(),
+            tx: BTreeMap::new(),
+            frames: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Set the timeouts, returns self for chaining
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> IsoTransport<C> {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Set the packets granted per CTS, returns self for chaining
+    pub fn with_window(mut self, window: u8) -> IsoTransport<C> {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Set the gap between broadcast packets, returns self for chaining
+    pub fn with_bam_gap(mut self, gap: Duration) -> IsoTransport<C> {
+        self.bam_gap = gap;
+        self
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    pub fn set_address(&mut self, address: u8) {
+        self.address = address;
+    }
+
+    /// Whether no session is open
+    pub fn is_idle(&self) -> bool {
+        self.rx.is_empty() && self.tx.is_empty()
+    }
+
+    /// Take the frames to send
+    pub fn take_frames(&mut self) -> Vec<CanFrame> {
+        std::mem::take(&mut self.frames)
+    }
+
+    /// Take the events so far
+    pub fn take_events(&mut self) -> Vec<TpEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Start sending data to destination, broadcast messages use BAM
+    pub fn send(&mut self, pgn: u32, destination: u8, data: &[u8]) -> Result<()> {
+        if data.len() <= 8 || data.len() > MAX_TP_LEN {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("IsoTransport::send: invalid payload length {}", data.len()),
+            ));
+        }
+        if self.tx.contains_key(&destination) {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("IsoTransport::send: session to {} in progress", destination),
+            ));
+        }
+        let now = self.clock.now();
+        let packets = packet_count(data.len()) as u8;
+        let size = (data.len() as u16).to_le_bytes();
+        let state = if destination == BROADCAST {
+            self.cm(destination, [BAM, size[0], size[1], packets, 0xFF], pgn)?;
+            TxState::Broadcast {
+                next_at: now + self.bam_gap,
+            }
+        } else {
+            self.cm(destination, [RTS, size[0], size[1], packets, 0xFF], pgn)?;
+            TxState::WaitCts
+        };
+        self.tx.insert(
+            destination,
+            TxSession {
+                pgn,
+                data: data.to_vec(),
+                packets,
+                sent: 0,
+                retransmits: 0,
+                state,
+                deadline: now + self.timeouts.t3,
+            },
+        );
+        Ok(())
+    }
+
+    /// Abort the session sending to destination
+    pub fn abort(&mut self, destination: u8, reason: AbortReason) -> Result<()> {
+        if let Some(session) = self.tx.remove(&destination) {
+            self.abort_session(session.pgn, self.address, destination, reason)?;
+        }
+        Ok(())
+    }
+
+    /// Handle a received frame, returns the message it completes
+    pub fn push(&mut self, frame: &CanFrame) -> Result<Option<Packet>> {
+        self.poll()?;
+        let header = match frame.header() {
+            Ok(header) => header,
+            Err(_) => return Ok(None),
+        };
+        if header.source == self.address
+            || (header.destination != BROADCAST && header.destination != self.address)
+        {
+            return Ok(None);
+        }
+        let data = frame.data();
+        match header.pgn {
+            TP_CM if data.len() == 8 => {
+                self.handle_cm(&header, data)?;
+                Ok(None)
+            }
+            TP_DT if data.len() == 8 => self.handle_dt(&header, data),
+            _ => Ok(None),
+        }
+    }
+
+    /// Send due broadcast packets and abort timed out sessions
+    pub fn poll(&mut self) -> Result<()> {
+        let now = self.clock.now();
+        let destinations: Vec<u8> = self.tx.keys().copied().collect();
+        for destination in destinations {
+            let session = self.tx.get_mut(&destination).unwrap();
+            if let TxState::Broadcast { next_at } = session.state {
+                if next_at > now {
+                    continue;
+                }
+                let (pgn, sequence) = (session.pgn, session.sent + 1);
+                session.sent = sequence;
+                session.state = TxState::Broadcast {
+                    next_at: next_at + self.bam_gap,
+                };
+                self.dt(destination, destination, sequence)?;
+                if sequence == self.tx[&destination].packets {
+                    self.tx.remove(&destination);
+                    self.events.push(TpEvent::Sent { pgn, destination });
+                }
+            } else if session.deadline < now {
+                let pgn = session.pgn;
+                self.tx.remove(&destination);
+                self.abort_session(pgn, self.address, destination, AbortReason::Timeout)?;
+            }
+        }
+        let expired: Vec<(u8, u8)> = self
+            .rx
+            .iter()
+            .filter(|(_, session)| session.deadline < now)
+            .map(|(key, _)| *key)
+            .collect();
+        for key in expired {
+            let session = self.rx.remove(&key).unwrap();
+            self.abort_session(session.header.pgn, key.0, key.1, AbortReason::Timeout)?;
+        }
+        Ok(())
+    }
+
+    fn handle_cm(&mut self, header: &N2kHeader, data: &[u8]) -> Result<()> {
+        let now = self.clock.now();
+        let pgn = u32::from_le_bytes([data[5], data[6], data[7], 0]);
+        let size = u16::from_le_bytes([data[1], data[2]]) as usize;
+        let (source, destination) = (header.source, header.destination);
+        match data[0] {
+            BAM | RTS => {
+                let bam = data[0] == BAM;
+                if bam != (destination == BROADCAST) {
+                    return Ok(());
+                }
+                let valid =
+                    (9..=MAX_TP_LEN).contains(&size) && data[3] as usize == packet_count(size);
+                if !valid {
+                    if !bam {
+                        self.abort_session(pgn, source, destination, AbortReason::Resources)?;
+                    }
+                    return Ok(());
+                }
+                if let Some(session) = self.rx.get(&(source, destination)) {
+                    // a repeated RTS restarts the session, a new PGN has to wait
+                    if !bam && session.header.pgn != pgn {
+                        return self.cm(
+                            source,
+                            [
+                                ABORT,
+                                AbortReason::AlreadyInSession.into(),
+                                0xFF,
+                                0xFF,
+                                0xFF,
+                            ],
+                            pgn,
+                        );
+                    }
+                }
+                let mut session = RxSession {
+                    header: N2kHeader {
+                        priority: header.priority,
+                        pgn,
+                        source,
+                        destination,
+                    },
+                    size,
+                    packets: data[3],
+                    next: 1,
+                    window_end: None,
+                    max_window: data[4].max(1),
+                    data: Vec::with_capacity(data[3] as usize * 7),
+                    deadline: now + self.timeouts.t1,
+                };
+                if !bam {
+                    let window = data[3].min(self.window).min(session.max_window);
+                    session.window_end = Some(window);
+                    session.deadline = now + self.timeouts.t2;
+                    self.cm(source, [CTS, window, 1, 0xFF, 0xFF], pgn)?;
+                }
+                self.rx.insert((source, destination), session);
+            }
+            CTS => {
+                let session = match self.tx.get_mut(&source) {
+                    Some(session)
+                        if session.pgn == pgn
+                            && !matches!(session.state, TxState::Broadcast { .. }) =>
+                    {
+                        session
+                    }
+                    _ => return Ok(()),
+                };
+                let (count, next) = (data[1], data[2]);
+                if count == 0 {
+                    session.state = TxState::Hold;
+                    session.deadline = now + self.timeouts.t4;
+                    return Ok(());
+                }
+                let last = next as usize + count as usize - 1;
+                if next == 0 || last > session.packets as usize {
+                    self.tx.remove(&source);
+                    return self.abort_session(pgn, self.address, source, AbortReason::BadSequence);
+                }
+                if next <= session.sent {
+                    session.retransmits += 1;
+                    if session.retransmits > MAX_RETRANSMITS {
+                        self.tx.remove(&source);
+                        return self.abort_session(
+                            pgn,
+                            self.address,
+                            source,
+                            AbortReason::MaxRetransmits,
+                        );
+                    }
+                }
+                session.sent = session.sent.max(last as u8);
+                session.state = TxState::WaitCts;
+                session.deadline = now + self.timeouts.t3;
+                for sequence in next..=last as u8 {
+                    self.dt(source, source, sequence)?;
+                }
+            }
+            EOMA if self
+                .tx
+                .get(&source)
+                .is_some_and(|session| session.pgn == pgn) =>
+            {
+                self.tx.remove(&source);
+                self.events.push(TpEvent::Sent {
+                    pgn,
+                    destination: source,
+                });
+            }
+            ABORT => {
+                let reason = AbortReason::from(data[1]);
+                if self
+                    .rx
+                    .get(&(source, destination))
+                    .is_some_and(|session| session.header.pgn == pgn)
+                {
+                    self.rx.remove(&(source, destination));
+                    self.events.push(TpEvent::Aborted {
+                        pgn,
+                        source,
+                        destination,
+                        reason,
+                        local: false,
+                    });
+                }
+                if self
+                    .tx
+                    .get(&source)
+                    .is_some_and(|session| session.pgn == pgn)
+                {
+                    self.tx.remove(&source);
+                    self.events.push(TpEvent::Aborted {
+                        pgn,
+                        source: self.address,
+                        destination: source,
+                        reason,
+                        local: false,
+                    });
+                }
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn handle_dt(&mut self, header: &N2kHeader, data: &[u8]) -> Result<Option<Packet>> {
+        let now = self.clock.now();
+        let key = (header.source, header.destination);
+        let session = match self.rx.get_mut(&key) {
+            Some(session) => session,
+            None => return Ok(None),
+        };
+        let sequence = data[0];
+        if sequence != session.next || session.window_end.is_some_and(|end| sequence > end) {
+            let reason = if sequence < session.next {
+                AbortReason::DuplicateSequence
+            } else {
+                AbortReason::BadSequence
+            };
+            let pgn = session.header.pgn;
+            self.rx.remove(&key);
+            self.abort_session(pgn, key.0, key.1, reason)?;
+            return Ok(None);
+        }
+        session.data.extend_from_slice(&data[1..]);
+        session.deadline = now + self.timeouts.t1;
+        let (pgn, source) = (session.header.pgn, session.header.source);
+        // complete before counting on, the last of 255 packets has no successor
+        if sequence == session.packets {
+            let mut session = self.rx.remove(&key).unwrap();
+            session.data.truncate(session.size);
+            if session.window_end.is_some() {
+                let size = (session.size as u16).to_le_bytes();
+                self.cm(source, [EOMA, size[0], size[1], session.packets, 0xFF], pgn)?;
+            }
+            return Ok(Some(Packet {
+                header: session.header,
+                data: session.data,
+                timestamp: now,
+            }));
+        }
+        session.next += 1;
+        if session.window_end == Some(sequence) {
+            let window = (session.packets - sequence)
+                .min(self.window)
+                .min(session.max_window);
+            session.window_end = Some(sequence + window);
+            session.deadline = now + self.timeouts.t2;
+            self.cm(source, [CTS, window, sequence + 1, 0xFF, 0xFF], pgn)?;
+        }
+        Ok(None)
+    }
+
+    /// Report an aborted session and tell the peer unless the session was a broadcast
+    fn abort_session(
+        &mut self,
+        pgn: u32,
+        source: u8,
+        destination: u8,
+        reason: AbortReason,
+    ) -> Result<()> {
+        self.events.push(TpEvent::Aborted {
+            pgn,
+            source,
+            destination,
+            reason,
+            local: true,
+        });
+        if destination == BROADCAST {
+            return Ok(());
+        }
+        let peer = if source == self.address {
+            destination
+        } else {
+            source
+        };
+        self.cm(peer, [ABORT, reason.into(), 0xFF, 0xFF, 0xFF], pgn)
+    }
+
+    fn cm(&mut self, destination: u8, control: [u8; 5], pgn: u32) -> Result<()> {
+        let frame = control_frame(TP_CM, self.address, destination, control, pgn)?;
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    fn dt(&mut self, session: u8, destination: u8, sequence: u8) -> Result<()> {
+        let start = (sequence as usize - 1) * 7;
+        let frame = data_frame(
+            TP_DT,
+            self.address,
+            destination,
+            sequence,
+            &self.tx[&session].data[start..],
+        )?;
+        self.frames.push(frame);
+        Ok(())
+    }
+}
+
+/// Build a connection management frame of the control byte, 4 parameter bytes and the
+/// transferred PGN
+pub(crate) fn control_frame(
+    cm: u32,
+    source: u8,
+    destination: u8,
+    control: [u8; 5],
+    pgn: u32,
+) -> Result<CanFrame> {
+    let pgn = pgn.to_le_bytes();
+    let mut data = [0; 8];
+    data[..5].copy_from_slice(&control);
+    data[5..].copy_from_slice(&pgn[..3]);
+    frame(cm, source, destination, &data)
+}
+
+/// Build a data transfer frame of the sequence number and up to 7 bytes of chunk, padded
+/// with 0xFF
+pub(crate) fn data_frame(
+    dt: u32,
+    source: u8,
+    destination: u8,
+    sequence: u8,
+    chunk: &[u8],
+) -> Result<CanFrame> {
+    let mut data = [0xFF; 8];
+    data[0] = sequence;
+    let len = chunk.len().min(7);
+    data[1..=len].copy_from_slice(&chunk[..len]);
+    frame(dt, source, destination, &data)
+}
+
+fn frame(pgn: u32, source: u8, destination: u8, data: &[u8]) -> Result<CanFrame> {
+    let id = N2kHeader::new(7, pgn, source)
+        .with_destination(destination)
+        .to_can_id()?;
+    CanFrame::extended(id, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    /// Deliver the frames of from to to, returns the completed messages
+    fn pump(
+        from: &mut IsoTransport<ManualClock>,
+        to: &mut IsoTransport<ManualClock>,
+    ) -> Vec<Packet> {
+        from.take_frames()
+            .iter()
+            .filter_map(|frame| to.push(frame).unwrap())
+            .collect()
+    }
+
+    fn nodes(clock: &ManualClock) -> (IsoTransport<ManualClock>, IsoTransport<ManualClock>) {
+        (
+            IsoTransport::new(0x10, clock.clone()),
+            IsoTransport::new(0x20, clock.clone()).with_window(2),
+        )
+    }
+
+    #[test]
+    fn test_bam() {
+        let clock = ManualClock::new();
+        let (mut sender, mut receiver) = nodes(&clock);
+        let data: Vec<u8> = (0..20).collect();
+        sender.send(65240, BROADCAST, &data).unwrap();
+        assert!(sender.send(65240, BROADCAST, &data).is_err());
+        let frames = sender.take_frames();
+        assert_eq!(frames[0].data(), [32, 20, 0, 3, 0xFF, 0xD8, 0xFE, 0x00]);
+        assert_eq!(frames[0].header().unwrap().pgn, TP_CM);
+        assert!(receiver.push(&frames[0]).unwrap().is_none());
+
+        let mut packets = Vec::new();
+        for _ in 0..3 {
+            clock.advance(ms(50));
+            sender.poll().unwrap();
+            let frames = sender.take_frames();
+            assert_eq!(frames.len(), 1);
+            packets.extend(
+                frames
+                    .iter()
+                    .filter_map(|frame| receiver.push(frame).unwrap()),
+            );
+        }
+        assert_eq!(frames_data(&packets), vec![data]);
+        assert_eq!(packets[0].header, N2kHeader::new(7, 65240, 0x10));
+        assert_eq!(
+            sender.take_events(),
+            vec![TpEvent::Sent {
+                pgn: 65240,
+                destination: BROADCAST
+            }]
+        );
+        assert!(sender.is_idle() && receiver.is_idle());
+
+        // a missing packet drops the message after T1 without an abort frame
+        sender.send(65240, BROADCAST, &[1; 20]).unwrap();
+        pump(&mut sender, &mut receiver);
+        clock.advance(ms(100));
+        sender.poll().unwrap();
+        sender.take_frames();
+        clock.advance(ms(800));
+        receiver.poll().unwrap();
+        assert!(receiver.take_frames().is_empty());
+        assert!(matches!(
+            receiver.take_events()[..],
+            [TpEvent::Aborted {
+                reason: AbortReason::Timeout,
+                local: true,
+                ..
+            }]
+        ));
+    }
+
+    fn frames_data(packets: &[Packet]) -> Vec<Vec<u8>> {
+        packets.iter().map(|packet| packet.data.clone()).collect()
+    }
+
+    #[test]
+    fn test_rts_cts() {
+        let clock = ManualClock::new();
+        let (mut sender, mut receiver) = nodes(&clock);
+        let data: Vec<u8> = (0..30).collect();
+        sender.send(126208, 0x20, &data).unwrap();
+        let rts = sender.take_frames();
+        assert_eq!(rts[0].data(), [16, 30, 0, 5, 0xFF, 0x00, 0xED, 0x01]);
+        assert!(receiver.push(&rts[0]).unwrap().is_none());
+        // windows of 2, 2 and 1 packets
+        let mut packets = Vec::new();
+        for window in &[2, 2, 1] {
+            let cts = receiver.take_frames();
+            assert_eq!(cts.len(), 1);
+            assert_eq!(cts[0].data()[..2], [17, *window]);
+            sender.push(&cts[0]).unwrap();
+            clock.advance(ms(10));
+            packets.extend(pump(&mut sender, &mut receiver));
+        }
+        assert_eq!(frames_data(&packets), vec![data]);
+        assert_eq!(packets[0].header.destination, 0x20);
+        let eoma = receiver.take_frames();
+        assert_eq!(eoma[0].data(), [19, 30, 0, 5, 0xFF, 0x00, 0xED, 0x01]);
+        sender.push(&eoma[0]).unwrap();
+        assert_eq!(
+            sender.take_events(),
+            vec![TpEvent::Sent {
+                pgn: 126208,
+                destination: 0x20
+            }]
+        );
+        assert!(sender.is_idle() && receiver.is_idle());
+    }
+
+    #[test]
+    fn test_max_len() {
+        let clock = ManualClock::new();
+        let (mut sender, mut receiver) = nodes(&clock);
+        let data: Vec<u8> = (0..MAX_TP_LEN).map(|i| i as u8).collect();
+
+        sender.send(126208, 0x20, &data).unwrap();
+        let mut packets = pump(&mut sender, &mut receiver);
+        while packets.is_empty() {
+            pump(&mut receiver, &mut sender);
+            packets = pump(&mut sender, &mut receiver);
+        }
+        assert_eq!(frames_data(&packets), vec![data.clone()]);
+        pump(&mut receiver, &mut sender);
+        assert_eq!(sender.take_events().len(), 1);
+
+        sender.send(65240, BROADCAST, &data).unwrap();
+        let mut packets = pump(&mut sender, &mut receiver);
+        for _ in 0..255 {
+            clock.advance(ms(50));
+            sender.poll().unwrap();
+            packets.extend(pump(&mut sender, &mut receiver));
+        }
+        assert_eq!(frames_data(&packets), vec![data]);
+        assert!(sender.is_idle() && receiver.is_idle());
+    }
+
+    #[test]
+    fn test_sender_window() {
+        // the sender accepts 2 packets per CTS, the receiver would grant 255
+        let clock = ManualClock::new();
+        let mut receiver = IsoTransport::new(0x20, clock.clone());
+        let rts = control_frame(TP_CM, 0x10, 0x20, [16, 30, 0, 5, 2], 126208).unwrap();
+        receiver.push(&rts).unwrap();
+        let mut windows = Vec::new();
+        let mut packets = Vec::new();
+        for sequence in 1..=5 {
+            windows.extend(
+                receiver
+                    .take_frames()
+                    .iter()
+                    .map(|frame| frame.data()[..3].to_vec()),
+            );
+            let frame = data_frame(TP_DT, 0x10, 0x20, sequence, &[sequence; 7]).unwrap();
+            packets.extend(receiver.push(&frame).unwrap());
+        }
+        assert_eq!(
+            windows,
+            vec![vec![17, 2, 1], vec![17, 2, 3], vec![17, 1, 5]]
+        );
+        assert_eq!(packets[0].data.len(), 30);
+    }
+
+    #[test]
+    fn test_concurrent() {
+        let clock = ManualClock::new();
+        let mut a = IsoTransport::new(0x10, clock.clone());
+        let mut b = IsoTransport::new(0x11, clock.clone());
+        let mut receiver = IsoTransport::new(0x20, clock.clone());
+        a.send(126464, 0x20, &[0xAA; 12]).unwrap();
+        b.send(126464, 0x20, &[0xBB; 40]).unwrap();
+        a.send(130000, BROADCAST, &[0xCC; 9]).unwrap();
+        pump(&mut a, &mut receiver);
+        pump(&mut b, &mut receiver);
+        let cts = receiver.take_frames();
+        assert_eq!(cts.len(), 2);
+        for frame in &cts {
+            a.push(frame).unwrap();
+            b.push(frame).unwrap();
+        }
+        let mut frames = b.take_frames();
+        for _ in 0..2 {
+            clock.advance(ms(50));
+            a.poll().unwrap();
+        }
+        frames.extend(a.take_frames());
+        frames.sort_by_key(|frame| frame.data()[0]);
+        let packets: Vec<Packet> = frames
+            .iter()
+            .filter_map(|frame| receiver.push(frame).unwrap())
+            .collect();
+        let mut sources: Vec<(u8, u32, usize)> = packets
+            .iter()
+            .map(|packet| (packet.header.source, packet.header.pgn, packet.data.len()))
+            .collect();
+        sources.sort_unstable();
+        assert_eq!(
+            sources,
+            vec![(0x10, 126464, 12), (0x10, 130000, 9), (0x11, 126464, 40)]
+        );
+    }
+
+    #[test]
+    fn test_aborts() {
+        let clock = ManualClock::new();
+        let (mut sender, mut receiver) = nodes(&clock);
+        let aborted = |events: Vec<TpEvent>| match events[..] {
+            [TpEvent::Aborted { reason, local, .. }] => (reason, local),
+            _ => panic!("unexpected {:?}", events),
+        };
+
+        // T3: no CTS
+        sender.send(126208, 0x20, &[0; 30]).unwrap();
+        sender.take_frames();
+        clock.advance(ms(1300));
+        sender.poll().unwrap();
+        assert_eq!(aborted(sender.take_events()), (AbortReason::Timeout, true));
+        let abort = sender.take_frames();
+        assert_eq!(
+            abort[0].data(),
+            [255, 3, 0xFF, 0xFF, 0xFF, 0x00, 0xED, 0x01]
+        );
+
+        // T4: hold without a following CTS
+        sender.send(126208, 0x20, &[0; 30]).unwrap();
+        pump(&mut sender, &mut receiver);
+        let mut hold = receiver.take_frames()[0].clone();
+        hold = CanFrame::extended(hold.id(), &[17, 0, 1, 0xFF, 0xFF, 0x00, 0xED, 0x01]).unwrap();
+        sender.push(&hold).unwrap();
+        clock.advance(ms(1100));
+        sender.poll().unwrap();
+        assert_eq!(aborted(sender.take_events()), (AbortReason::Timeout, true));
+        // the receiver learns about it from the abort frame
+        pump(&mut sender, &mut receiver);
+        assert_eq!(
+            aborted(receiver.take_events()),
+            (AbortReason::Timeout, false)
+        );
+
+        // T2: no data after CTS
+        sender.send(126208, 0x20, &[0; 30]).unwrap();
+        pump(&mut sender, &mut receiver);
+        receiver.take_frames();
+        clock.advance(ms(1300));
+        receiver.poll().unwrap();
+        assert_eq!(
+            aborted(receiver.take_events()),
+            (AbortReason::Timeout, true)
+        );
+        assert_eq!(receiver.take_frames()[0].data()[..2], [255, 3]);
+        sender.poll().unwrap();
+        sender.take_frames();
+        sender.take_events();
+
+        // bad sequence
+        sender.send(126208, 0x20, &[0; 30]).unwrap();
+        pump(&mut sender, &mut receiver);
+        pump(&mut receiver, &mut sender);
+        let data = sender.take_frames();
+        receiver.push(&data[1]).unwrap();
+        assert_eq!(
+            aborted(receiver.take_events()),
+            (AbortReason::BadSequence, true)
+        );
+        pump(&mut receiver, &mut sender);
+        sender.take_events();
+
+        // a second PGN from the same source waits
+        sender.send(126208, 0x20, &[0; 30]).unwrap();
+        pump(&mut sender, &mut receiver);
+        receiver.take_frames();
+        let rts = N2kHeader::new(7, TP_CM, 0x10)
+            .with_destination(0x20)
+            .to_can_id()
+            .unwrap();
+        let rts = CanFrame::extended(rts, &[16, 9, 0, 2, 0xFF, 0x00, 0xEE, 0x01]).unwrap();
+        receiver.push(&rts).unwrap();
+        assert_eq!(receiver.take_frames()[0].data()[..2], [255, 1]);
+
+        // local abort
+        sender.abort(0x20, AbortReason::Resources).unwrap();
+        pump(&mut sender, &mut receiver);
+        assert_eq!(
+            aborted(receiver.take_events()),
+            (AbortReason::Resources, false)
+        );
+        assert!(sender.is_idle() && receiver.is_idle());
+        assert!(sender.send(126208, 0x20, &[0; 8]).is_err());
+        assert!(sender.send(126208, 0x20, &[0; 1786]).is_err());
+    }
+}
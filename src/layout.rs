@@ -0,0 +1,263 @@
+use crate::{BitField, Endianness, FieldSpec};
+use std::fmt::Write;
+
+// Annotated dump of a payload: which bits belong to which field and what the fields decode to.
+
+/// A named field to show in a layout dump
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutField {
+    pub name: String,
+    pub spec: FieldSpec,
+}
+
+impl LayoutField {
+    pub fn new(name: &str, spec: FieldSpec) -> LayoutField {
+        LayoutField {
+            name: name.to_owned(),
+            spec,
+        }
+    }
+}
+
+/// Decoded view of one field in a layout dump
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutEntry {
+    pub name: String,
+    pub start: usize,
+    pub end: usize,
+    /// raw bits, None if the field is out of range of the payload
+    pub raw: Option<u64>,
+    /// unscaled value, None if out of range or not available
+    pub value: Option<i64>,
+    /// scaled value, None if out of range or not available
+    pub scaled: Option<f64>,
+    pub na: bool,
+}
+
+/// Decode all fields for a layout dump
+pub fn decode_layout(bitfield: &BitField, fields: &[LayoutField]) -> Vec<LayoutEntry> {
+    fields
+        .iter()
+        .map(|field| {
+            let raw = field.spec.get_raw(bitfield).ok();
+            let value = field.spec.get_i64(bitfield).unwrap_or(None);
+            LayoutEntry {
+                name: field.name.clone(),
+                start: field.spec.start,
+                end: field.spec.end,
+                raw,
+                value,
+                scaled: field.spec.get_f64(bitfield).unwrap_or(None),
+                na: raw.map(|raw| field.spec.is_na(raw)).unwrap_or(false),
+            }
+        })
+        .collect()
+}
+
+const MARKERS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+fn marker(index: usize) -> char {
+    MARKERS
+        .get(index)
+        .map(|marker| *marker as char)
+        .unwrap_or('*')
+}
+
+/// Render an ASCII diagram of the payload with one row per byte showing which field owns
+/// each bit ('.' = unused, '#' = overlapping fields), followed by a table of decoded fields
+pub fn render_layout(bitfield: &BitField, fields: &[LayoutField]) -> String {
+    let bytes = bitfield.as_bytes();
+    let mut owners: Vec<Option<usize>> = vec![None; bitfield.bit_len()];
+    let mut overlaps = vec![false; bitfield.bit_len()];
+    for (index, field) in fields.iter().enumerate() {
+        for bit in field.spec.start..=field.spec.end.min(bitfield.bit_len().max(1) - 1) {
+            // the grid shows the most significant bit of each byte first
+            let bit = match field.spec.endianness {
+                Endianness::Intel => bit / 8 * 8 + 7 - bit % 8,
+                _ => bit,
+            };
+            if bit < owners.len() {
+                if owners[bit].is_some() {
+                    overlaps[bit] = true;
+                }
+                owners[bit] = Some(index);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "byte | 0 1 2 3 4 5 6 7 | bits     | hex");
+    for (byte_index, byte) in bytes.iter().enumerate() {
+        let _ = write!(out, "{:>4} |", byte_index);
+        for bit in byte_index * 8..byte_index * 8 + 8 {
+            let cell = match owners[bit] {
+                _ if overlaps[bit] => '#',
+                Some(index) => marker(index),
+                None => '.',
+            };
+            let _ = write!(out, " {}", cell);
+        }
+        let _ = writeln!(out, " | {:08b} | {:02X}", byte, byte);
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "  {:<24} {:<11} {:>18} {:>20}  value",
+        "field", "bits", "raw hex", "raw"
+    );
+    for (index, entry) in decode_layout(bitfield, fields).iter().enumerate() {
+        let digits = (entry.end.saturating_sub(entry.start) + 4) / 4;
+        let (raw_hex, raw) = match entry.raw {
+            Some(raw) => (
+                format!("0x{:0width$X}", raw, width = digits),
+                raw.to_string(),
+            ),
+            None => ("-".to_owned(), "-".to_owned()),
+        };
+        let value = match (entry.raw, entry.scaled) {
+            (None, _) => "OUT OF RANGE".to_owned(),
+            (_, _) if entry.na => "NA".to_owned(),
+            (_, Some(scaled)) => format_value(&fields[index].spec, entry.value, scaled),
+            (_, None) => "-".to_owned(),
+        };
+        let _ = writeln!(
+            out,
+            "{} {:<24} {:<11} {:>18} {:>20}  {}",
+            marker(index),
+            entry.name,
+            format!("{}..{}", entry.start, entry.end),
+            raw_hex,
+            raw,
+            value
+        );
+    }
+    out
+}
+
+fn format_value(spec: &FieldSpec, value: Option<i64>, scaled: f64) -> String {
+    match value {
+        Some(value) if spec.resolution == 1.0 && spec.offset == 0.0 => value.to_string(),
+        _ => scaled.to_string(),
+    }
+}
+
+/// Render the decoded fields as a JSON document
+pub fn render_layout_json(bitfield: &BitField, fields: &[LayoutField]) -> String {
+    let mut out = String::new();
+    let _ = write!(out, "{{\"bytes\":\"{}\",\"fields\":[", bitfield);
+    for (index, entry) in decode_layout(bitfield, fields).iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+        out.push_str("{\"name\":");
+        write_json_string(&mut out, &entry.name);
+        let _ = write!(
+            out,
+            ",\"start\":{},\"end\":{},\"raw\":{},\"value\":{},\"scaled\":{},\"na\":{}}}",
+            entry.start,
+            entry.end,
+            json_option(entry.raw),
+            json_option(entry.value),
+            json_option(entry.scaled.filter(|scaled| scaled.is_finite())),
+            entry.na
+        );
+    }
+    out.push_str("]}");
+    out
+}
+
+fn json_option<T: ToString>(value: Option<T>) -> String {
+    value
+        .map(|value| value.to_string())
+        .unwrap_or_else(|| "null".to_owned())
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Vec<LayoutField> {
+        vec![
+            LayoutField::new("sid", FieldSpec::new(0, 7, Endianness::Little)),
+            LayoutField::new(
+                "speed",
+                FieldSpec {
+                    resolution: 0.01,
+                    ..FieldSpec::new(8, 23, Endianness::Little)
+                },
+            ),
+            LayoutField::new("mode", FieldSpec::new(24, 27, Endianness::Big)),
+            LayoutField::new("depth", FieldSpec::new(32, 47, Endianness::Little)),
+        ]
+    }
+
+    #[test]
+    fn test_render_layout() {
+        let bitfield = BitField::new(&[0x09, 0xF8, 0x01, 0x3F]);
+        let dump = render_layout(&bitfield, &fields());
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines[1], "   0 | A A A A A A A A | 00001001 | 09");
+        assert_eq!(lines[3], "   2 | B B B B B B B B | 00000001 | 01");
+        assert_eq!(lines[4], "   3 | C C C C . . . . | 00111111 | 3F");
+        assert!(lines[7].starts_with("A sid "));
+        assert!(lines[7].ends_with("  9"));
+        assert!(lines[8].contains("0x01F8"));
+        assert!(lines[8].ends_with("  5.04"));
+        assert!(lines[9].ends_with("  3"));
+        assert!(lines[10].ends_with("OUT OF RANGE"));
+
+        let bitfield = BitField::new(&[0x09, 0xFF, 0xFF, 0x3F]);
+        let dump = render_layout(&bitfield, &fields());
+        assert!(dump.lines().nth(8).unwrap().ends_with("  NA"));
+    }
+
+    #[test]
+    fn test_render_layout_intel() {
+        // intel bits count from the least significant bit, the grid shows it last
+        let bitfield = BitField::new(&[0xA5, 0x3C]);
+        let fields = vec![
+            LayoutField::new("low", FieldSpec::new(0, 3, Endianness::Intel)),
+            LayoutField::new("high", FieldSpec::new(4, 11, Endianness::Intel)),
+        ];
+        let dump = render_layout(&bitfield, &fields);
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines[1], "   0 | B B B B A A A A | 10100101 | A5");
+        assert_eq!(lines[2], "   1 | . . . . B B B B | 00111100 | 3C");
+        assert!(lines[5].ends_with("  5"));
+        assert!(lines[6].ends_with("  202"));
+    }
+
+    #[test]
+    fn test_render_layout_json() {
+        let bitfield = BitField::new(&[0x09, 0xFF, 0xFF, 0x3F]);
+        let fields = vec![
+            LayoutField::new("s\"id", FieldSpec::new(0, 7, Endianness::Little)),
+            LayoutField::new("speed", FieldSpec::new(8, 23, Endianness::Little)),
+        ];
+        assert_eq!(
+            render_layout_json(&bitfield, &fields),
+            "{\"bytes\":\"09 FF FF 3F\",\"fields\":[\
+             {\"name\":\"s\\\"id\",\"start\":0,\"end\":7,\"raw\":9,\"value\":9,\"scaled\":9,\"na\":false},\
+             {\"name\":\"speed\",\"start\":8,\"end\":23,\"raw\":65535,\"value\":null,\"scaled\":null,\"na\":true}]}"
+        );
+    }
+}
@@ -1,5 +1,53 @@
 pub mod bitfield;
 mod error;
-pub use crate::bitfield::BitField;
+pub use crate::bitfield::{BitField, Endianness};
+pub use crate::error::{Error, ErrorKind, Result};
 pub mod mutable_bitfield;
 pub use crate::mutable_bitfield::MutableBitField;
+pub mod packed;
+mod varint;
+pub use crate::packed::{PackedArray, PackedVec};
+pub mod field;
+pub use crate::field::FieldSpec;
+pub mod codec;
+pub mod column;
+pub mod format;
+pub mod layout;
+pub use crate::codec::{BitDecode, BitEncode, BitLookup, BitVariant};
+#[cfg(feature = "derive")]
+pub use bitfield_derive::{BitDecode, BitEncode, BitLookup, BitVariant, BitView};
+pub mod schema;
+pub use crate::schema::{
+    FieldDescriptor, FieldType, IndirectLookup, Lookup, MessageSchema, RepeatingGroup, Transport,
+    Variant, Variants,
+};
+pub mod validate;
+pub use crate::validate::{validate, Issue};
+pub mod dynamic;
+pub use crate::dynamic::{decode_dynamic, encode_dynamic, DecodedMessage, Value};
+pub mod units;
+pub use crate::units::{Dimension, Measurement, Unit, UnitConverter};
+pub mod clock;
+pub use crate::clock::{Clock, ManualClock, SystemClock};
+pub mod can;
+pub use crate::can::{CanFrame, CanId, FrameSink, Interface, N2kHeader};
+pub mod fast_packet;
+pub use crate::fast_packet::{FastPacketRegistry, Fragmenter, Packet, Reassembler};
+pub mod iso_tp;
+pub use crate::iso_tp::{AbortReason, IsoTransport, TpEvent};
+pub mod etp;
+pub use crate::etp::ExtendedTransport;
+pub mod iso_name;
+pub use crate::iso_name::{IsoName, NameFields};
+pub mod address_claim;
+pub use crate::address_claim::{AddressClaimer, ClaimState};
+pub mod request;
+pub use crate::request::{AckControl, IsoAcknowledgement, IsoRequest, Responder};
+pub mod group_function;
+pub use crate::group_function::{GroupFunction, Parameter, ProprietaryId};
+#[cfg(feature = "canboat")]
+pub mod canboat;
+#[cfg(feature = "canboat")]
+pub use crate::canboat::PgnDatabase;
+#[cfg(feature = "canboat")]
+pub mod codegen;
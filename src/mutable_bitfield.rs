@@ -1,5 +1,6 @@
-use crate::error::{Error, ErrorKind, Result};
-use crate::BitField;
+use crate::error::{Error, ErrorKind, Result, ToError};
+use crate::{BitField, Endianness};
+use log::debug;
 
 pub struct MutableBitField<'a> {
     data: &'a mut [u8],
@@ -14,6 +15,194 @@ impl<'a> MutableBitField<'a> {
         BitField::new(self.data)
     }
 
+    /// Get the underlying bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        self.data
+    }
+
+    /// Get the underlying bytes for modification
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.data
+    }
+
+    /// Get the size of the bitfield in bits
+    pub fn bit_len(&self) -> usize {
+        self.data.len() * 8
+    }
+
+    /// Set a single bit
+    pub fn set_bit(&mut self, index: usize, value: bool) -> Result<()> {
+        self.set_bits(value as u8, index / 8, index % 8, index % 8)
+            .error_with_all(
+                ErrorKind::OutOfRange,
+                &format!(
+                    "Bit index is out of range: {} >= {}",
+                    index,
+                    self.data.len() * 8,
+                ),
+            )
+    }
+
+    /// Set a i64 value at the given offset and size
+    pub fn set_i64(
+        &mut self,
+        value: i64,
+        start: usize,
+        end: usize,
+        endianness: Endianness,
+    ) -> Result<()> {
+        match endianness {
+            Endianness::Big => self.set_i64_be(value, start, end),
+            Endianness::Little => self.set_i64_le(value, start, end),
+            Endianness::Intel => {
+                self.set_u64_intel(MutableBitField::truncate(value, start, end), start, end)
+            }
+        }
+    }
+
+    /// Set a u64 value at the given offset and size
+    pub fn set_u64(
+        &mut self,
+        value: u64,
+        start: usize,
+        end: usize,
+        endianness: Endianness,
+    ) -> Result<()> {
+        match endianness {
+            Endianness::Big => self.set_u64_be(value, start, end),
+            Endianness::Little => self.set_u64_le(value, start, end),
+            Endianness::Intel => self.set_u64_intel(value, start, end),
+        }
+    }
+
+    /// Set a u64 value with bits counted from the least significant bit of each byte,
+    /// see Endianness::Intel
+    pub fn set_u64_intel(&mut self, value: u64, start: usize, end: usize) -> Result<()> {
+        debug!("set_u64_intel: {},{}", start, end);
+        MutableBitField::check_size(start, end, 64)?;
+        if end / 8 >= self.data.len() {
+            return Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!(
+                    "set_u64_intel: bit index is out of range: {} >= {}",
+                    end,
+                    self.data.len() * 8
+                ),
+            ));
+        }
+        let mut curr = start;
+        while curr <= end {
+            let low = curr % 8;
+            let high = usize::min(7, low + end - curr);
+            let chunk = (value >> (curr - start)) & ((1u64 << (high - low + 1)) - 1);
+            self.set_bits(chunk as u8, curr / 8, 7 - high, 7 - low)?;
+            curr += high - low + 1;
+        }
+        Ok(())
+    }
+
+    /// Set a i64 big endian value at the given offset and size
+    pub fn set_i64_be(&mut self, value: i64, start: usize, end: usize) -> Result<()> {
+        self.set_u64_be(MutableBitField::truncate(value, start, end), start, end)
+    }
+
+    /// Set a u64 big endian value at the given offset and size
+    pub fn set_u64_be(&mut self, value: u64, start: usize, end: usize) -> Result<()> {
+        debug!("set_u64_be: {},{}", start, end);
+        let size = MutableBitField::check_size(start, end, 64)?;
+        let mut curr = start;
+        let first = size % 8;
+        if first > 0 {
+            self.set_u8((value >> (size - first)) as u8, curr, curr + first - 1)?;
+            curr += first;
+        }
+
+        while curr < end {
+            self.set_u8((value >> (end - curr - 7)) as u8, curr, curr + 7)?;
+            curr += 8;
+        }
+        Ok(())
+    }
+
+    /// Set a i64 little endian value at the given offset and size
+    pub fn set_i64_le(&mut self, value: i64, start: usize, end: usize) -> Result<()> {
+        self.set_u64_le(MutableBitField::truncate(value, start, end), start, end)
+    }
+
+    /// Set a u64 little endian value at the given offset and size
+    pub fn set_u64_le(&mut self, value: u64, start: usize, end: usize) -> Result<()> {
+        debug!("set_u64_le: {},{}", start, end);
+        let size = MutableBitField::check_size(start, end, 64)?;
+        let full = size / 8;
+        for index in 0..full {
+            let curr = start + index * 8;
+            self.set_u8((value >> (index * 8)) as u8, curr, curr + 7)?;
+        }
+        if size % 8 > 0 {
+            self.set_u8((value >> (full * 8)) as u8, start + full * 8, end)?;
+        }
+        Ok(())
+    }
+
+    /// Set a i32 big endian value at the given offset and size
+    pub fn set_i32_be(&mut self, value: i32, start: usize, end: usize) -> Result<()> {
+        MutableBitField::check_size(start, end, 32)?;
+        self.set_i64_be(value as i64, start, end)
+    }
+
+    /// Set a u32 big endian value at the given offset and size
+    pub fn set_u32_be(&mut self, value: u32, start: usize, end: usize) -> Result<()> {
+        MutableBitField::check_size(start, end, 32)?;
+        self.set_u64_be(value as u64, start, end)
+    }
+
+    /// Set a i32 little endian value at the given offset and size
+    pub fn set_i32_le(&mut self, value: i32, start: usize, end: usize) -> Result<()> {
+        MutableBitField::check_size(start, end, 32)?;
+        self.set_i64_le(value as i64, start, end)
+    }
+
+    /// Set a u32 little endian value at the given offset and size
+    pub fn set_u32_le(&mut self, value: u32, start: usize, end: usize) -> Result<()> {
+        MutableBitField::check_size(start, end, 32)?;
+        self.set_u64_le(value as u64, start, end)
+    }
+
+    /// Set a i16 big endian value at the given offset and size
+    pub fn set_i16_be(&mut self, value: i16, start: usize, end: usize) -> Result<()> {
+        MutableBitField::check_size(start, end, 16)?;
+        self.set_i64_be(value as i64, start, end)
+    }
+
+    /// Set a u16 big endian value at the given offset and size
+    pub fn set_u16_be(&mut self, value: u16, start: usize, end: usize) -> Result<()> {
+        MutableBitField::check_size(start, end, 16)?;
+        self.set_u64_be(value as u64, start, end)
+    }
+
+    /// Set a i16 little endian value at the given offset and size
+    pub fn set_i16_le(&mut self, value: i16, start: usize, end: usize) -> Result<()> {
+        MutableBitField::check_size(start, end, 16)?;
+        self.set_i64_le(value as i64, start, end)
+    }
+
+    /// Set a u16 little endian value at the given offset and size
+    pub fn set_u16_le(&mut self, value: u16, start: usize, end: usize) -> Result<()> {
+        MutableBitField::check_size(start, end, 16)?;
+        self.set_u64_le(value as u64, start, end)
+    }
+
+    /// Set a i8 value at the given offset and size
+    pub fn set_i8(&mut self, value: i8, start: usize, end: usize) -> Result<()> {
+        MutableBitField::check_size(start, end, 8)?;
+        self.set_u8(
+            MutableBitField::truncate(value as i64, start, end) as u8,
+            start,
+            end,
+        )
+    }
+
+    /// Set a u8 value at the given offset and size
     pub fn set_u8(&mut self, value: u8, start: usize, end: usize) -> Result<()> {
         if end >= start {
             let end_offset = end - start;
@@ -46,41 +235,33 @@ impl<'a> MutableBitField<'a> {
                     }
                 } else {
                     let last_offset = end_offset + start_bit - 8;
-                    match self.set_bits(value >> (7 - start_bit) as u8, start_byte, start_bit, 7) {
-                        Ok(_) => {
-                            // println!("get_unsigned_byte: upper part: byte: {} start: {}, end: 7, res: {:08b}", start_byte , start , byte);
-                            ()
-                        }
-                        Err(why) => {
-                            return Err(Error::with_all(
-                                why.kind(),
-                                &format!(
-                                    "get_unsigned_byte: error from get bits for bits {}:{} of {}",
-                                    start,
-                                    end,
-                                    self.data.len() * 8
-                                ),
-                                Box::new(why),
-                            ))
-                        }
-                    };
+                    // the upper bits go to the first byte, the lower last_offset + 1 bits to the next
+                    if let Err(why) =
+                        self.set_bits(value >> (last_offset + 1) as u8, start_byte, start_bit, 7)
+                    {
+                        return Err(Error::with_all(
+                            why.kind(),
+                            &format!(
+                                "get_unsigned_byte: error from get bits for bits {}:{} of {}",
+                                start,
+                                end,
+                                self.data.len() * 8
+                            ),
+                            Box::new(why),
+                        ));
+                    }
                     match self.set_bits(value, start_byte + 1, 0, last_offset) {
-                        Ok(_) => {
-                            // println!("get_unsigned_byte: lower part: byte: {} start: 0, end: {}, res: {:08b}", start_byte + 1, last_offset , byte);
-                            Ok(())
-                        }
-                        Err(why) => {
-                            return Err(Error::with_all(
-                                why.kind(),
-                                &format!(
-                                    "get_unsigned_byte: error from get bits for bits {}:{} of {}",
-                                    start,
-                                    end,
-                                    self.data.len() * 8
-                                ),
-                                Box::new(why),
-                            ))
-                        }
+                        Ok(_) => Ok(()),
+                        Err(why) => Err(Error::with_all(
+                            why.kind(),
+                            &format!(
+                                "get_unsigned_byte: error from get bits for bits {}:{} of {}",
+                                start,
+                                end,
+                                self.data.len() * 8
+                            ),
+                            Box::new(why),
+                        )),
                     }
                 }
             }
@@ -92,6 +273,39 @@ impl<'a> MutableBitField<'a> {
         }
     }
 
+    // Returns the number of bits in start..=end, checked against the maximum size
+    fn check_size(start: usize, end: usize, max: usize) -> Result<usize> {
+        if end < start {
+            Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!("Start is greater that end {} > {}", start, end),
+            ))
+        } else if end - start >= max {
+            Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "check_size: too many bits {} to {} = {} > {}",
+                    start,
+                    end,
+                    end - start + 1,
+                    max
+                ),
+            ))
+        } else {
+            Ok(end - start + 1)
+        }
+    }
+
+    // Cut a signed value down to the two's complement representation in start..=end
+    fn truncate(value: i64, start: usize, end: usize) -> u64 {
+        let size = end.saturating_sub(start) + 1;
+        if size >= 64 {
+            value as u64
+        } else {
+            value as u64 & ((1u64 << size) - 1)
+        }
+    }
+
     // No checks done on this, ranges must be checked upstream
     fn create_mask(first: usize, last: usize) -> u8 {
         let mut byte: u8 = 0;
@@ -197,4 +411,88 @@ mod tests {
         assert_eq!(bytes[0], 0b10101011);
         assert_eq!(bytes[1], 0b11010101);
     }
+
+    #[test]
+    fn test_set_u8_crossing() {
+        let mut bytes: [u8; 2] = [0, 0];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        bitfield.set_u8(0b10000001, 4, 11).unwrap();
+        assert_eq!(bytes, [0b00001000, 0b00010000]);
+
+        let mut bytes: [u8; 2] = [0, 0];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        bitfield.set_u8(0b101, 6, 8).unwrap();
+        assert_eq!(bytes, [0b00000010, 0b10000000]);
+    }
+
+    #[test]
+    fn test_set_bit() {
+        let mut bytes: [u8; 2] = [0, 0xFF];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        bitfield.set_bit(0, true).unwrap();
+        bitfield.set_bit(9, false).unwrap();
+        assert!(bitfield.set_bit(16, true).is_err());
+        assert_eq!(bytes, [0b10000000, 0b10111111]);
+    }
+
+    #[test]
+    fn test_set_u64() {
+        let mut bytes: [u8; 9] = [0; 9];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        bitfield.set_u64_le(0xAAAAAAAAAAFF00AA, 0, 63).unwrap();
+        assert_eq!(
+            BitField::new(&bytes).get_u64_le(0, 63).unwrap(),
+            0xAAAAAAAAAAFF00AA
+        );
+
+        let mut bytes: [u8; 9] = [0; 9];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        bitfield.set_u64_be(0x1234_5678_9ABC, 3, 53).unwrap();
+        bitfield.set_u32_le(0x3FF, 54, 65).unwrap();
+        let bitfield = BitField::new(&bytes);
+        assert_eq!(bitfield.get_u64_be(3, 53).unwrap(), 0x1234_5678_9ABC);
+        assert_eq!(bitfield.get_u64_le(54, 65).unwrap(), 0x3FF);
+    }
+
+    #[test]
+    fn test_set_signed() {
+        let mut bytes: [u8; 10] = [0; 10];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        bitfield.set_i8(-3, 2, 6).unwrap();
+        bitfield.set_i16_le(-300, 8, 23).unwrap();
+        bitfield.set_i32_be(-70000, 24, 47).unwrap();
+        bitfield.set_i64_le(-2, 48, 79).unwrap();
+        assert!(bitfield.set_i16_be(1, 0, 16).is_err());
+        let bitfield = BitField::new(&bytes);
+        assert_eq!(bitfield.get_i8(2, 6).unwrap(), -3);
+        assert_eq!(bitfield.get_i16_le(8, 23).unwrap(), -300);
+        assert_eq!(bitfield.get_i32_be(24, 47).unwrap(), -70000);
+        assert_eq!(bitfield.get_i64_le(48, 79).unwrap(), -2);
+    }
+
+    #[test]
+    fn test_set_u64_intel() {
+        let mut bytes: [u8; 10] = [0; 10];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        bitfield.set_u64(0x729, 2, 12, Endianness::Intel).unwrap();
+        bitfield.set_i64(-2, 13, 21, Endianness::Intel).unwrap();
+        bitfield
+            .set_u64(0x02AA_5555_1234_8765, 22, 79, Endianness::Intel)
+            .unwrap();
+        assert!(bitfield.set_u64(1, 75, 80, Endianness::Intel).is_err());
+        assert_eq!(bitfield.as_bytes()[0] & 0x03, 0);
+        let bitfield = BitField::new(&bytes);
+        assert_eq!(bitfield.get_u64(2, 12, Endianness::Intel).unwrap(), 0x729);
+        assert_eq!(bitfield.get_i64(13, 21, Endianness::Intel).unwrap(), -2);
+        assert_eq!(
+            bitfield.get_u64(22, 79, Endianness::Intel).unwrap(),
+            0x02AA_5555_1234_8765
+        );
+
+        // bits outside the field are kept
+        let mut bytes: [u8; 2] = [0xFF; 2];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        bitfield.set_u64(0, 2, 12, Endianness::Intel).unwrap();
+        assert_eq!(bytes, [0x03, 0xE0]);
+    }
 }
@@ -0,0 +1,328 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::{BitField, MutableBitField};
+use std::ops::Deref;
+
+/// Largest supported element width in bits
+pub const MAX_WIDTH: usize = 16;
+
+/// A growable vector of unsigned values stored contiguously with a runtime element width
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackedVec {
+    width: usize,
+    len: usize,
+    data: Vec<u8>,
+}
+
+impl PackedVec {
+    pub fn new(width: usize) -> Result<PackedVec> {
+        PackedVec::with_capacity(width, 0)
+    }
+
+    pub fn with_capacity(width: usize, capacity: usize) -> Result<PackedVec> {
+        if width == 0 || width > MAX_WIDTH {
+            Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "PackedVec: invalid element width {}, expected 1..={}",
+                    width, MAX_WIDTH
+                ),
+            ))
+        } else {
+            Ok(PackedVec {
+                width,
+                len: 0,
+                data: Vec::with_capacity((capacity * width).div_ceil(8)),
+            })
+        }
+    }
+
+    /// Create a packed vector from a slice of values, all values must fit into width bits
+    pub fn from_slice(width: usize, values: &[u16]) -> Result<PackedVec> {
+        let mut packed = PackedVec::with_capacity(width, values.len())?;
+        for value in values {
+            packed.push(*value)?;
+        }
+        Ok(packed)
+    }
+
+    /// Get the element width in bits
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the packed representation, unused bits of the last byte are 0
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Get a bitfield over the packed representation
+    pub fn as_bitfield(&self) -> BitField<'_> {
+        BitField::new(&self.data)
+    }
+
+    /// Get the value at the given index
+    pub fn get(&self, index: usize) -> Result<u16> {
+        if index < self.len {
+            let start = index * self.width;
+            self.as_bitfield().get_u16_be(start, start + self.width - 1)
+        } else {
+            Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!(
+                    "PackedVec::get: index out of range {} >= {}",
+                    index, self.len
+                ),
+            ))
+        }
+    }
+
+    /// Set the value at the given index
+    pub fn set(&mut self, index: usize, value: u16) -> Result<()> {
+        if index < self.len {
+            self.check_value(value)?;
+            let start = index * self.width;
+            MutableBitField::new(&mut self.data).set_u16_be(value, start, start + self.width - 1)
+        } else {
+            Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!(
+                    "PackedVec::set: index out of range {} >= {}",
+                    index, self.len
+                ),
+            ))
+        }
+    }
+
+    /// Append a value
+    pub fn push(&mut self, value: u16) -> Result<()> {
+        self.check_value(value)?;
+        let size = ((self.len + 1) * self.width).div_ceil(8);
+        if size > self.data.len() {
+            self.data.resize(size, 0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, value)
+    }
+
+    /// Remove and return the last value
+    pub fn pop(&mut self) -> Option<u16> {
+        if self.len == 0 {
+            None
+        } else {
+            let value = self.get(self.len - 1).ok()?;
+            self.set(self.len - 1, 0).ok()?;
+            self.len -= 1;
+            self.data.truncate((self.len * self.width).div_ceil(8));
+            Some(value)
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.data.clear();
+    }
+
+    pub fn iter(&self) -> PackedIter<'_> {
+        PackedIter {
+            packed: self,
+            index: 0,
+        }
+    }
+
+    /// Unpack all values
+    pub fn to_vec(&self) -> Vec<u16> {
+        self.iter().collect()
+    }
+
+    fn check_value(&self, value: u16) -> Result<()> {
+        if self.width < 16 && value >> self.width != 0 {
+            Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "PackedVec: value {} does not fit into {} bits",
+                    value, self.width
+                ),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl From<&PackedVec> for Vec<u16> {
+    fn from(packed: &PackedVec) -> Self {
+        packed.to_vec()
+    }
+}
+
+impl<'a> IntoIterator for &'a PackedVec {
+    type Item = u16;
+    type IntoIter = PackedIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct PackedIter<'a> {
+    packed: &'a PackedVec,
+    index: usize,
+}
+
+impl<'a> Iterator for PackedIter<'a> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        if self.index < self.packed.len() {
+            self.index += 1;
+            self.packed.get(self.index - 1).ok()
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.packed.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> ExactSizeIterator for PackedIter<'a> {}
+
+/// A packed vector with the element width W fixed at compile time
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackedArray<const W: usize> {
+    inner: PackedVec,
+}
+
+impl<const W: usize> PackedArray<W> {
+    const VALID_WIDTH: () = assert!(W > 0 && W <= MAX_WIDTH, "invalid element width");
+
+    pub fn new() -> PackedArray<W> {
+        PackedArray::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> PackedArray<W> {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::VALID_WIDTH;
+        PackedArray {
+            inner: PackedVec {
+                width: W,
+                len: 0,
+                data: Vec::with_capacity((capacity * W).div_ceil(8)),
+            },
+        }
+    }
+
+    /// Create a packed array from a slice of values, all values must fit into W bits
+    pub fn from_slice(values: &[u16]) -> Result<PackedArray<W>> {
+        let mut packed = PackedArray::with_capacity(values.len());
+        for value in values {
+            packed.push(*value)?;
+        }
+        Ok(packed)
+    }
+
+    pub fn set(&mut self, index: usize, value: u16) -> Result<()> {
+        self.inner.set(index, value)
+    }
+
+    pub fn push(&mut self, value: u16) -> Result<()> {
+        self.inner.push(value)
+    }
+
+    pub fn pop(&mut self) -> Option<u16> {
+        self.inner.pop()
+    }
+
+    pub fn clear(&mut self) {
+        self.inner.clear()
+    }
+
+    pub fn into_packed_vec(self) -> PackedVec {
+        self.inner
+    }
+}
+
+impl<const W: usize> Default for PackedArray<W> {
+    fn default() -> Self {
+        PackedArray::new()
+    }
+}
+
+impl<const W: usize> Deref for PackedArray<W> {
+    type Target = PackedVec;
+
+    fn deref(&self) -> &PackedVec {
+        &self.inner
+    }
+}
+
+impl<const W: usize> From<&PackedArray<W>> for Vec<u16> {
+    fn from(packed: &PackedArray<W>) -> Self {
+        packed.to_vec()
+    }
+}
+
+impl<'a, const W: usize> IntoIterator for &'a PackedArray<W> {
+    type Item = u16;
+    type IntoIter = PackedIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packed_vec() {
+        let mut packed = PackedVec::new(5).unwrap();
+        for value in 0..32 {
+            packed.push(value).unwrap();
+        }
+        assert_eq!(packed.len(), 32);
+        assert_eq!(packed.as_bytes().len(), 20);
+        assert_eq!(packed.as_bytes()[0], 0b00000000);
+        assert_eq!(packed.as_bytes()[1], 0b01000100);
+        assert_eq!(packed.get(17).unwrap(), 17);
+        assert!(packed.get(32).is_err());
+        assert!(packed.push(32).is_err());
+
+        packed.set(3, 31).unwrap();
+        assert_eq!(packed.get(2).unwrap(), 2);
+        assert_eq!(packed.get(3).unwrap(), 31);
+        assert_eq!(packed.get(4).unwrap(), 4);
+        assert_eq!(packed.pop(), Some(31));
+        assert_eq!(packed.iter().len(), 31);
+        assert_eq!(packed.iter().sum::<u16>(), (0..31).sum::<u16>() + 28);
+
+        assert!(PackedVec::new(0).is_err());
+        assert!(PackedVec::new(17).is_err());
+    }
+
+    #[test]
+    fn test_packed_array() {
+        let values: Vec<u16> = (0..100).map(|value| value * 41).collect();
+        let packed = PackedArray::<12>::from_slice(&values).unwrap();
+        assert_eq!(packed.width(), 12);
+        assert_eq!(packed.as_bytes().len(), 150);
+        assert_eq!(Vec::from(&packed), values);
+
+        let mut packed = PackedArray::<16>::new();
+        packed.push(0xFFFF).unwrap();
+        packed.push(0x1234).unwrap();
+        assert_eq!(packed.as_bytes(), &[0xFF, 0xFF, 0x12, 0x34]);
+        assert!(PackedArray::<3>::from_slice(&[8]).is_err());
+    }
+}
@@ -0,0 +1,320 @@
+use crate::bitfield::{BitField, Endianness};
+use crate::can::{CanFrame, FrameSink, N2kHeader, BROADCAST};
+use crate::codec::{BitDecode, BitEncode, BitLookup};
+use crate::error::{Error, ErrorKind, Result};
+use crate::mutable_bitfield::MutableBitField;
+use std::collections::BTreeSet;
+
+// ISO 11783-3 / J1939-21 requests and acknowledgements. A request (PGN 59904) carries the
+// requested PGN in 3 bytes and is sent to one node or to all nodes. A node that can not
+// provide a PGN requested from it specifically answers with a NAK in an acknowledgement
+// (PGN 59392):
+//
+//   byte 0     control: 0 ACK, 1 NAK, 2 access denied, 3 cannot respond
+//   byte 1     group function, 0xFF if unused
+//   bytes 2-3  reserved
+//   byte 4     address of the requester
+//   bytes 5-7  acknowledged PGN
+
+/// ISO request PGN
+pub const ISO_REQUEST: u32 = 59904;
+/// ISO acknowledgement PGN
+pub const ISO_ACKNOWLEDGEMENT: u32 = 59392;
+
+/// A request for a PGN
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IsoRequest {
+    pub pgn: u32,
+}
+
+impl IsoRequest {
+    pub fn new(pgn: u32) -> IsoRequest {
+        IsoRequest { pgn }
+    }
+
+    /// Build the request frame from source to destination, BROADCAST for all nodes
+    pub fn to_frame(&self, source: u8, destination: u8) -> Result<CanFrame> {
+        frame(ISO_REQUEST, source, destination, &self.to_vec()?)
+    }
+
+    /// Get the header and request of a request frame
+    pub fn from_frame(frame: &CanFrame) -> Result<(N2kHeader, IsoRequest)> {
+        let header = expect_pgn(frame, ISO_REQUEST)?;
+        Ok((header, IsoRequest::from_bytes(frame.data())?))
+    }
+}
+
+impl BitDecode for IsoRequest {
+    fn decode(bitfield: &BitField) -> Result<IsoRequest> {
+        Ok(IsoRequest {
+            pgn: bitfield.get_u64(0, 23, Endianness::Intel)? as u32,
+        })
+    }
+}
+
+impl BitEncode for IsoRequest {
+    const BIT_LEN: usize = 24;
+
+    fn encode(&self, bitfield: &mut MutableBitField) -> Result<()> {
+        bitfield.set_u64(self.pgn as u64, 0, 23, Endianness::Intel)
+    }
+}
+
+/// The control byte of an acknowledgement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AckControl {
+    Ack,
+    Nak,
+    AccessDenied,
+    CannotRespond,
+    Other(u8),
+}
+
+impl BitLookup for AckControl {
+    fn from_raw(raw: u64) -> Result<AckControl> {
+        Ok(match raw {
+            0 => AckControl::Ack,
+            1 => AckControl::Nak,
+            2 => AckControl::AccessDenied,
+            3 => AckControl::CannotRespond,
+            raw => AckControl::Other(raw as u8),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            AckControl::Ack => 0,
+            AckControl::Nak => 1,
+            AckControl::AccessDenied => 2,
+            AckControl::CannotRespond => 3,
+            AckControl::Other(raw) => *raw as u64,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            AckControl::Ack => Some("ACK"),
+            AckControl::Nak => Some("NAK"),
+            AckControl::AccessDenied => Some("Access Denied"),
+            AckControl::CannotRespond => Some("Cannot Respond"),
+            AckControl::Other(_) => None,
+        }
+    }
+}
+
+/// A positive or negative acknowledgement of a PGN
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IsoAcknowledgement {
+    pub control: AckControl,
+    pub group_function: u8,
+    /// address of the requester
+    pub address: u8,
+    pub pgn: u32,
+}
+
+impl IsoAcknowledgement {
+    /// Create an acknowledgement without group function
+    pub fn new(control: AckControl, pgn: u32, address: u8) -> IsoAcknowledgement {
+        IsoAcknowledgement {
+            control,
+            group_function: 0xFF,
+            address,
+            pgn,
+        }
+    }
+
+    /// Build the acknowledgement frame from source to destination
+    pub fn to_frame(&self, source: u8, destination: u8) -> Result<CanFrame> {
+        frame(ISO_ACKNOWLEDGEMENT, source, destination, &self.to_vec()?)
+    }
+
+    /// Get the header and acknowledgement of an acknowledgement frame
+    pub fn from_frame(frame: &CanFrame) -> Result<(N2kHeader, IsoAcknowledgement)> {
+        let header = expect_pgn(frame, ISO_ACKNOWLEDGEMENT)?;
+        Ok((header, IsoAcknowledgement::from_bytes(frame.data())?))
+    }
+}
+
+impl BitDecode for IsoAcknowledgement {
+    fn decode(bitfield: &BitField) -> Result<IsoAcknowledgement> {
+        Ok(IsoAcknowledgement {
+            control: AckControl::from_raw(bitfield.get_u64(0, 7, Endianness::Intel)?)?,
+            group_function: bitfield.get_u64(8, 15, Endianness::Intel)? as u8,
+            address: bitfield.get_u64(32, 39, Endianness::Intel)? as u8,
+            pgn: bitfield.get_u64(40, 63, Endianness::Intel)? as u32,
+        })
+    }
+}
+
+impl BitEncode for IsoAcknowledgement {
+    const BIT_LEN: usize = 64;
+
+    fn encode(&self, bitfield: &mut MutableBitField) -> Result<()> {
+        bitfield.set_u64(self.control.to_raw(), 0, 7, Endianness::Intel)?;
+        bitfield.set_u64(self.group_function as u64, 8, 15, Endianness::Intel)?;
+        bitfield.set_u64(self.address as u64, 32, 39, Endianness::Intel)?;
+        bitfield.set_u64(self.pgn as u64, 40, 63, Endianness::Intel)
+    }
+}
+
+/// A request the node has to answer by sending pgn to destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Requested {
+    pub pgn: u32,
+    pub requester: u8,
+    /// the requester for requests to this node, BROADCAST for requests to all nodes
+    pub destination: u8,
+}
+
+/// Answers requests from the set of PGNs a node provides
+///
+/// PGNs answered by other parts of the node, like the address claim, have to be in the set
+/// as well or requests for them are refused.
+#[derive(Debug, Clone, Default)]
+pub struct Responder {
+    pgns: BTreeSet<u32>,
+}
+
+impl Responder {
+    pub fn new() -> Responder {
+        Responder::default()
+    }
+
+    /// Add a provided PGN, returns self for chaining
+    pub fn with_pgn(mut self, pgn: u32) -> Responder {
+        self.insert(pgn);
+        self
+    }
+
+    pub fn insert(&mut self, pgn: u32) {
+        self.pgns.insert(pgn);
+    }
+
+    pub fn contains(&self, pgn: u32) -> bool {
+        self.pgns.contains(&pgn)
+    }
+
+    /// Handle a received frame for the node at address. Returns the request to answer for
+    /// provided PGNs, sends a NAK to the requester for other PGNs requested from this node
+    /// and ignores other PGNs requested from all nodes.
+    pub fn handle<S: FrameSink>(
+        &self,
+        frame: &CanFrame,
+        address: u8,
+        sink: &mut S,
+    ) -> Result<Option<Requested>> {
+        let (header, request) = match IsoRequest::from_frame(frame) {
+            Ok(request) => request,
+            Err(_) => return Ok(None),
+        };
+        if header.destination != address && header.destination != BROADCAST {
+            return Ok(None);
+        }
+        if self.contains(request.pgn) {
+            return Ok(Some(Requested {
+                pgn: request.pgn,
+                requester: header.source,
+                destination: if header.destination == BROADCAST {
+                    BROADCAST
+                } else {
+                    header.source
+                },
+            }));
+        }
+        if header.destination == address {
+            let nak = IsoAcknowledgement::new(AckControl::Nak, request.pgn, header.source);
+            sink.send(nak.to_frame(address, header.source)?)?;
+        }
+        Ok(None)
+    }
+}
+
+fn frame(pgn: u32, source: u8, destination: u8, data: &[u8]) -> Result<CanFrame> {
+    let id = N2kHeader::new(6, pgn, source)
+        .with_destination(destination)
+        .to_can_id()?;
+    CanFrame::extended(id, data)
+}
+
+fn expect_pgn(frame: &CanFrame, pgn: u32) -> Result<N2kHeader> {
+    let header = frame.header()?;
+    if header.pgn != pgn {
+        return Err(Error::with_context(
+            ErrorKind::InvParam,
+            &format!("expect_pgn: PGN {} instead of {}", header.pgn, pgn),
+        ));
+    }
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request() {
+        let frame = IsoRequest::new(126996).to_frame(0x10, 0x23).unwrap();
+        assert_eq!(frame.id(), 0x18EA_2310);
+        assert_eq!(frame.data(), [0x14, 0xF0, 0x01]);
+        let (header, request) = IsoRequest::from_frame(&frame).unwrap();
+        assert_eq!((header.source, header.destination), (0x10, 0x23));
+        assert_eq!(request.pgn, 126996);
+        assert!(IsoRequest::from_frame(&CanFrame::extended(0x18EA_2310, &[1]).unwrap()).is_err());
+        assert!(IsoAcknowledgement::from_frame(&frame).is_err());
+
+        let ack = IsoAcknowledgement::new(AckControl::Nak, 126996, 0x10);
+        let frame = ack.to_frame(0x23, 0x10).unwrap();
+        assert_eq!(frame.id(), 0x18E8_1023);
+        assert_eq!(
+            frame.data(),
+            [0x01, 0xFF, 0xFF, 0xFF, 0x10, 0x14, 0xF0, 0x01]
+        );
+        assert_eq!(IsoAcknowledgement::from_frame(&frame).unwrap().1, ack);
+        assert_eq!(ack.control.name(), Some("NAK"));
+        assert_eq!(
+            IsoAcknowledgement::from_bytes(&[7, 2, 0, 0, 0x10, 0, 0xEE, 0])
+                .unwrap()
+                .control,
+            AckControl::Other(7)
+        );
+    }
+
+    #[test]
+    fn test_responder() {
+        let responder = Responder::new().with_pgn(126996).with_pgn(60928);
+        let mut sink = Vec::new();
+
+        let request = IsoRequest::new(126996).to_frame(0x10, 0x23).unwrap();
+        assert_eq!(
+            responder.handle(&request, 0x23, &mut sink).unwrap(),
+            Some(Requested {
+                pgn: 126996,
+                requester: 0x10,
+                destination: 0x10
+            })
+        );
+        let request = IsoRequest::new(126996).to_frame(0x10, BROADCAST).unwrap();
+        assert_eq!(
+            responder
+                .handle(&request, 0x23, &mut sink)
+                .unwrap()
+                .unwrap()
+                .destination,
+            BROADCAST
+        );
+        assert!(sink.is_empty());
+
+        // unsupported PGNs are refused to the requester, broadcast requests are ignored
+        let request = IsoRequest::new(130306).to_frame(0x10, 0x23).unwrap();
+        assert_eq!(responder.handle(&request, 0x23, &mut sink).unwrap(), None);
+        assert_eq!(sink.len(), 1);
+        let (header, ack) = IsoAcknowledgement::from_frame(&sink[0]).unwrap();
+        assert_eq!((header.source, header.destination), (0x23, 0x10));
+        assert_eq!(ack, IsoAcknowledgement::new(AckControl::Nak, 130306, 0x10));
+        let request = IsoRequest::new(130306).to_frame(0x10, BROADCAST).unwrap();
+        assert_eq!(responder.handle(&request, 0x23, &mut sink).unwrap(), None);
+        let request = IsoRequest::new(130306).to_frame(0x10, 0x24).unwrap();
+        assert_eq!(responder.handle(&request, 0x23, &mut sink).unwrap(), None);
+        assert_eq!(sink.len(), 1);
+    }
+}
@@ -0,0 +1,479 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::{Endianness, FieldSpec};
+use std::collections::BTreeMap;
+
+/// How the bits of a field are interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Bool,
+    /// unsigned number, scaled by resolution and offset
+    Unsigned,
+    /// two's complement number, scaled by resolution and offset
+    Signed,
+    /// IEEE 754 single precision float, 32 bits
+    Float,
+    /// unsigned value mapped to a name by the field lookup or indirect lookup
+    Lookup,
+    /// set of flags, the lookup maps bit numbers to names
+    BitLookup,
+    /// days since 1970-01-01
+    Date,
+    /// seconds since midnight, scaled by resolution
+    Time,
+    /// fixed length text, padding (0x00, 0xFF, '@') is stripped
+    String,
+    /// variable length text: length byte, text and a terminating zero,
+    /// the field covers only the length byte
+    StringLz,
+    /// variable length text: total length byte, encoding byte (0 = UTF-16, 1 = ASCII) and text,
+    /// the field covers only the two header bytes
+    StringLau,
+    /// raw bytes
+    Binary,
+    /// bits that are not used, written as 1
+    Reserved,
+}
+
+/// Value to name table for lookup fields
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Lookup {
+    pub name: String,
+    pub values: BTreeMap<u64, String>,
+}
+
+impl Lookup {
+    pub fn new(name: &str) -> Lookup {
+        Lookup {
+            name: name.to_owned(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Create a lookup from value, name pairs
+    pub fn from_pairs(name: &str, pairs: &[(u64, &str)]) -> Lookup {
+        Lookup {
+            name: name.to_owned(),
+            values: pairs
+                .iter()
+                .map(|(value, name)| (*value, (*name).to_owned()))
+                .collect(),
+        }
+    }
+
+    pub fn name_of(&self, value: u64) -> Option<&str> {
+        self.values.get(&value).map(|name| name.as_str())
+    }
+
+    pub fn value_of(&self, name: &str) -> Option<u64> {
+        self.values
+            .iter()
+            .find(|(_, entry)| entry.as_str() == name)
+            .map(|(value, _)| *value)
+    }
+
+    /// Get the names of the set bits of a bit lookup value, unknown bits have no name
+    pub fn flag_names(&self, raw: u64) -> Vec<&str> {
+        self.values
+            .iter()
+            .filter(|(bit, _)| **bit < 64 && raw >> **bit & 1 == 1)
+            .map(|(_, name)| name.as_str())
+            .collect()
+    }
+
+    /// Get the bit lookup value with the named bits set, None if a name is unknown
+    pub fn flags_of<S: AsRef<str>>(&self, names: &[S]) -> Option<u64> {
+        names.iter().try_fold(0, |raw, name| {
+            self.value_of(name.as_ref())
+                .filter(|bit| *bit < 64)
+                .map(|bit| raw | 1 << bit)
+        })
+    }
+}
+
+/// Name table for lookup fields keyed by the value of another field and the field value
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IndirectLookup {
+    pub name: String,
+    /// name of the field holding the first key
+    pub key: String,
+    pub values: BTreeMap<(u64, u64), String>,
+}
+
+impl IndirectLookup {
+    pub fn new(name: &str, key: &str) -> IndirectLookup {
+        IndirectLookup {
+            name: name.to_owned(),
+            key: key.to_owned(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    /// Create a lookup from key, value, name triples
+    pub fn from_triples(name: &str, key: &str, triples: &[(u64, u64, &str)]) -> IndirectLookup {
+        IndirectLookup {
+            name: name.to_owned(),
+            key: key.to_owned(),
+            values: triples
+                .iter()
+                .map(|(key, value, name)| ((*key, *value), (*name).to_owned()))
+                .collect(),
+        }
+    }
+
+    pub fn name_of(&self, key: u64, value: u64) -> Option<&str> {
+        self.values.get(&(key, value)).map(|name| name.as_str())
+    }
+
+    pub fn value_of(&self, key: u64, name: &str) -> Option<u64> {
+        self.values
+            .range((key, 0)..=(key, u64::MAX))
+            .find(|(_, entry)| entry.as_str() == name)
+            .map(|((_, value), _)| *value)
+    }
+}
+
+/// Runtime description of a single field
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDescriptor {
+    pub name: String,
+    /// first bit of the field
+    pub start: usize,
+    /// last bit of the field, inclusive
+    pub end: usize,
+    pub field_type: FieldType,
+    pub endianness: Endianness,
+    pub resolution: f64,
+    pub offset: f64,
+    pub unit: Option<String>,
+    pub lookup: Option<Lookup>,
+    /// names depending on another field, used when lookup is None
+    pub indirect_lookup: Option<IndirectLookup>,
+}
+
+impl FieldDescriptor {
+    /// Create a little endian field with resolution 1 and no unit
+    pub fn new(name: &str, start: usize, end: usize, field_type: FieldType) -> FieldDescriptor {
+        FieldDescriptor {
+            name: name.to_owned(),
+            start,
+            end,
+            field_type,
+            endianness: Endianness::Little,
+            resolution: 1.0,
+            offset: 0.0,
+            unit: None,
+            lookup: None,
+            indirect_lookup: None,
+        }
+    }
+
+    /// Get the field size in bits
+    pub fn size(&self) -> usize {
+        self.end.saturating_sub(self.start) + 1
+    }
+
+    /// Whether the field decodes to a scaled value rather than an integer
+    pub fn is_scaled(&self) -> bool {
+        self.resolution != 1.0 || self.offset != 0.0
+    }
+
+    /// Whether the field size depends on the payload, following fields move with it
+    pub fn is_variable(&self) -> bool {
+        matches!(self.field_type, FieldType::StringLz | FieldType::StringLau)
+    }
+
+    /// Get the numeric extraction parameters of the field
+    pub fn spec(&self) -> FieldSpec {
+        FieldSpec {
+            start: self.start,
+            end: self.end,
+            endianness: self.endianness,
+            signed: self.field_type == FieldType::Signed,
+            resolution: self.resolution,
+            offset: self.offset,
+            na: match self.field_type {
+                FieldType::Bool => self.size() > 1,
+                FieldType::BitLookup => false,
+                _ => true,
+            },
+        }
+    }
+
+    /// Check the field range, numeric fields are limited to 64 bits
+    pub fn check(&self) -> Result<()> {
+        let numeric = !matches!(
+            self.field_type,
+            FieldType::String | FieldType::Binary | FieldType::Reserved
+        ) && !self.is_variable();
+        if self.end < self.start
+            || (numeric && self.size() > 64)
+            || (self.field_type == FieldType::Float && self.size() != 32)
+            || (self.is_variable() && (self.start % 8 != 0 || self.size() % 8 != 0))
+        {
+            Err(Error::with_context(
+                ErrorKind::InvRange,
+                &format!(
+                    "FieldDescriptor: invalid range {}..={} for field {}",
+                    self.start, self.end, self.name
+                ),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Consecutive fields repeated in the payload, the field positions describe the first
+/// repetition and the following repetitions are packed behind it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepeatingGroup {
+    pub name: String,
+    /// index of the first field of the group in MessageSchema::fields
+    pub first: usize,
+    /// number of fields in the group
+    pub size: usize,
+    /// field holding the number of repetitions, None repeats until the payload ends
+    pub count_field: Option<String>,
+}
+
+impl RepeatingGroup {
+    pub fn new(name: &str, first: usize, size: usize) -> RepeatingGroup {
+        RepeatingGroup {
+            name: name.to_owned(),
+            first,
+            size,
+            count_field: None,
+        }
+    }
+
+    /// Get the fields of one repetition
+    pub fn fields<'a>(&self, schema: &'a MessageSchema) -> &'a [FieldDescriptor] {
+        let end = (self.first + self.size).min(schema.fields.len());
+        &schema.fields[self.first.min(end)..end]
+    }
+
+    /// Get the declared size of one repetition in bits
+    pub fn bit_len(&self, schema: &MessageSchema) -> usize {
+        let fields = self.fields(schema);
+        match (fields.first(), fields.last()) {
+            (Some(first), Some(last)) => (last.end + 1).saturating_sub(first.start),
+            _ => 0,
+        }
+    }
+}
+
+/// A layout of the fields behind the common fields of a message
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub name: String,
+    /// discriminator values selecting the variant, in the order of Variants::discriminators
+    pub values: Vec<u64>,
+    pub fields: Vec<FieldDescriptor>,
+}
+
+impl Variant {
+    pub fn new(name: &str, values: &[u64]) -> Variant {
+        Variant {
+            name: name.to_owned(),
+            values: values.to_vec(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Append a field, returns self for chaining
+    pub fn with_field(mut self, field: FieldDescriptor) -> Variant {
+        self.fields.push(field);
+        self
+    }
+}
+
+/// Alternative layouts of the payload following the common fields, selected by the values of
+/// discriminator fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variants {
+    /// name of the decoded value holding the selected variant
+    pub name: String,
+    /// names of the common fields selecting the variant
+    pub discriminators: Vec<String>,
+    /// first bit of the variant fields, unknown variants keep the bits from here on
+    pub start: usize,
+    pub cases: Vec<Variant>,
+}
+
+impl Variants {
+    pub fn new(name: &str, discriminators: &[&str], start: usize) -> Variants {
+        Variants {
+            name: name.to_owned(),
+            discriminators: discriminators.iter().map(|name| name.to_string()).collect(),
+            start,
+            cases: Vec::new(),
+        }
+    }
+
+    /// Add a variant, returns self for chaining
+    pub fn with_variant(mut self, variant: Variant) -> Variants {
+        self.cases.push(variant);
+        self
+    }
+
+    /// Get the variant selected by the discriminator values
+    pub fn select(&self, values: &[u64]) -> Option<&Variant> {
+        self.cases.iter().find(|case| case.values == values)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Variant> {
+        self.cases.iter().find(|case| case.name == name)
+    }
+}
+
+/// How a message is sent on the bus, limits the payload length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    /// a single CAN frame
+    Single,
+    /// NMEA 2000 fast packet
+    FastPacket,
+    /// ISO 11783-3 transport protocol
+    TransportProtocol,
+}
+
+impl Transport {
+    /// Get the maximum payload length in bytes
+    pub fn max_length(&self) -> usize {
+        match self {
+            Transport::Single => 8,
+            Transport::FastPacket => 223,
+            Transport::TransportProtocol => 1785,
+        }
+    }
+}
+
+/// Runtime description of a message
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageSchema {
+    /// message identifier, the PGN for NMEA 2000 messages
+    pub id: u32,
+    pub name: String,
+    pub description: Option<String>,
+    /// payload length in bytes, None for variable length messages
+    pub length: Option<usize>,
+    pub transport: Transport,
+    pub fields: Vec<FieldDescriptor>,
+    pub groups: Vec<RepeatingGroup>,
+    pub variants: Option<Variants>,
+}
+
+impl MessageSchema {
+    pub fn new(id: u32, name: &str) -> MessageSchema {
+        MessageSchema {
+            id,
+            name: name.to_owned(),
+            description: None,
+            length: None,
+            transport: Transport::Single,
+            fields: Vec::new(),
+            groups: Vec::new(),
+            variants: None,
+        }
+    }
+
+    /// Append a field, returns self for chaining
+    pub fn with_field(mut self, field: FieldDescriptor) -> MessageSchema {
+        self.fields.push(field);
+        self
+    }
+
+    /// Add a repeating group, returns self for chaining
+    pub fn with_group(mut self, group: RepeatingGroup) -> MessageSchema {
+        self.groups.push(group);
+        self
+    }
+
+    /// Set the variant layouts, returns self for chaining
+    pub fn with_variants(mut self, variants: Variants) -> MessageSchema {
+        self.variants = Some(variants);
+        self
+    }
+
+    /// Get the group starting at field index
+    pub fn group_at(&self, index: usize) -> Option<&RepeatingGroup> {
+        self.groups
+            .iter()
+            .find(|group| group.first == index && group.size > 0)
+    }
+
+    pub fn get_field(&self, name: &str) -> Option<&FieldDescriptor> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Get the number of bits covered by the fields
+    pub fn bit_len(&self) -> usize {
+        match self.length {
+            Some(length) => length * 8,
+            None => self
+                .fields
+                .iter()
+                .map(|field| field.end + 1)
+                .max()
+                .unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema() {
+        let schema = MessageSchema::new(127488, "Engine Parameters, Rapid Update")
+            .with_field(FieldDescriptor::new("instance", 0, 7, FieldType::Unsigned))
+            .with_field(FieldDescriptor {
+                resolution: 0.25,
+                unit: Some("rpm".to_owned()),
+                ..FieldDescriptor::new("speed", 8, 23, FieldType::Unsigned)
+            });
+        assert_eq!(schema.bit_len(), 24);
+        let speed = schema.get_field("speed").unwrap();
+        assert!(speed.is_scaled());
+        assert_eq!(speed.spec().resolution, 0.25);
+        assert!(FieldDescriptor::new("x", 0, 64, FieldType::Unsigned)
+            .check()
+            .is_err());
+        assert!(FieldDescriptor::new("x", 0, 64, FieldType::Binary)
+            .check()
+            .is_ok());
+        assert!(FieldDescriptor::new("x", 4, 11, FieldType::StringLz)
+            .check()
+            .is_err());
+        assert!(FieldDescriptor::new("x", 8, 15, FieldType::StringLz)
+            .check()
+            .is_ok());
+
+        let schema = schema
+            .with_field(FieldDescriptor::new("pgn", 24, 47, FieldType::Unsigned))
+            .with_group(RepeatingGroup::new("pgns", 2, 1));
+        assert_eq!(schema.group_at(2).unwrap().bit_len(&schema), 24);
+        assert!(schema.group_at(1).is_none());
+
+        let lookup = Lookup::from_pairs("DIRECTION_REFERENCE", &[(0, "True"), (1, "Magnetic")]);
+        assert_eq!(lookup.name_of(1), Some("Magnetic"));
+        assert_eq!(lookup.value_of("True"), Some(0));
+        assert_eq!(lookup.value_of("Error"), None);
+
+        let flags = Lookup::from_pairs("FLAGS", &[(0, "Check Engine"), (2, "Low Oil")]);
+        assert_eq!(flags.flag_names(0b1101), vec!["Check Engine", "Low Oil"]);
+        assert_eq!(flags.flags_of(&["Low Oil", "Check Engine"]), Some(0b101));
+        assert_eq!(flags.flags_of(&["Check Engine", "Error"]), None);
+
+        let function = IndirectLookup::from_triples(
+            "DEVICE_FUNCTION",
+            "deviceClass",
+            &[(130, 25, "PC Gateway"), (140, 25, "Autopilot")],
+        );
+        assert_eq!(function.name_of(140, 25), Some("Autopilot"));
+        assert_eq!(function.name_of(150, 25), None);
+        assert_eq!(function.value_of(130, "PC Gateway"), Some(25));
+        assert_eq!(function.value_of(130, "Autopilot"), None);
+    }
+}
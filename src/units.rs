@@ -0,0 +1,459 @@
+use crate::dynamic::{DecodedField, DecodedMessage, Value};
+use crate::error::{Error, ErrorKind, Result};
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fmt::{self, Display, Formatter};
+
+// Physical units of decoded fields. NMEA 2000 transmits SI units (rad, K, m/s, Pa, C), the
+// units are converted to the target unit chosen per dimension, e.g. knots for speeds:
+//
+//     let converter = UnitConverter::new().with_target(Unit::Knot).with_target(Unit::Celsius);
+//     let message = converter.convert_message(&decode_dynamic(schema, &bitfield)?);
+//
+// Converted fields carry the symbol of their new unit.
+
+/// The physical quantity measured by a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dimension {
+    Angle,
+    AngularVelocity,
+    Temperature,
+    Speed,
+    Pressure,
+    Length,
+    Charge,
+    Volume,
+    VolumeFlow,
+    Time,
+}
+
+/// A physical unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Radian,
+    Degree,
+    RadianPerSecond,
+    DegreePerSecond,
+    DegreePerMinute,
+    Kelvin,
+    Celsius,
+    Fahrenheit,
+    MeterPerSecond,
+    Knot,
+    KilometerPerHour,
+    Pascal,
+    Hectopascal,
+    Kilopascal,
+    Bar,
+    Psi,
+    Meter,
+    Kilometer,
+    NauticalMile,
+    Foot,
+    Coulomb,
+    AmpereHour,
+    Liter,
+    CubicMeter,
+    Gallon,
+    LiterPerHour,
+    GallonPerHour,
+    Second,
+    Minute,
+    Hour,
+}
+
+const UNITS: &[Unit] = &[
+    Unit::Radian,
+    Unit::Degree,
+    Unit::RadianPerSecond,
+    Unit::DegreePerSecond,
+    Unit::DegreePerMinute,
+    Unit::Kelvin,
+    Unit::Celsius,
+    Unit::Fahrenheit,
+    Unit::MeterPerSecond,
+    Unit::Knot,
+    Unit::KilometerPerHour,
+    Unit::Pascal,
+    Unit::Hectopascal,
+    Unit::Kilopascal,
+    Unit::Bar,
+    Unit::Psi,
+    Unit::Meter,
+    Unit::Kilometer,
+    Unit::NauticalMile,
+    Unit::Foot,
+    Unit::Coulomb,
+    Unit::AmpereHour,
+    Unit::Liter,
+    Unit::CubicMeter,
+    Unit::Gallon,
+    Unit::LiterPerHour,
+    Unit::GallonPerHour,
+    Unit::Second,
+    Unit::Minute,
+    Unit::Hour,
+];
+
+impl Unit {
+    /// Get the symbol used in schemas and output, canboat symbols for the SI units
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Radian => "rad",
+            Unit::Degree => "deg",
+            Unit::RadianPerSecond => "rad/s",
+            Unit::DegreePerSecond => "deg/s",
+            Unit::DegreePerMinute => "deg/min",
+            Unit::Kelvin => "K",
+            Unit::Celsius => "°C",
+            Unit::Fahrenheit => "°F",
+            Unit::MeterPerSecond => "m/s",
+            Unit::Knot => "kn",
+            Unit::KilometerPerHour => "km/h",
+            Unit::Pascal => "Pa",
+            Unit::Hectopascal => "hPa",
+            Unit::Kilopascal => "kPa",
+            Unit::Bar => "bar",
+            Unit::Psi => "psi",
+            Unit::Meter => "m",
+            Unit::Kilometer => "km",
+            Unit::NauticalMile => "NM",
+            Unit::Foot => "ft",
+            Unit::Coulomb => "C",
+            Unit::AmpereHour => "Ah",
+            Unit::Liter => "L",
+            Unit::CubicMeter => "m3",
+            Unit::Gallon => "gal",
+            Unit::LiterPerHour => "L/h",
+            Unit::GallonPerHour => "gal/h",
+            Unit::Second => "s",
+            Unit::Minute => "min",
+            Unit::Hour => "h",
+        }
+    }
+
+    /// Get the unit of a symbol, None for unknown symbols
+    pub fn from_symbol(symbol: &str) -> Option<Unit> {
+        UNITS.iter().copied().find(|unit| unit.symbol() == symbol)
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Unit::Radian | Unit::Degree => Dimension::Angle,
+            Unit::RadianPerSecond | Unit::DegreePerSecond | Unit::DegreePerMinute => {
+                Dimension::AngularVelocity
+            }
+            Unit::Kelvin | Unit::Celsius | Unit::Fahrenheit => Dimension::Temperature,
+            Unit::MeterPerSecond | Unit::Knot | Unit::KilometerPerHour => Dimension::Speed,
+            Unit::Pascal | Unit::Hectopascal | Unit::Kilopascal | Unit::Bar | Unit::Psi => {
+                Dimension::Pressure
+            }
+            Unit::Meter | Unit::Kilometer | Unit::NauticalMile | Unit::Foot => Dimension::Length,
+            Unit::Coulomb | Unit::AmpereHour => Dimension::Charge,
+            Unit::Liter | Unit::CubicMeter | Unit::Gallon => Dimension::Volume,
+            Unit::LiterPerHour | Unit::GallonPerHour => Dimension::VolumeFlow,
+            Unit::Second | Unit::Minute | Unit::Hour => Dimension::Time,
+        }
+    }
+
+    // factor and offset converting a value to the unit NMEA 2000 uses for the dimension:
+    // base = value * factor + offset
+    fn scale(&self) -> (f64, f64) {
+        match self {
+            Unit::Radian
+            | Unit::RadianPerSecond
+            | Unit::Kelvin
+            | Unit::MeterPerSecond
+            | Unit::Pascal
+            | Unit::Meter
+            | Unit::Coulomb
+            | Unit::Liter
+            | Unit::LiterPerHour
+            | Unit::Second => (1.0, 0.0),
+            Unit::Degree | Unit::DegreePerSecond => (PI / 180.0, 0.0),
+            Unit::DegreePerMinute => (PI / 180.0 / 60.0, 0.0),
+            Unit::Celsius => (1.0, 273.15),
+            Unit::Fahrenheit => (5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0),
+            Unit::Knot => (1852.0 / 3600.0, 0.0),
+            Unit::KilometerPerHour => (1.0 / 3.6, 0.0),
+            Unit::Hectopascal => (100.0, 0.0),
+            Unit::Kilopascal => (1000.0, 0.0),
+            Unit::Bar => (100_000.0, 0.0),
+            Unit::Psi => (6_894.757_293_168, 0.0),
+            Unit::Kilometer => (1000.0, 0.0),
+            Unit::NauticalMile => (1852.0, 0.0),
+            Unit::Foot => (0.3048, 0.0),
+            Unit::AmpereHour => (3600.0, 0.0),
+            Unit::CubicMeter => (1000.0, 0.0),
+            Unit::Gallon | Unit::GallonPerHour => (3.785_411_784, 0.0),
+            Unit::Minute => (60.0, 0.0),
+            Unit::Hour => (3600.0, 0.0),
+        }
+    }
+
+    /// Convert value from this unit to target, both must measure the same dimension
+    pub fn convert(&self, value: f64, target: Unit) -> Result<f64> {
+        if self.dimension() != target.dimension() {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "Unit::convert: can not convert {} to {}",
+                    self.symbol(),
+                    target.symbol()
+                ),
+            ));
+        }
+        let (factor, offset) = self.scale();
+        let (target_factor, target_offset) = target.scale();
+        Ok((value * factor + offset - target_offset) / target_factor)
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// A value with its unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub value: f64,
+    pub unit: Unit,
+}
+
+impl Measurement {
+    pub fn new(value: f64, unit: Unit) -> Measurement {
+        Measurement { value, unit }
+    }
+
+    /// Convert to unit
+    pub fn to(&self, unit: Unit) -> Result<Measurement> {
+        Ok(Measurement::new(self.unit.convert(self.value, unit)?, unit))
+    }
+}
+
+impl Display for Measurement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+impl DecodedField {
+    /// Get the value as a measurement, None for values without a number or a known unit
+    pub fn measurement(&self) -> Option<Measurement> {
+        let unit = Unit::from_symbol(self.unit.as_deref()?)?;
+        match &self.value {
+            Value::Float(value) => Some(Measurement::new(*value, unit)),
+            Value::Unsigned(value) => Some(Measurement::new(*value as f64, unit)),
+            Value::Signed(value) => Some(Measurement::new(*value as f64, unit)),
+            _ => None,
+        }
+    }
+}
+
+/// Converts decoded values to the target unit of their dimension
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UnitConverter {
+    pub targets: BTreeMap<Dimension, Unit>,
+}
+
+impl UnitConverter {
+    /// Create a converter without targets, values keep their unit
+    pub fn new() -> UnitConverter {
+        UnitConverter::default()
+    }
+
+    /// Create a converter to knots, degrees, °C, bar and nautical miles
+    pub fn nautical() -> UnitConverter {
+        UnitConverter::new()
+            .with_target(Unit::Knot)
+            .with_target(Unit::Degree)
+            .with_target(Unit::DegreePerSecond)
+            .with_target(Unit::Celsius)
+            .with_target(Unit::Bar)
+            .with_target(Unit::NauticalMile)
+    }
+
+    /// Convert values of the dimension of unit to unit, returns self for chaining
+    pub fn with_target(mut self, unit: Unit) -> UnitConverter {
+        self.targets.insert(unit.dimension(), unit);
+        self
+    }
+
+    /// Convert a measurement to the target of its dimension
+    pub fn convert(&self, measurement: Measurement) -> Measurement {
+        self.targets
+            .get(&measurement.unit.dimension())
+            .and_then(|unit| measurement.to(*unit).ok())
+            .unwrap_or(measurement)
+    }
+
+    /// Convert a field, numbers with a known unit become a Float in the target unit
+    pub fn convert_field(&self, field: &DecodedField) -> DecodedField {
+        let value = match &field.value {
+            Value::Group(rows) => {
+                Value::Group(rows.iter().map(|row| self.convert_fields(row)).collect())
+            }
+            Value::Variant(name, fields) => {
+                Value::Variant(name.clone(), self.convert_fields(fields))
+            }
+            _ => match field.measurement() {
+                Some(measurement) if self.targets.contains_key(&measurement.unit.dimension()) => {
+                    let measurement = self.convert(measurement);
+                    return DecodedField {
+                        value: Value::Float(measurement.value),
+                        unit: Some(measurement.unit.symbol().to_owned()),
+                        ..field.clone()
+                    };
+                }
+                _ => return field.clone(),
+            },
+        };
+        DecodedField {
+            value,
+            ..field.clone()
+        }
+    }
+
+    /// Convert all fields of a message
+    pub fn convert_message(&self, message: &DecodedMessage) -> DecodedMessage {
+        DecodedMessage {
+            fields: self.convert_fields(&message.fields),
+            ..message.clone()
+        }
+    }
+
+    fn convert_fields(&self, fields: &[DecodedField]) -> Vec<DecodedField> {
+        fields
+            .iter()
+            .map(|field| self.convert_field(field))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(value: f64, expected: f64) -> bool {
+        (value - expected).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_convert() {
+        assert!(close(
+            Unit::MeterPerSecond.convert(10.0, Unit::Knot).unwrap(),
+            19.438445
+        ));
+        assert!(close(
+            Unit::Radian.convert(PI, Unit::Degree).unwrap(),
+            180.0
+        ));
+        assert!(close(
+            Unit::Kelvin.convert(293.15, Unit::Celsius).unwrap(),
+            20.0
+        ));
+        assert!(close(
+            Unit::Celsius.convert(100.0, Unit::Fahrenheit).unwrap(),
+            212.0
+        ));
+        assert!(close(
+            Unit::Pascal.convert(101_325.0, Unit::Bar).unwrap(),
+            1.01325
+        ));
+        assert!(close(
+            Unit::Coulomb.convert(360_000.0, Unit::AmpereHour).unwrap(),
+            100.0
+        ));
+        assert!(close(
+            Unit::RadianPerSecond
+                .convert(PI, Unit::DegreePerMinute)
+                .unwrap(),
+            10_800.0
+        ));
+        assert_eq!(
+            Unit::Kelvin.convert(1.0, Unit::Bar).unwrap_err().kind(),
+            ErrorKind::InvParam
+        );
+
+        assert_eq!(Unit::from_symbol("m/s"), Some(Unit::MeterPerSecond));
+        assert_eq!(Unit::from_symbol("°C"), Some(Unit::Celsius));
+        assert_eq!(Unit::from_symbol("V"), None);
+        for unit in UNITS {
+            assert_eq!(Unit::from_symbol(unit.symbol()), Some(*unit));
+            assert!(close(unit.convert(1.5, *unit).unwrap(), 1.5));
+        }
+
+        let depth = Measurement::new(10.0, Unit::Meter).to(Unit::Foot).unwrap();
+        assert!(close(depth.value, 32.808399));
+        assert_eq!(Measurement::new(2.5, Unit::Bar).to_string(), "2.5 bar");
+    }
+
+    #[test]
+    fn test_convert_message() {
+        let field = |name: &str, value: Value, unit: &str| DecodedField {
+            unit: Some(unit.to_owned()),
+            ..DecodedField::new(name, value)
+        };
+        let message = DecodedMessage {
+            fields: vec![
+                field("speed", Value::Float(5.0), "m/s"),
+                field("temperature", Value::Float(300.0), "K"),
+                field("depth", Value::Unsigned(10), "m"),
+                field("voltage", Value::Float(12.5), "V"),
+                field("pressure", Value::NotAvailable, "Pa"),
+                DecodedField::new(
+                    "set1",
+                    Value::Group(vec![vec![field("angle", Value::Float(PI / 2.0), "rad")]]),
+                ),
+            ],
+            ..DecodedMessage::new(1, "units")
+        };
+        let converted = UnitConverter::nautical()
+            .with_target(Unit::Foot)
+            .convert_message(&message);
+        let speed = &converted.fields[0];
+        assert!(close(speed.value.as_f64().unwrap(), 9.719222));
+        assert_eq!(speed.unit.as_deref(), Some("kn"));
+        assert!(close(converted.fields[1].value.as_f64().unwrap(), 26.85));
+        assert_eq!(converted.fields[1].unit.as_deref(), Some("°C"));
+        assert!(close(
+            converted.fields[2].value.as_f64().unwrap(),
+            32.808399
+        ));
+        // unknown units and missing values are kept
+        assert_eq!(converted.fields[3], message.fields[3]);
+        assert_eq!(converted.fields[4], message.fields[4]);
+        let rows = converted.get("set1").unwrap().as_group().unwrap();
+        assert!(close(rows[0][0].value.as_f64().unwrap(), 90.0));
+        assert_eq!(rows[0][0].unit.as_deref(), Some("deg"));
+
+        // without a target the unit is kept
+        assert_eq!(UnitConverter::new().convert_message(&message), message);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let message = DecodedMessage {
+            fields: vec![DecodedField {
+                unit: Some("m/s".to_owned()),
+                ..DecodedField::new("speed", Value::Float(5.0))
+            }],
+            ..DecodedMessage::new(130306, "windData")
+        };
+        let converted = UnitConverter::nautical().convert_message(&message);
+        let json = serde_json::to_value(&converted).unwrap();
+        assert_eq!(json["fields"][0]["name"], "speed");
+        assert_eq!(json["fields"][0]["unit"], "kn");
+        assert!(close(
+            json["fields"][0]["value"]["Float"].as_f64().unwrap(),
+            9.719222
+        ));
+        let text = serde_json::to_string(&converted).unwrap();
+        let decoded: DecodedMessage = serde_json::from_str(&text).unwrap();
+        assert_eq!(decoded.fields[0].unit.as_deref(), Some("kn"));
+        assert!(close(decoded.fields[0].value.as_f64().unwrap(), 9.719222));
+    }
+}
@@ -0,0 +1,621 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::schema::{FieldDescriptor, FieldType, MessageSchema, Variants};
+use crate::Endianness;
+use std::fmt::{self, Display, Formatter};
+
+// Consistency checks for message schemas, used when adding or editing message definitions.
+
+/// A problem found in a message schema
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    /// end is before start
+    InvalidRange { field: String },
+    /// two fields share bits
+    Overlap { field: String, other: String },
+    /// bits not covered by any field, undocumented bits should be a reserved field
+    Gap { start: usize, end: usize },
+    /// field is wider than its value type
+    TooWide {
+        field: String,
+        size: usize,
+        max: usize,
+    },
+    /// field ends past the declared payload length
+    PastLength {
+        field: String,
+        end: usize,
+        length: usize,
+    },
+    /// field or declared length exceeds the maximum payload length of the transport
+    PastMaxLength {
+        field: Option<String>,
+        length: usize,
+        max: usize,
+    },
+    /// resolution, offset or lookup that does not fit the field type
+    Scaling { field: String, reason: String },
+    /// repeating group with invalid fields or count field
+    Group { group: String, reason: String },
+    /// variant with invalid discriminators or fields
+    Variant { variant: String, reason: String },
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::InvalidRange { field } => write!(f, "field {} ends before it starts", field),
+            Issue::Overlap { field, other } => {
+                write!(f, "field {} overlaps field {}", field, other)
+            }
+            Issue::Gap { start, end } => {
+                write!(f, "bits {}..={} are not covered by a field", start, end)
+            }
+            Issue::TooWide { field, size, max } => write!(
+                f,
+                "field {} has {} bits, its type allows at most {}",
+                field, size, max
+            ),
+            Issue::PastLength { field, end, length } => write!(
+                f,
+                "field {} ends at bit {}, past the payload length of {} bytes",
+                field, end, length
+            ),
+            Issue::PastMaxLength {
+                field: Some(field),
+                length,
+                max,
+            } => write!(
+                f,
+                "field {} needs {} bytes, the transport allows {}",
+                field, length, max
+            ),
+            Issue::PastMaxLength {
+                field: None,
+                length,
+                max,
+            } => write!(
+                f,
+                "payload length {} exceeds the maximum of {} bytes",
+                length, max
+            ),
+            Issue::Scaling { field, reason } => write!(f, "field {}: {}", field, reason),
+            Issue::Group { group, reason } => write!(f, "group {}: {}", group, reason),
+            Issue::Variant { variant, reason } => write!(f, "variant {}: {}", variant, reason),
+        }
+    }
+}
+
+/// Check schema for overlapping fields, gaps, fields wider than their type, fields past the
+/// payload length, scaling or lookups that do not fit the field type, invalid repeating groups and
+/// variants.
+/// Gaps are reported in the bit numbering of the first field
+pub fn validate(schema: &MessageSchema) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    let max = schema.transport.max_length();
+    if let Some(length) = schema.length {
+        if length > max {
+            issues.push(Issue::PastMaxLength {
+                field: None,
+                length,
+                max,
+            });
+        }
+    }
+
+    for (index, group) in schema.groups.iter().enumerate() {
+        if let Some(reason) = check_group(schema, index) {
+            issues.push(Issue::Group {
+                group: group.name.clone(),
+                reason,
+            });
+        }
+    }
+
+    if let Some(variants) = &schema.variants {
+        for name in &variants.discriminators {
+            if schema.get_field(name).is_none() {
+                issues.push(Issue::Variant {
+                    variant: variants.name.clone(),
+                    reason: format!("discriminator {} does not exist", name),
+                });
+            }
+        }
+        for (index, case) in variants.cases.iter().enumerate() {
+            if let Some(reason) = check_variant(variants, index) {
+                issues.push(Issue::Variant {
+                    variant: case.name.clone(),
+                    reason,
+                });
+            }
+        }
+    }
+
+    let mut fields = Vec::new();
+    for field in &schema.fields {
+        if field.end < field.start {
+            issues.push(Issue::InvalidRange {
+                field: field.name.clone(),
+            });
+            continue;
+        }
+        if let Some(limit) = max_size(field) {
+            if field.size() > limit {
+                issues.push(Issue::TooWide {
+                    field: field.name.clone(),
+                    size: field.size(),
+                    max: limit,
+                });
+            }
+        }
+        let length = (field.end / 8) + 1;
+        match schema.length {
+            Some(declared) if field.end >= declared * 8 => issues.push(Issue::PastLength {
+                field: field.name.clone(),
+                end: field.end,
+                length: declared,
+            }),
+            _ if length > max => issues.push(Issue::PastMaxLength {
+                field: Some(field.name.clone()),
+                length,
+                max,
+            }),
+            _ => (),
+        }
+        if let Some(reason) = check_scaling(field) {
+            issues.push(Issue::Scaling {
+                field: field.name.clone(),
+                reason,
+            });
+        }
+        if let Some(lookup) = &field.indirect_lookup {
+            if lookup.key == field.name || schema.get_field(&lookup.key).is_none() {
+                issues.push(Issue::Scaling {
+                    field: field.name.clone(),
+                    reason: format!("key field {} of the indirect lookup is invalid", lookup.key),
+                });
+            }
+        }
+        fields.push(field);
+    }
+
+    // map all fields to MSB first bit positions so mixed bit orders compare correctly
+    let used = fields.iter().map(|field| field.end + 1).max().unwrap_or(0);
+    let bits = schema.length.map_or(used, |length| used.max(length * 8));
+    let mut owners: Vec<Option<usize>> = vec![None; bits.div_ceil(8) * 8];
+    for (index, field) in fields.iter().enumerate() {
+        let mut reported = Vec::new();
+        for bit in field.start..=field.end {
+            let position = physical(field, bit);
+            match owners[position] {
+                Some(other) if !reported.contains(&other) => {
+                    reported.push(other);
+                    issues.push(Issue::Overlap {
+                        field: field.name.clone(),
+                        other: fields[other].name.clone(),
+                    });
+                }
+                _ => (),
+            }
+            owners[position] = Some(index);
+        }
+    }
+
+    let order = fields
+        .first()
+        .map(|field| field.endianness)
+        .unwrap_or(Endianness::Big);
+    let free: Vec<usize> = (0..bits)
+        .filter(|bit| owners[physical_of(order, *bit)].is_none())
+        .collect();
+    let mut index = 0;
+    while index < free.len() {
+        let start = free[index];
+        while index + 1 < free.len() && free[index + 1] == free[index] + 1 {
+            index += 1;
+        }
+        issues.push(Issue::Gap {
+            start,
+            end: free[index],
+        });
+        index += 1;
+    }
+    issues
+}
+
+/// Validate schema, fails with ErrorKind::InvParam listing all issues
+pub fn check_schema(schema: &MessageSchema) -> Result<()> {
+    let issues = validate(schema);
+    if issues.is_empty() {
+        Ok(())
+    } else {
+        let list: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+        Err(Error::with_context(
+            ErrorKind::InvParam,
+            &format!(
+                "check_schema: invalid schema {}: {}",
+                schema.name,
+                list.join("; ")
+            ),
+        ))
+    }
+}
+
+// the widest field the value type can hold
+fn max_size(field: &FieldDescriptor) -> Option<usize> {
+    match field.field_type {
+        FieldType::Bool => Some(2),
+        FieldType::Float => Some(32),
+        FieldType::String | FieldType::Binary | FieldType::Reserved => None,
+        FieldType::StringLz => Some(8),
+        FieldType::StringLau => Some(16),
+        _ => Some(64),
+    }
+}
+
+fn check_scaling(field: &FieldDescriptor) -> Option<String> {
+    if !field.resolution.is_finite() || field.resolution == 0.0 {
+        return Some(format!("invalid resolution {}", field.resolution));
+    }
+    if !field.offset.is_finite() {
+        return Some(format!("invalid offset {}", field.offset));
+    }
+    let scalable = matches!(
+        field.field_type,
+        FieldType::Unsigned | FieldType::Signed | FieldType::Time
+    );
+    if field.is_scaled() && !scalable {
+        return Some(format!(
+            "resolution {} and offset {} are not supported for {:?} fields",
+            field.resolution, field.offset, field.field_type
+        ));
+    }
+    if field.lookup.is_some()
+        && !matches!(field.field_type, FieldType::Lookup | FieldType::BitLookup)
+    {
+        return Some(format!("lookup given for a {:?} field", field.field_type));
+    }
+    if field.indirect_lookup.is_some() && field.field_type != FieldType::Lookup {
+        return Some(format!(
+            "indirect lookup given for a {:?} field",
+            field.field_type
+        ));
+    }
+    if field.field_type == FieldType::BitLookup {
+        if let Some(lookup) = &field.lookup {
+            if let Some(bit) = lookup
+                .values
+                .keys()
+                .find(|bit| **bit >= field.size() as u64)
+            {
+                return Some(format!("flag bit {} is outside the field", bit));
+            }
+        }
+    }
+    None
+}
+
+fn check_group(schema: &MessageSchema, index: usize) -> Option<String> {
+    let group = &schema.groups[index];
+    if group.size == 0 || group.first + group.size > schema.fields.len() {
+        return Some(format!(
+            "fields {}..{} are outside of the {} fields",
+            group.first,
+            group.first + group.size,
+            schema.fields.len()
+        ));
+    }
+    if let Some(other) = schema.groups[..index].iter().find(|other| {
+        other.first < group.first + group.size && group.first < other.first + other.size
+    }) {
+        return Some(format!("shares fields with group {}", other.name));
+    }
+    let name = group.count_field.as_ref()?;
+    match schema.fields.iter().position(|field| field.name == *name) {
+        None => Some(format!("count field {} does not exist", name)),
+        Some(position) if position >= group.first => {
+            Some(format!("count field {} does not precede the group", name))
+        }
+        Some(position)
+            if !matches!(
+                schema.fields[position].field_type,
+                FieldType::Unsigned | FieldType::Signed
+            ) || schema.fields[position].is_scaled() =>
+        {
+            Some(format!("count field {} is not an integer", name))
+        }
+        _ => None,
+    }
+}
+
+fn check_variant(variants: &Variants, index: usize) -> Option<String> {
+    let case = &variants.cases[index];
+    if case.values.len() != variants.discriminators.len() {
+        return Some(format!(
+            "{} values given for {} discriminators",
+            case.values.len(),
+            variants.discriminators.len()
+        ));
+    }
+    if let Some(other) = variants.cases[..index]
+        .iter()
+        .find(|other| other.values == case.values)
+    {
+        return Some(format!("selected by the same values as {}", other.name));
+    }
+    for field in &case.fields {
+        if field.start < variants.start || field.end < field.start {
+            return Some(format!(
+                "field {} is outside of the variant bits starting at {}",
+                field.name, variants.start
+            ));
+        }
+        if let Some(reason) = check_scaling(field) {
+            return Some(format!("field {}: {}", field.name, reason));
+        }
+    }
+    None
+}
+
+// position of bit counting from the most significant bit of the first byte
+fn physical(field: &FieldDescriptor, bit: usize) -> usize {
+    physical_of(field.endianness, bit)
+}
+
+fn physical_of(order: Endianness, bit: usize) -> usize {
+    match order {
+        Endianness::Intel => bit / 8 * 8 + 7 - bit % 8,
+        _ => bit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{IndirectLookup, Lookup, RepeatingGroup, Transport, Variant};
+
+    fn field(name: &str, start: usize, end: usize, field_type: FieldType) -> FieldDescriptor {
+        FieldDescriptor {
+            endianness: Endianness::Intel,
+            ..FieldDescriptor::new(name, start, end, field_type)
+        }
+    }
+
+    fn schema() -> MessageSchema {
+        MessageSchema {
+            length: Some(8),
+            ..MessageSchema::new(127488, "engineParametersRapidUpdate")
+        }
+        .with_field(field("instance", 0, 7, FieldType::Lookup))
+        .with_field(FieldDescriptor {
+            resolution: 0.25,
+            ..field("speed", 8, 23, FieldType::Unsigned)
+        })
+        .with_field(field("boostPressure", 24, 39, FieldType::Unsigned))
+        .with_field(field("tiltTrim", 40, 47, FieldType::Signed))
+        .with_field(field("reserved", 48, 63, FieldType::Reserved))
+    }
+
+    #[test]
+    fn test_valid() {
+        assert_eq!(validate(&schema()), vec![]);
+        assert!(check_schema(&schema()).is_ok());
+        // 4 bit fields in the low and high nibble
+        let nibbles = MessageSchema {
+            length: Some(1),
+            ..MessageSchema::new(1, "nibbles")
+        }
+        .with_field(field("low", 0, 3, FieldType::Unsigned))
+        .with_field(field("high", 4, 7, FieldType::Unsigned));
+        assert_eq!(validate(&nibbles), vec![]);
+    }
+
+    #[test]
+    fn test_overlap_and_gap() {
+        let mut schema = schema();
+        schema.fields[2].start = 20;
+        schema.fields[4].start = 52;
+        let issues = validate(&schema);
+        assert_eq!(
+            issues,
+            vec![
+                Issue::Overlap {
+                    field: "boostPressure".to_owned(),
+                    other: "speed".to_owned()
+                },
+                Issue::Gap { start: 48, end: 51 },
+            ]
+        );
+        assert_eq!(
+            issues[0].to_string(),
+            "field boostPressure overlaps field speed"
+        );
+        let error = check_schema(&schema).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvParam);
+    }
+
+    #[test]
+    fn test_length() {
+        let mut schema = schema();
+        schema.fields[4].end = 71;
+        assert_eq!(
+            validate(&schema),
+            vec![Issue::PastLength {
+                field: "reserved".to_owned(),
+                end: 71,
+                length: 8
+            }]
+        );
+
+        schema.length = None;
+        assert_eq!(
+            validate(&schema),
+            vec![Issue::PastMaxLength {
+                field: Some("reserved".to_owned()),
+                length: 9,
+                max: 8
+            }]
+        );
+        schema.transport = Transport::FastPacket;
+        assert_eq!(validate(&schema), vec![]);
+
+        schema.length = Some(224);
+        assert!(validate(&schema).contains(&Issue::PastMaxLength {
+            field: None,
+            length: 224,
+            max: 223
+        }));
+        schema.transport = Transport::TransportProtocol;
+        assert!(!validate(&schema)
+            .iter()
+            .any(|issue| matches!(issue, Issue::PastMaxLength { .. })));
+    }
+
+    #[test]
+    fn test_types() {
+        let schema = MessageSchema {
+            length: Some(24),
+            transport: Transport::FastPacket,
+            ..MessageSchema::new(1, "types")
+        }
+        .with_field(field("wide", 0, 71, FieldType::Unsigned))
+        .with_field(field("float", 72, 111, FieldType::Float))
+        .with_field(FieldDescriptor {
+            resolution: 0.1,
+            lookup: Some(Lookup::new("YES_NO")),
+            ..field("lookup", 112, 119, FieldType::Lookup)
+        })
+        .with_field(FieldDescriptor {
+            resolution: 0.0,
+            ..field("zero", 120, 127, FieldType::Unsigned)
+        })
+        .with_field(FieldDescriptor {
+            lookup: Some(Lookup::from_pairs("FLAGS", &[(0, "a"), (8, "b")])),
+            ..field("flags", 128, 135, FieldType::BitLookup)
+        })
+        .with_field(FieldDescriptor {
+            indirect_lookup: Some(IndirectLookup::new("FUNCTION", "class")),
+            ..field("function", 136, 143, FieldType::Lookup)
+        })
+        .with_field(FieldDescriptor {
+            indirect_lookup: Some(IndirectLookup::new("FUNCTION", "function")),
+            ..field("count", 144, 151, FieldType::Unsigned)
+        })
+        .with_field(field("text", 152, 191, FieldType::String));
+        let issues = validate(&schema);
+        assert_eq!(
+            issues[0],
+            Issue::TooWide {
+                field: "wide".to_owned(),
+                size: 72,
+                max: 64
+            }
+        );
+        assert_eq!(
+            issues[1],
+            Issue::TooWide {
+                field: "float".to_owned(),
+                size: 40,
+                max: 32
+            }
+        );
+        assert_eq!(
+            issues[2].to_string(),
+            "field lookup: resolution 0.1 and offset 0 are not supported for Lookup fields"
+        );
+        assert_eq!(issues[3].to_string(), "field zero: invalid resolution 0");
+        assert_eq!(
+            issues[4].to_string(),
+            "field flags: flag bit 8 is outside the field"
+        );
+        assert_eq!(
+            issues[5].to_string(),
+            "field function: key field class of the indirect lookup is invalid"
+        );
+        assert_eq!(
+            issues[6].to_string(),
+            "field count: indirect lookup given for a Unsigned field"
+        );
+        assert_eq!(issues.len(), 7);
+    }
+
+    #[test]
+    fn test_groups() {
+        let schema = MessageSchema::new(129540, "gnssSatsInView")
+            .with_field(field("sid", 0, 7, FieldType::Unsigned))
+            .with_field(field("satsInView", 8, 15, FieldType::Unsigned))
+            .with_field(field("prn", 16, 23, FieldType::Unsigned))
+            .with_field(field("snr", 24, 31, FieldType::Unsigned));
+        let group = RepeatingGroup {
+            count_field: Some("satsInView".to_owned()),
+            ..RepeatingGroup::new("set1", 2, 2)
+        };
+        assert_eq!(validate(&schema.clone().with_group(group.clone())), vec![]);
+
+        let issues = validate(
+            &schema
+                .clone()
+                .with_group(RepeatingGroup::new("set0", 4, 1))
+                .with_group(RepeatingGroup {
+                    count_field: Some("snr".to_owned()),
+                    ..group.clone()
+                })
+                .with_group(RepeatingGroup {
+                    count_field: Some("sats".to_owned()),
+                    ..RepeatingGroup::new("set3", 2, 1)
+                }),
+        );
+        let issues: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+        assert_eq!(
+            issues,
+            vec![
+                "group set0: fields 4..5 are outside of the 4 fields",
+                "group set1: count field snr does not precede the group",
+                "group set3: shares fields with group set1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_variants() {
+        let schema = MessageSchema::new(65280, "proprietary")
+            .with_field(field("manufacturerCode", 0, 10, FieldType::Unsigned))
+            .with_field(field("reserved", 11, 12, FieldType::Reserved))
+            .with_field(field("industryCode", 13, 15, FieldType::Unsigned));
+        let variants = Variants::new("data", &["manufacturerCode", "industryCode"], 16)
+            .with_variant(Variant::new("maretron", &[137, 4]).with_field(field(
+                "command",
+                16,
+                23,
+                FieldType::Unsigned,
+            )));
+        assert_eq!(
+            validate(&schema.clone().with_variants(variants.clone())),
+            vec![]
+        );
+
+        let variants = Variants {
+            discriminators: vec!["manufacturer".to_owned()],
+            ..variants
+                .with_variant(Variant::new("garmin", &[137, 4]))
+                .with_variant(Variant::new("navico", &[275]).with_field(field(
+                    "mode",
+                    8,
+                    15,
+                    FieldType::Unsigned,
+                )))
+        };
+        let issues: Vec<String> = validate(&schema.with_variants(variants))
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                "variant data: discriminator manufacturer does not exist",
+                "variant maretron: 2 values given for 1 discriminators",
+                "variant garmin: 2 values given for 1 discriminators",
+                "variant navico: field mode is outside of the variant bits starting at 16",
+            ]
+        );
+    }
+}
@@ -0,0 +1,485 @@
+use crate::error::{Error, ErrorKind, Result};
+use crate::{BitField, MutableBitField};
+
+// Variable length integer codes starting at arbitrary bit offsets.
+// All readers return the decoded value and the number of bits consumed,
+// all writers return the number of bits written.
+
+impl<'a> BitField<'a> {
+    /// Get an unsigned LEB128 value starting at the given offset
+    pub fn get_uleb128(&self, start: usize) -> Result<(u64, usize)> {
+        let mut value: u64 = 0;
+        let mut shift = 0;
+        let mut curr = start;
+        loop {
+            let byte = self.get_u8(curr, curr + 7).map_err(|why| {
+                Error::with_all(
+                    why.kind(),
+                    &format!("get_uleb128: failed to read group at {}", curr),
+                    Box::new(why),
+                )
+            })?;
+            curr += 8;
+            let group = (byte & 0x7F) as u64;
+            if shift > 63 || (shift == 63 && group > 1) {
+                return Err(Error::with_context(
+                    ErrorKind::InvParam,
+                    &format!("get_uleb128: value at {} does not fit into 64 bits", start),
+                ));
+            }
+            value |= group << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Ok((value, curr - start));
+            }
+        }
+    }
+
+    /// Get a signed LEB128 value starting at the given offset
+    pub fn get_sleb128(&self, start: usize) -> Result<(i64, usize)> {
+        let mut value: i64 = 0;
+        let mut shift = 0;
+        let mut curr = start;
+        loop {
+            let byte = self.get_u8(curr, curr + 7).map_err(|why| {
+                Error::with_all(
+                    why.kind(),
+                    &format!("get_sleb128: failed to read group at {}", curr),
+                    Box::new(why),
+                )
+            })?;
+            curr += 8;
+            if shift > 63 {
+                return Err(Error::with_context(
+                    ErrorKind::InvParam,
+                    &format!("get_sleb128: value at {} does not fit into 64 bits", start),
+                ));
+            }
+            value |= ((byte & 0x7F) as i64) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    value |= -1i64 << shift;
+                }
+                return Ok((value, curr - start));
+            }
+        }
+    }
+
+    /// Get an Elias gamma coded value (>= 1) starting at the given offset
+    pub fn get_elias_gamma(&self, start: usize) -> Result<(u64, usize)> {
+        let zeros = self.count_bits(start, false, "get_elias_gamma")?;
+        if zeros > 63 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("get_elias_gamma: too many leading zeros at {}", start),
+            ));
+        }
+        let value = self.read_bits(start + zeros, zeros + 1, "get_elias_gamma")?;
+        Ok((value, 2 * zeros + 1))
+    }
+
+    /// Get an Elias delta coded value (>= 1) starting at the given offset
+    pub fn get_elias_delta(&self, start: usize) -> Result<(u64, usize)> {
+        let (size, consumed) = self.get_elias_gamma(start)?;
+        if size > 64 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("get_elias_delta: invalid value size {} at {}", size, start),
+            ));
+        }
+        let size = size as usize;
+        let low = self.read_bits(start + consumed, size - 1, "get_elias_delta")?;
+        Ok((1u64 << (size - 1) | low, consumed + size - 1))
+    }
+
+    /// Get an exponential Golomb coded value of order k starting at the given offset
+    pub fn get_exp_golomb(&self, start: usize, k: usize) -> Result<(u64, usize)> {
+        let zeros = self.count_bits(start, false, "get_exp_golomb")?;
+        if zeros + k > 63 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "get_exp_golomb: value at {} does not fit into 64 bits",
+                    start
+                ),
+            ));
+        }
+        let size = zeros + k + 1;
+        let value = self.read_bits(start + zeros, size, "get_exp_golomb")?;
+        Ok((value - (1u64 << k), zeros + size))
+    }
+
+    /// Get a Rice coded value with parameter k starting at the given offset
+    pub fn get_rice(&self, start: usize, k: usize) -> Result<(u64, usize)> {
+        if k > 63 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("get_rice: invalid parameter {} > 63", k),
+            ));
+        }
+        let quotient = self.count_bits(start, true, "get_rice")?;
+        let remainder = self.read_bits(start + quotient + 1, k, "get_rice")?;
+        match (quotient as u64)
+            .checked_mul(1u64 << k)
+            .and_then(|value| value.checked_add(remainder))
+        {
+            Some(value) => Ok((value, quotient + 1 + k)),
+            None => Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("get_rice: value at {} does not fit into 64 bits", start),
+            )),
+        }
+    }
+
+    /// Get a Golomb coded value with divisor m starting at the given offset
+    pub fn get_golomb(&self, start: usize, m: u64) -> Result<(u64, usize)> {
+        if m == 0 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                "get_golomb: divisor must not be 0",
+            ));
+        }
+        let quotient = self.count_bits(start, true, "get_golomb")?;
+        let mut curr = start + quotient + 1;
+        let (size, cutoff) = golomb_params(m);
+        let mut remainder = 0;
+        if size > 0 {
+            remainder = self.read_bits(curr, size - 1, "get_golomb")?;
+            curr += size - 1;
+            if remainder >= cutoff {
+                remainder = (remainder << 1 | self.read_bits(curr, 1, "get_golomb")?) - cutoff;
+                curr += 1;
+            }
+        }
+        match (quotient as u64)
+            .checked_mul(m)
+            .and_then(|value| value.checked_add(remainder))
+        {
+            Some(value) => Ok((value, curr - start)),
+            None => Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("get_golomb: value at {} does not fit into 64 bits", start),
+            )),
+        }
+    }
+
+    // count consecutive bits equal to value starting at start
+    fn count_bits(&self, start: usize, value: bool, context: &str) -> Result<usize> {
+        let mut curr = start;
+        loop {
+            match self.get_bit(curr) {
+                Ok(bit) if bit == value => curr += 1,
+                Ok(_) => return Ok(curr - start),
+                Err(why) => {
+                    return Err(Error::with_all(
+                        why.kind(),
+                        &format!("{}: unterminated code starting at {}", context, start),
+                        Box::new(why),
+                    ))
+                }
+            }
+        }
+    }
+
+    // read size bits (may be 0) big endian
+    fn read_bits(&self, start: usize, size: usize, context: &str) -> Result<u64> {
+        if size == 0 {
+            Ok(0)
+        } else {
+            self.get_u64_be(start, start + size - 1).map_err(|why| {
+                Error::with_all(
+                    why.kind(),
+                    &format!("{}: failed to read {} bits at {}", context, size, start),
+                    Box::new(why),
+                )
+            })
+        }
+    }
+}
+
+impl<'a> MutableBitField<'a> {
+    /// Set an unsigned LEB128 value starting at the given offset
+    pub fn set_uleb128(&mut self, value: u64, start: usize) -> Result<usize> {
+        let mut value = value;
+        let mut curr = start;
+        loop {
+            let mut byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value != 0 {
+                byte |= 0x80;
+            }
+            self.set_u8(byte, curr, curr + 7)?;
+            curr += 8;
+            if value == 0 {
+                return Ok(curr - start);
+            }
+        }
+    }
+
+    /// Set a signed LEB128 value starting at the given offset
+    pub fn set_sleb128(&mut self, value: i64, start: usize) -> Result<usize> {
+        let mut value = value;
+        let mut curr = start;
+        loop {
+            let mut byte = (value & 0x7F) as u8;
+            value >>= 7;
+            let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+            if !done {
+                byte |= 0x80;
+            }
+            self.set_u8(byte, curr, curr + 7)?;
+            curr += 8;
+            if done {
+                return Ok(curr - start);
+            }
+        }
+    }
+
+    /// Set an Elias gamma coded value (>= 1) starting at the given offset
+    pub fn set_elias_gamma(&mut self, value: u64, start: usize) -> Result<usize> {
+        if value == 0 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                "set_elias_gamma: value must be >= 1",
+            ));
+        }
+        let size = bit_size(value);
+        self.write_bits(0, start, size - 1)?;
+        self.write_bits(value, start + size - 1, size)?;
+        Ok(2 * size - 1)
+    }
+
+    /// Set an Elias delta coded value (>= 1) starting at the given offset
+    pub fn set_elias_delta(&mut self, value: u64, start: usize) -> Result<usize> {
+        if value == 0 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                "set_elias_delta: value must be >= 1",
+            ));
+        }
+        let size = bit_size(value);
+        let written = self.set_elias_gamma(size as u64, start)?;
+        self.write_bits(value, start + written, size - 1)?;
+        Ok(written + size - 1)
+    }
+
+    /// Set an exponential Golomb coded value of order k starting at the given offset
+    pub fn set_exp_golomb(&mut self, value: u64, start: usize, k: usize) -> Result<usize> {
+        let shifted = if k < 64 {
+            value.checked_add(1u64 << k)
+        } else {
+            None
+        };
+        match shifted {
+            Some(shifted) => {
+                let size = bit_size(shifted);
+                let zeros = size - 1 - k;
+                self.write_bits(0, start, zeros)?;
+                self.write_bits(shifted, start + zeros, size)?;
+                Ok(zeros + size)
+            }
+            None => Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!(
+                    "set_exp_golomb: value {} of order {} does not fit into 64 bits",
+                    value, k
+                ),
+            )),
+        }
+    }
+
+    /// Set a Rice coded value with parameter k starting at the given offset
+    pub fn set_rice(&mut self, value: u64, start: usize, k: usize) -> Result<usize> {
+        if k > 63 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                &format!("set_rice: invalid parameter {} > 63", k),
+            ));
+        }
+        let quotient = self.write_unary(value >> k, start, "set_rice")?;
+        self.write_bits(value, start + quotient, k)?;
+        Ok(quotient + k)
+    }
+
+    /// Set a Golomb coded value with divisor m starting at the given offset
+    pub fn set_golomb(&mut self, value: u64, start: usize, m: u64) -> Result<usize> {
+        if m == 0 {
+            return Err(Error::with_context(
+                ErrorKind::InvParam,
+                "set_golomb: divisor must not be 0",
+            ));
+        }
+        let mut curr = start + self.write_unary(value / m, start, "set_golomb")?;
+        let remainder = value % m;
+        let (size, cutoff) = golomb_params(m);
+        if size > 0 {
+            if remainder < cutoff {
+                self.write_bits(remainder, curr, size - 1)?;
+                curr += size - 1;
+            } else {
+                self.write_bits(remainder + cutoff, curr, size)?;
+                curr += size;
+            }
+        }
+        Ok(curr - start)
+    }
+
+    // write value as a run of 1 bits terminated by a 0 bit
+    fn write_unary(&mut self, value: u64, start: usize, context: &str) -> Result<usize> {
+        if value as usize >= self.bit_len() {
+            return Err(Error::with_context(
+                ErrorKind::OutOfRange,
+                &format!("{}: quotient {} exceeds the bitfield", context, value),
+            ));
+        }
+        let value = value as usize;
+        for index in start..start + value {
+            self.set_bit(index, true)?;
+        }
+        self.set_bit(start + value, false)?;
+        Ok(value + 1)
+    }
+
+    // write the lowest size bits (may be 0) of value big endian
+    fn write_bits(&mut self, value: u64, start: usize, size: usize) -> Result<()> {
+        if size == 0 {
+            Ok(())
+        } else {
+            let value = if size < 64 {
+                value & ((1u64 << size) - 1)
+            } else {
+                value
+            };
+            self.set_u64_be(value, start, start + size - 1)
+        }
+    }
+}
+
+// number of significant bits in value
+fn bit_size(value: u64) -> usize {
+    (64 - value.leading_zeros()) as usize
+}
+
+// size in bits of the long truncated binary remainder and the cutoff below
+// which the short form (size - 1 bits) is used
+fn golomb_params(m: u64) -> (usize, u64) {
+    if m == 1 {
+        (0, 0)
+    } else {
+        let size = bit_size(m - 1);
+        let cutoff = if size < 64 { (1u64 << size) - m } else { 0 };
+        (size, cutoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leb128() {
+        const BYTES: [u8; 3] = [0xE5, 0x8E, 0x26];
+        let bitfield = BitField::new(&BYTES);
+        assert_eq!(bitfield.get_uleb128(0).unwrap(), (624485, 24));
+
+        const SBYTES: [u8; 3] = [0xC0, 0xBB, 0x78];
+        let bitfield = BitField::new(&SBYTES);
+        assert_eq!(bitfield.get_sleb128(0).unwrap(), (-123456, 24));
+
+        let mut bytes: [u8; 17] = [0; 17];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        assert_eq!(bitfield.set_uleb128(624485, 3).unwrap(), 24);
+        assert_eq!(bitfield.set_sleb128(-123456, 27).unwrap(), 24);
+        assert_eq!(bitfield.set_uleb128(u64::MAX, 51).unwrap(), 80);
+        let bitfield = BitField::new(&bytes);
+        assert_eq!(bitfield.get_uleb128(3).unwrap(), (624485, 24));
+        assert_eq!(bitfield.get_sleb128(27).unwrap(), (-123456, 24));
+        assert_eq!(bitfield.get_uleb128(51).unwrap(), (u64::MAX, 80));
+
+        let mut bytes: [u8; 10] = [0; 10];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        assert!(bitfield.set_uleb128(u64::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn test_elias() {
+        // gamma(5) = 00101, gamma(1) = 1, delta(10) = 00100 010
+        const BYTES: [u8; 2] = [0b00101100, 0b10001000];
+        let bitfield = BitField::new(&BYTES);
+        assert_eq!(bitfield.get_elias_gamma(0).unwrap(), (5, 5));
+        assert_eq!(bitfield.get_elias_gamma(5).unwrap(), (1, 1));
+        assert_eq!(bitfield.get_elias_delta(6).unwrap(), (10, 8));
+        assert!(bitfield.get_elias_gamma(14).is_err());
+
+        let mut bytes: [u8; 32] = [0xFF; 32];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        let mut curr = 1;
+        for value in &[1, 2, 17, u64::MAX] {
+            curr += bitfield.set_elias_gamma(*value, curr).unwrap();
+            curr += bitfield.set_elias_delta(*value, curr).unwrap();
+        }
+        assert!(bitfield.set_elias_gamma(0, curr).is_err());
+        let bitfield = BitField::new(&bytes);
+        let mut curr = 1;
+        for value in &[1, 2, 17, u64::MAX] {
+            let (decoded, size) = bitfield.get_elias_gamma(curr).unwrap();
+            assert_eq!(decoded, *value);
+            curr += size;
+            let (decoded, size) = bitfield.get_elias_delta(curr).unwrap();
+            assert_eq!(decoded, *value);
+            curr += size;
+        }
+    }
+
+    #[test]
+    fn test_exp_golomb() {
+        // order 0: 3 = 00100, order 2: 3 = 111
+        const BYTES: [u8; 1] = [0b00100111];
+        let bitfield = BitField::new(&BYTES);
+        assert_eq!(bitfield.get_exp_golomb(0, 0).unwrap(), (3, 5));
+        assert_eq!(bitfield.get_exp_golomb(5, 2).unwrap(), (3, 3));
+
+        let mut bytes: [u8; 16] = [0; 16];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        let mut curr = 0;
+        for (value, k) in &[(0, 0), (7, 0), (1000, 3), (5, 4)] {
+            curr += bitfield.set_exp_golomb(*value, curr, *k).unwrap();
+        }
+        let bitfield = BitField::new(&bytes);
+        let mut curr = 0;
+        for (value, k) in &[(0, 0), (7, 0), (1000, 3), (5, 4)] {
+            let (decoded, size) = bitfield.get_exp_golomb(curr, *k).unwrap();
+            assert_eq!(decoded, *value);
+            curr += size;
+        }
+    }
+
+    #[test]
+    fn test_rice_golomb() {
+        // rice k=2: 9 = 110 01, golomb m=3: 7 = 110 10
+        const BYTES: [u8; 2] = [0b11001110, 0b10000000];
+        let bitfield = BitField::new(&BYTES);
+        assert_eq!(bitfield.get_rice(0, 2).unwrap(), (9, 5));
+        assert_eq!(bitfield.get_golomb(5, 3).unwrap(), (7, 5));
+
+        let mut bytes: [u8; 32] = [0; 32];
+        let mut bitfield = MutableBitField::new(&mut bytes);
+        let mut curr = 0;
+        for (value, m) in &[(0, 1), (9, 4), (7, 3), (20, 10), (5, 5)] {
+            curr += bitfield.set_golomb(*value, curr, *m).unwrap();
+            curr += bitfield.set_rice(*value, curr, 2).unwrap();
+        }
+        assert!(bitfield.set_rice(1 << 20, 0, 0).is_err());
+        let bitfield = BitField::new(&bytes);
+        let mut curr = 0;
+        for (value, m) in &[(0, 1), (9, 4), (7, 3), (20, 10), (5, 5)] {
+            let (decoded, size) = bitfield.get_golomb(curr, *m).unwrap();
+            assert_eq!(decoded, *value);
+            curr += size;
+            let (decoded, size) = bitfield.get_rice(curr, 2).unwrap();
+            assert_eq!(decoded, *value);
+            curr += size;
+        }
+    }
+}
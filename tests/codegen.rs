@@ -0,0 +1,139 @@
+#![cfg(feature = "canboat")]
+
+// Compiles the code generated from data/canboat/pgns-sample.json, the generator test in
+// src/codegen.rs checks that the file is up to date
+
+#[allow(dead_code)]
+mod pgns {
+    include!("generated/pgns_sample.rs");
+}
+
+use bitfield::{BitDecode, BitEncode, BitLookup};
+use pgns::*;
+
+#[test]
+fn test_generated_numbers() {
+    let data = [0x00, 0x40, 0x1F, 0xFF, 0xFF, 0xFB, 0xFF, 0xFF];
+    let rapid = EngineParametersRapidUpdate::from_bytes(&data).unwrap();
+    assert_eq!(EngineParametersRapidUpdate::PGN, 127488);
+    assert_eq!(
+        rapid.instance,
+        Some(EngineInstance::SingleEngineOrDualEnginePort)
+    );
+    assert_eq!(rapid.speed, Some(2000.0));
+    assert_eq!(rapid.boost_pressure, None);
+    assert_eq!(rapid.tilt_trim, Some(-5));
+    assert_eq!(rapid.to_vec().unwrap(), data);
+
+    let data = [0x01, 0xF0, 0x38, 0x4A, 0x00, 0xCC, 0xBF, 0x19];
+    let time = SystemTime::from_bytes(&data).unwrap();
+    assert_eq!(time.source, Some(SystemTimeLookup::Gps));
+    assert_eq!(time.date, Some(19000));
+    assert_eq!(time.time, Some(43200.0));
+    assert_eq!(time.to_vec().unwrap(), data);
+}
+
+#[test]
+fn test_generated_lookups() {
+    let data = [0x45, 0x23, 0x21, 0x11, 0x11, 0x82, 0x33, 0xC0];
+    let claim = IsoAddressClaim::from_bytes(&data).unwrap();
+    assert_eq!(claim.unique_number, vec![0x45, 0x23, 0x01]);
+    assert_eq!(claim.manufacturer_code, Some(ManufacturerCode::Maretron));
+    assert_eq!(claim.device_function, Some(130));
+    assert_eq!(
+        claim.device_class,
+        Some(DeviceClass::InterIntranetworkDevice)
+    );
+    assert_eq!(claim.industry_group.unwrap().name(), Some("Marine"));
+    assert_eq!(claim.device_function_name(), Some("PC Gateway"));
+    assert_eq!(claim.to_vec().unwrap(), data);
+
+    let unknown = ManufacturerCode::from_raw(1000).unwrap();
+    assert_eq!(unknown, ManufacturerCode::Unknown(1000));
+    assert_eq!(unknown.to_raw(), 1000);
+    assert_eq!(unknown.name(), None);
+    assert_eq!(
+        DeviceClass::from_name("Navigation"),
+        Some(DeviceClass::Navigation)
+    );
+}
+
+#[test]
+fn test_generated_flags() {
+    let mut data = vec![0xFF; 26];
+    data[0] = 0x01;
+    data[20..24].copy_from_slice(&[0x05, 0x01, 0x80, 0x00]);
+    let engine = EngineParametersDynamic::from_bytes(&data).unwrap();
+    let status = engine.discrete_status1;
+    assert!(status.contains(EngineStatus1::CHECK_ENGINE | EngineStatus1::LOW_OIL_PRESSURE));
+    assert!(!status.contains(EngineStatus1::OVER_TEMPERATURE));
+    assert_eq!(status.0, 0x105);
+    assert_eq!(
+        status.names(),
+        ["Check Engine", "Low Oil Pressure", "Water In Fuel"]
+    );
+    assert_eq!(engine.discrete_status2.names(), ["Engine Shutting Down"]);
+    assert_eq!(engine.to_vec().unwrap(), data);
+
+    assert_eq!(
+        EngineStatus1::from_names(&["Low Oil Pressure", "Check Engine"]),
+        Some(EngineStatus1(0x05))
+    );
+    assert_eq!(EngineStatus1::from_names(&["Fire"]), None);
+}
+
+#[test]
+fn test_generated_strings() {
+    let data = [
+        0x07, 0x01, b'H', b'e', b'l', b'l', b'o', 0x02, 0x01, 0x05, 0x01, b'A', b'B', b'C',
+    ];
+    let config = ConfigurationInformation::from_bytes(&data).unwrap();
+    assert_eq!(config.installation_description1, "Hello");
+    assert_eq!(config.installation_description2, "");
+    assert_eq!(config.manufacturer_information, "ABC");
+    assert_eq!(config.to_vec().unwrap(), data);
+
+    let mut data = vec![0xFF; 134];
+    data[0..4].copy_from_slice(&[0x64, 0x08, 0x39, 0x30]);
+    data[4..9].copy_from_slice(b"DST80");
+    let info = ProductInformation::from_bytes(&data).unwrap();
+    assert_eq!(info.model_id, "DST80");
+    assert_eq!(info.product_code, Some(12345));
+    assert_eq!(info.to_vec().unwrap(), data);
+}
+
+#[test]
+fn test_generated_repeating_fields() {
+    let mut data = vec![0x01, 0xFC, 0x02];
+    data.extend_from_slice(&[0x05, 0x10, 0x27, 0x20, 0x4E, 0x88, 0x13]);
+    data.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0x7F, 0xF2]);
+    data.extend_from_slice(&[0x0C, 0xFF, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF]);
+    data.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0x7F, 0xF0]);
+    let mut sats = GnssSatsInView::from_bytes(&data).unwrap();
+    assert_eq!(sats.set1.len(), 2);
+    assert_eq!(sats.set1[0].prn, Some(5));
+    assert_eq!(sats.set1[0].snr, Some(50.0));
+    assert_eq!(sats.set1[0].status, Some(SatelliteStatus::Used));
+    assert_eq!(sats.set1[1].elevation, None);
+    assert_eq!(sats.to_vec().unwrap(), data);
+
+    // the count follows the rows
+    sats.set1.truncate(1);
+    let encoded = sats.to_vec().unwrap();
+    assert_eq!(encoded.len(), 15);
+    assert_eq!(encoded[2], 0x01);
+    // 255 rows would be read back as a "not available" count
+    let row = sats.set1[0].clone();
+    sats.set1 = vec![row; 255];
+    assert_eq!(
+        sats.to_vec().unwrap_err().kind(),
+        bitfield::ErrorKind::OutOfRange
+    );
+
+    let data = [0x00, 0x00, 0xEE, 0x00, 0x10, 0xF0, 0x01];
+    let list = PgnListTransmitAndReceive::from_bytes(&data).unwrap();
+    assert_eq!(list.function_code, Some(PgnListFunction::TransmitPgnList));
+    let pgns: Vec<Option<u32>> = list.set1.iter().map(|row| row.pgn).collect();
+    assert_eq!(pgns, [Some(60928), Some(126992)]);
+    assert_eq!(list.to_vec().unwrap(), data);
+}
@@ -0,0 +1,2485 @@
+// Generated by bitfield-codegen, do not edit.
+// PGN database version 5.0.0
+
+/// Lookup AIS_MESSAGE_ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AisMessageId {
+    /// Scheduled Class A position report = 1
+    ScheduledClassAPositionReport,
+    /// Assigned scheduled Class A position report = 2
+    AssignedScheduledClassAPositionReport,
+    /// Interrogated Class A position report = 3
+    InterrogatedClassAPositionReport,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for AisMessageId {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            1 => AisMessageId::ScheduledClassAPositionReport,
+            2 => AisMessageId::AssignedScheduledClassAPositionReport,
+            3 => AisMessageId::InterrogatedClassAPositionReport,
+            raw => AisMessageId::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            AisMessageId::ScheduledClassAPositionReport => 1,
+            AisMessageId::AssignedScheduledClassAPositionReport => 2,
+            AisMessageId::InterrogatedClassAPositionReport => 3,
+            AisMessageId::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            AisMessageId::ScheduledClassAPositionReport => Some("Scheduled Class A position report"),
+            AisMessageId::AssignedScheduledClassAPositionReport => Some("Assigned scheduled Class A position report"),
+            AisMessageId::InterrogatedClassAPositionReport => Some("Interrogated Class A position report"),
+            AisMessageId::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Scheduled Class A position report" => Some(AisMessageId::ScheduledClassAPositionReport),
+            "Assigned scheduled Class A position report" => Some(AisMessageId::AssignedScheduledClassAPositionReport),
+            "Interrogated Class A position report" => Some(AisMessageId::InterrogatedClassAPositionReport),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup AIS_SPECIAL_MANEUVER
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AisSpecialManeuver {
+    /// Not available = 0
+    NotAvailable,
+    /// Not engaged = 1
+    NotEngaged,
+    /// Engaged = 2
+    Engaged,
+    /// Reserved = 3
+    Reserved,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for AisSpecialManeuver {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => AisSpecialManeuver::NotAvailable,
+            1 => AisSpecialManeuver::NotEngaged,
+            2 => AisSpecialManeuver::Engaged,
+            3 => AisSpecialManeuver::Reserved,
+            raw => AisSpecialManeuver::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            AisSpecialManeuver::NotAvailable => 0,
+            AisSpecialManeuver::NotEngaged => 1,
+            AisSpecialManeuver::Engaged => 2,
+            AisSpecialManeuver::Reserved => 3,
+            AisSpecialManeuver::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            AisSpecialManeuver::NotAvailable => Some("Not available"),
+            AisSpecialManeuver::NotEngaged => Some("Not engaged"),
+            AisSpecialManeuver::Engaged => Some("Engaged"),
+            AisSpecialManeuver::Reserved => Some("Reserved"),
+            AisSpecialManeuver::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Not available" => Some(AisSpecialManeuver::NotAvailable),
+            "Not engaged" => Some(AisSpecialManeuver::NotEngaged),
+            "Engaged" => Some(AisSpecialManeuver::Engaged),
+            "Reserved" => Some(AisSpecialManeuver::Reserved),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup AIS_TRANSCEIVER
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AisTransceiver {
+    /// Channel A VDL reception = 0
+    ChannelAVdlReception,
+    /// Channel B VDL reception = 1
+    ChannelBVdlReception,
+    /// Channel A VDL transmission = 2
+    ChannelAVdlTransmission,
+    /// Channel B VDL transmission = 3
+    ChannelBVdlTransmission,
+    /// Own information not broadcast = 4
+    OwnInformationNotBroadcast,
+    /// Reserved = 5
+    Reserved,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for AisTransceiver {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => AisTransceiver::ChannelAVdlReception,
+            1 => AisTransceiver::ChannelBVdlReception,
+            2 => AisTransceiver::ChannelAVdlTransmission,
+            3 => AisTransceiver::ChannelBVdlTransmission,
+            4 => AisTransceiver::OwnInformationNotBroadcast,
+            5 => AisTransceiver::Reserved,
+            raw => AisTransceiver::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            AisTransceiver::ChannelAVdlReception => 0,
+            AisTransceiver::ChannelBVdlReception => 1,
+            AisTransceiver::ChannelAVdlTransmission => 2,
+            AisTransceiver::ChannelBVdlTransmission => 3,
+            AisTransceiver::OwnInformationNotBroadcast => 4,
+            AisTransceiver::Reserved => 5,
+            AisTransceiver::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            AisTransceiver::ChannelAVdlReception => Some("Channel A VDL reception"),
+            AisTransceiver::ChannelBVdlReception => Some("Channel B VDL reception"),
+            AisTransceiver::ChannelAVdlTransmission => Some("Channel A VDL transmission"),
+            AisTransceiver::ChannelBVdlTransmission => Some("Channel B VDL transmission"),
+            AisTransceiver::OwnInformationNotBroadcast => Some("Own information not broadcast"),
+            AisTransceiver::Reserved => Some("Reserved"),
+            AisTransceiver::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Channel A VDL reception" => Some(AisTransceiver::ChannelAVdlReception),
+            "Channel B VDL reception" => Some(AisTransceiver::ChannelBVdlReception),
+            "Channel A VDL transmission" => Some(AisTransceiver::ChannelAVdlTransmission),
+            "Channel B VDL transmission" => Some(AisTransceiver::ChannelBVdlTransmission),
+            "Own information not broadcast" => Some(AisTransceiver::OwnInformationNotBroadcast),
+            "Reserved" => Some(AisTransceiver::Reserved),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup CERTIFICATION_LEVEL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CertificationLevel {
+    /// Level A = 0
+    LevelA,
+    /// Level B = 1
+    LevelB,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for CertificationLevel {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => CertificationLevel::LevelA,
+            1 => CertificationLevel::LevelB,
+            raw => CertificationLevel::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            CertificationLevel::LevelA => 0,
+            CertificationLevel::LevelB => 1,
+            CertificationLevel::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            CertificationLevel::LevelA => Some("Level A"),
+            CertificationLevel::LevelB => Some("Level B"),
+            CertificationLevel::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Level A" => Some(CertificationLevel::LevelA),
+            "Level B" => Some(CertificationLevel::LevelB),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup DEVICE_CLASS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceClass {
+    /// Reserved for 2000 Use = 0
+    ReservedFor2000Use,
+    /// Inter/Intranetwork Device = 25
+    InterIntranetworkDevice,
+    /// Navigation = 60
+    Navigation,
+    /// Sensor Communication Interface = 75
+    SensorCommunicationInterface,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for DeviceClass {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => DeviceClass::ReservedFor2000Use,
+            25 => DeviceClass::InterIntranetworkDevice,
+            60 => DeviceClass::Navigation,
+            75 => DeviceClass::SensorCommunicationInterface,
+            raw => DeviceClass::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            DeviceClass::ReservedFor2000Use => 0,
+            DeviceClass::InterIntranetworkDevice => 25,
+            DeviceClass::Navigation => 60,
+            DeviceClass::SensorCommunicationInterface => 75,
+            DeviceClass::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            DeviceClass::ReservedFor2000Use => Some("Reserved for 2000 Use"),
+            DeviceClass::InterIntranetworkDevice => Some("Inter/Intranetwork Device"),
+            DeviceClass::Navigation => Some("Navigation"),
+            DeviceClass::SensorCommunicationInterface => Some("Sensor Communication Interface"),
+            DeviceClass::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Reserved for 2000 Use" => Some(DeviceClass::ReservedFor2000Use),
+            "Inter/Intranetwork Device" => Some(DeviceClass::InterIntranetworkDevice),
+            "Navigation" => Some(DeviceClass::Navigation),
+            "Sensor Communication Interface" => Some(DeviceClass::SensorCommunicationInterface),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup ENGINE_INSTANCE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineInstance {
+    /// Single Engine or Dual Engine Port = 0
+    SingleEngineOrDualEnginePort,
+    /// Dual Engine Starboard = 1
+    DualEngineStarboard,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for EngineInstance {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => EngineInstance::SingleEngineOrDualEnginePort,
+            1 => EngineInstance::DualEngineStarboard,
+            raw => EngineInstance::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            EngineInstance::SingleEngineOrDualEnginePort => 0,
+            EngineInstance::DualEngineStarboard => 1,
+            EngineInstance::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            EngineInstance::SingleEngineOrDualEnginePort => Some("Single Engine or Dual Engine Port"),
+            EngineInstance::DualEngineStarboard => Some("Dual Engine Starboard"),
+            EngineInstance::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Single Engine or Dual Engine Port" => Some(EngineInstance::SingleEngineOrDualEnginePort),
+            "Dual Engine Starboard" => Some(EngineInstance::DualEngineStarboard),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup INDUSTRY_CODE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndustryCode {
+    /// Global = 0
+    Global,
+    /// Highway = 1
+    Highway,
+    /// Agriculture = 2
+    Agriculture,
+    /// Construction = 3
+    Construction,
+    /// Marine = 4
+    Marine,
+    /// Industrial = 5
+    Industrial,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for IndustryCode {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => IndustryCode::Global,
+            1 => IndustryCode::Highway,
+            2 => IndustryCode::Agriculture,
+            3 => IndustryCode::Construction,
+            4 => IndustryCode::Marine,
+            5 => IndustryCode::Industrial,
+            raw => IndustryCode::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            IndustryCode::Global => 0,
+            IndustryCode::Highway => 1,
+            IndustryCode::Agriculture => 2,
+            IndustryCode::Construction => 3,
+            IndustryCode::Marine => 4,
+            IndustryCode::Industrial => 5,
+            IndustryCode::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            IndustryCode::Global => Some("Global"),
+            IndustryCode::Highway => Some("Highway"),
+            IndustryCode::Agriculture => Some("Agriculture"),
+            IndustryCode::Construction => Some("Construction"),
+            IndustryCode::Marine => Some("Marine"),
+            IndustryCode::Industrial => Some("Industrial"),
+            IndustryCode::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Global" => Some(IndustryCode::Global),
+            "Highway" => Some(IndustryCode::Highway),
+            "Agriculture" => Some(IndustryCode::Agriculture),
+            "Construction" => Some(IndustryCode::Construction),
+            "Marine" => Some(IndustryCode::Marine),
+            "Industrial" => Some(IndustryCode::Industrial),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup MANUFACTURER_CODE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManufacturerCode {
+    /// Airmar = 135
+    Airmar,
+    /// Maretron = 137
+    Maretron,
+    /// Garmin = 229
+    Garmin,
+    /// Navico = 275
+    Navico,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for ManufacturerCode {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            135 => ManufacturerCode::Airmar,
+            137 => ManufacturerCode::Maretron,
+            229 => ManufacturerCode::Garmin,
+            275 => ManufacturerCode::Navico,
+            raw => ManufacturerCode::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            ManufacturerCode::Airmar => 135,
+            ManufacturerCode::Maretron => 137,
+            ManufacturerCode::Garmin => 229,
+            ManufacturerCode::Navico => 275,
+            ManufacturerCode::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            ManufacturerCode::Airmar => Some("Airmar"),
+            ManufacturerCode::Maretron => Some("Maretron"),
+            ManufacturerCode::Garmin => Some("Garmin"),
+            ManufacturerCode::Navico => Some("Navico"),
+            ManufacturerCode::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Airmar" => Some(ManufacturerCode::Airmar),
+            "Maretron" => Some(ManufacturerCode::Maretron),
+            "Garmin" => Some(ManufacturerCode::Garmin),
+            "Navico" => Some(ManufacturerCode::Navico),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup NAV_STATUS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavStatus {
+    /// Under way using engine = 0
+    UnderWayUsingEngine,
+    /// At anchor = 1
+    AtAnchor,
+    /// Not under command = 2
+    NotUnderCommand,
+    /// Restricted manoeuverability = 3
+    RestrictedManoeuverability,
+    /// Moored = 5
+    Moored,
+    /// Under way sailing = 8
+    UnderWaySailing,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for NavStatus {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => NavStatus::UnderWayUsingEngine,
+            1 => NavStatus::AtAnchor,
+            2 => NavStatus::NotUnderCommand,
+            3 => NavStatus::RestrictedManoeuverability,
+            5 => NavStatus::Moored,
+            8 => NavStatus::UnderWaySailing,
+            raw => NavStatus::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            NavStatus::UnderWayUsingEngine => 0,
+            NavStatus::AtAnchor => 1,
+            NavStatus::NotUnderCommand => 2,
+            NavStatus::RestrictedManoeuverability => 3,
+            NavStatus::Moored => 5,
+            NavStatus::UnderWaySailing => 8,
+            NavStatus::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            NavStatus::UnderWayUsingEngine => Some("Under way using engine"),
+            NavStatus::AtAnchor => Some("At anchor"),
+            NavStatus::NotUnderCommand => Some("Not under command"),
+            NavStatus::RestrictedManoeuverability => Some("Restricted manoeuverability"),
+            NavStatus::Moored => Some("Moored"),
+            NavStatus::UnderWaySailing => Some("Under way sailing"),
+            NavStatus::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Under way using engine" => Some(NavStatus::UnderWayUsingEngine),
+            "At anchor" => Some(NavStatus::AtAnchor),
+            "Not under command" => Some(NavStatus::NotUnderCommand),
+            "Restricted manoeuverability" => Some(NavStatus::RestrictedManoeuverability),
+            "Moored" => Some(NavStatus::Moored),
+            "Under way sailing" => Some(NavStatus::UnderWaySailing),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup PGN_LIST_FUNCTION
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PgnListFunction {
+    /// Transmit PGN list = 0
+    TransmitPgnList,
+    /// Receive PGN list = 1
+    ReceivePgnList,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for PgnListFunction {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => PgnListFunction::TransmitPgnList,
+            1 => PgnListFunction::ReceivePgnList,
+            raw => PgnListFunction::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            PgnListFunction::TransmitPgnList => 0,
+            PgnListFunction::ReceivePgnList => 1,
+            PgnListFunction::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            PgnListFunction::TransmitPgnList => Some("Transmit PGN list"),
+            PgnListFunction::ReceivePgnList => Some("Receive PGN list"),
+            PgnListFunction::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Transmit PGN list" => Some(PgnListFunction::TransmitPgnList),
+            "Receive PGN list" => Some(PgnListFunction::ReceivePgnList),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup POSITION_ACCURACY
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionAccuracy {
+    /// Low = 0
+    Low,
+    /// High = 1
+    High,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for PositionAccuracy {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => PositionAccuracy::Low,
+            1 => PositionAccuracy::High,
+            raw => PositionAccuracy::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            PositionAccuracy::Low => 0,
+            PositionAccuracy::High => 1,
+            PositionAccuracy::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            PositionAccuracy::Low => Some("Low"),
+            PositionAccuracy::High => Some("High"),
+            PositionAccuracy::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Low" => Some(PositionAccuracy::Low),
+            "High" => Some(PositionAccuracy::High),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup RAIM_FLAG
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaimFlag {
+    /// not in use = 0
+    NotInUse,
+    /// in use = 1
+    InUse,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for RaimFlag {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => RaimFlag::NotInUse,
+            1 => RaimFlag::InUse,
+            raw => RaimFlag::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            RaimFlag::NotInUse => 0,
+            RaimFlag::InUse => 1,
+            RaimFlag::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            RaimFlag::NotInUse => Some("not in use"),
+            RaimFlag::InUse => Some("in use"),
+            RaimFlag::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "not in use" => Some(RaimFlag::NotInUse),
+            "in use" => Some(RaimFlag::InUse),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup RANGE_RESIDUAL_MODE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeResidualMode {
+    /// Range residuals were used to calculate data = 0
+    RangeResidualsWereUsedToCalculateData,
+    /// Range residuals were calculated after the position = 1
+    RangeResidualsWereCalculatedAfterThePosition,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for RangeResidualMode {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => RangeResidualMode::RangeResidualsWereUsedToCalculateData,
+            1 => RangeResidualMode::RangeResidualsWereCalculatedAfterThePosition,
+            raw => RangeResidualMode::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            RangeResidualMode::RangeResidualsWereUsedToCalculateData => 0,
+            RangeResidualMode::RangeResidualsWereCalculatedAfterThePosition => 1,
+            RangeResidualMode::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            RangeResidualMode::RangeResidualsWereUsedToCalculateData => Some("Range residuals were used to calculate data"),
+            RangeResidualMode::RangeResidualsWereCalculatedAfterThePosition => Some("Range residuals were calculated after the position"),
+            RangeResidualMode::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Range residuals were used to calculate data" => Some(RangeResidualMode::RangeResidualsWereUsedToCalculateData),
+            "Range residuals were calculated after the position" => Some(RangeResidualMode::RangeResidualsWereCalculatedAfterThePosition),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup REPEAT_INDICATOR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepeatIndicator {
+    /// Initial = 0
+    Initial,
+    /// First retransmission = 1
+    FirstRetransmission,
+    /// Second retransmission = 2
+    SecondRetransmission,
+    /// Final retransmission = 3
+    FinalRetransmission,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for RepeatIndicator {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => RepeatIndicator::Initial,
+            1 => RepeatIndicator::FirstRetransmission,
+            2 => RepeatIndicator::SecondRetransmission,
+            3 => RepeatIndicator::FinalRetransmission,
+            raw => RepeatIndicator::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            RepeatIndicator::Initial => 0,
+            RepeatIndicator::FirstRetransmission => 1,
+            RepeatIndicator::SecondRetransmission => 2,
+            RepeatIndicator::FinalRetransmission => 3,
+            RepeatIndicator::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            RepeatIndicator::Initial => Some("Initial"),
+            RepeatIndicator::FirstRetransmission => Some("First retransmission"),
+            RepeatIndicator::SecondRetransmission => Some("Second retransmission"),
+            RepeatIndicator::FinalRetransmission => Some("Final retransmission"),
+            RepeatIndicator::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Initial" => Some(RepeatIndicator::Initial),
+            "First retransmission" => Some(RepeatIndicator::FirstRetransmission),
+            "Second retransmission" => Some(RepeatIndicator::SecondRetransmission),
+            "Final retransmission" => Some(RepeatIndicator::FinalRetransmission),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup SATELLITE_STATUS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SatelliteStatus {
+    /// Not tracked = 0
+    NotTracked,
+    /// Tracked = 1
+    Tracked,
+    /// Used = 2
+    Used,
+    /// Not tracked+Diff = 3
+    NotTrackedDiff,
+    /// Tracked+Diff = 4
+    TrackedDiff,
+    /// Used+Diff = 5
+    UsedDiff,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for SatelliteStatus {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => SatelliteStatus::NotTracked,
+            1 => SatelliteStatus::Tracked,
+            2 => SatelliteStatus::Used,
+            3 => SatelliteStatus::NotTrackedDiff,
+            4 => SatelliteStatus::TrackedDiff,
+            5 => SatelliteStatus::UsedDiff,
+            raw => SatelliteStatus::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            SatelliteStatus::NotTracked => 0,
+            SatelliteStatus::Tracked => 1,
+            SatelliteStatus::Used => 2,
+            SatelliteStatus::NotTrackedDiff => 3,
+            SatelliteStatus::TrackedDiff => 4,
+            SatelliteStatus::UsedDiff => 5,
+            SatelliteStatus::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            SatelliteStatus::NotTracked => Some("Not tracked"),
+            SatelliteStatus::Tracked => Some("Tracked"),
+            SatelliteStatus::Used => Some("Used"),
+            SatelliteStatus::NotTrackedDiff => Some("Not tracked+Diff"),
+            SatelliteStatus::TrackedDiff => Some("Tracked+Diff"),
+            SatelliteStatus::UsedDiff => Some("Used+Diff"),
+            SatelliteStatus::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Not tracked" => Some(SatelliteStatus::NotTracked),
+            "Tracked" => Some(SatelliteStatus::Tracked),
+            "Used" => Some(SatelliteStatus::Used),
+            "Not tracked+Diff" => Some(SatelliteStatus::NotTrackedDiff),
+            "Tracked+Diff" => Some(SatelliteStatus::TrackedDiff),
+            "Used+Diff" => Some(SatelliteStatus::UsedDiff),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup SYSTEM_TIME
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SystemTimeLookup {
+    /// GPS = 0
+    Gps,
+    /// GLONASS = 1
+    Glonass,
+    /// Radio Station = 2
+    RadioStation,
+    /// Local Cesium clock = 3
+    LocalCesiumClock,
+    /// Local Rubidium clock = 4
+    LocalRubidiumClock,
+    /// Local Crystal clock = 5
+    LocalCrystalClock,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for SystemTimeLookup {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => SystemTimeLookup::Gps,
+            1 => SystemTimeLookup::Glonass,
+            2 => SystemTimeLookup::RadioStation,
+            3 => SystemTimeLookup::LocalCesiumClock,
+            4 => SystemTimeLookup::LocalRubidiumClock,
+            5 => SystemTimeLookup::LocalCrystalClock,
+            raw => SystemTimeLookup::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            SystemTimeLookup::Gps => 0,
+            SystemTimeLookup::Glonass => 1,
+            SystemTimeLookup::RadioStation => 2,
+            SystemTimeLookup::LocalCesiumClock => 3,
+            SystemTimeLookup::LocalRubidiumClock => 4,
+            SystemTimeLookup::LocalCrystalClock => 5,
+            SystemTimeLookup::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            SystemTimeLookup::Gps => Some("GPS"),
+            SystemTimeLookup::Glonass => Some("GLONASS"),
+            SystemTimeLookup::RadioStation => Some("Radio Station"),
+            SystemTimeLookup::LocalCesiumClock => Some("Local Cesium clock"),
+            SystemTimeLookup::LocalRubidiumClock => Some("Local Rubidium clock"),
+            SystemTimeLookup::LocalCrystalClock => Some("Local Crystal clock"),
+            SystemTimeLookup::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "GPS" => Some(SystemTimeLookup::Gps),
+            "GLONASS" => Some(SystemTimeLookup::Glonass),
+            "Radio Station" => Some(SystemTimeLookup::RadioStation),
+            "Local Cesium clock" => Some(SystemTimeLookup::LocalCesiumClock),
+            "Local Rubidium clock" => Some(SystemTimeLookup::LocalRubidiumClock),
+            "Local Crystal clock" => Some(SystemTimeLookup::LocalCrystalClock),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup TIME_STAMP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeStamp {
+    /// Not available = 60
+    NotAvailable,
+    /// Manual input mode = 61
+    ManualInputMode,
+    /// Dead reckoning mode = 62
+    DeadReckoningMode,
+    /// Positioning system is inoperative = 63
+    PositioningSystemIsInoperative,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for TimeStamp {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            60 => TimeStamp::NotAvailable,
+            61 => TimeStamp::ManualInputMode,
+            62 => TimeStamp::DeadReckoningMode,
+            63 => TimeStamp::PositioningSystemIsInoperative,
+            raw => TimeStamp::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            TimeStamp::NotAvailable => 60,
+            TimeStamp::ManualInputMode => 61,
+            TimeStamp::DeadReckoningMode => 62,
+            TimeStamp::PositioningSystemIsInoperative => 63,
+            TimeStamp::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            TimeStamp::NotAvailable => Some("Not available"),
+            TimeStamp::ManualInputMode => Some("Manual input mode"),
+            TimeStamp::DeadReckoningMode => Some("Dead reckoning mode"),
+            TimeStamp::PositioningSystemIsInoperative => Some("Positioning system is inoperative"),
+            TimeStamp::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Not available" => Some(TimeStamp::NotAvailable),
+            "Manual input mode" => Some(TimeStamp::ManualInputMode),
+            "Dead reckoning mode" => Some(TimeStamp::DeadReckoningMode),
+            "Positioning system is inoperative" => Some(TimeStamp::PositioningSystemIsInoperative),
+            _ => None,
+        }
+    }
+}
+
+/// Lookup YES_NO
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YesNo {
+    /// No = 0
+    No,
+    /// Yes = 1
+    Yes,
+    Unknown(u64),
+}
+
+impl ::bitfield::BitLookup for YesNo {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(match raw {
+            0 => YesNo::No,
+            1 => YesNo::Yes,
+            raw => YesNo::Unknown(raw),
+        })
+    }
+
+    fn to_raw(&self) -> u64 {
+        match self {
+            YesNo::No => 0,
+            YesNo::Yes => 1,
+            YesNo::Unknown(raw) => *raw,
+        }
+    }
+
+    fn name(&self) -> Option<&'static str> {
+        match self {
+            YesNo::No => Some("No"),
+            YesNo::Yes => Some("Yes"),
+            YesNo::Unknown(_) => None,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "No" => Some(YesNo::No),
+            "Yes" => Some(YesNo::Yes),
+            _ => None,
+        }
+    }
+}
+
+/// Bit lookup ENGINE_STATUS_1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EngineStatus1(pub u64);
+
+impl EngineStatus1 {
+    /// Check Engine
+    pub const CHECK_ENGINE: EngineStatus1 = EngineStatus1(1 << 0);
+    /// Over Temperature
+    pub const OVER_TEMPERATURE: EngineStatus1 = EngineStatus1(1 << 1);
+    /// Low Oil Pressure
+    pub const LOW_OIL_PRESSURE: EngineStatus1 = EngineStatus1(1 << 2);
+    /// Low Oil Level
+    pub const LOW_OIL_LEVEL: EngineStatus1 = EngineStatus1(1 << 3);
+    /// Low Fuel Pressure
+    pub const LOW_FUEL_PRESSURE: EngineStatus1 = EngineStatus1(1 << 4);
+    /// Low System Voltage
+    pub const LOW_SYSTEM_VOLTAGE: EngineStatus1 = EngineStatus1(1 << 5);
+    /// Low Coolant Level
+    pub const LOW_COOLANT_LEVEL: EngineStatus1 = EngineStatus1(1 << 6);
+    /// Water Flow
+    pub const WATER_FLOW: EngineStatus1 = EngineStatus1(1 << 7);
+    /// Water In Fuel
+    pub const WATER_IN_FUEL: EngineStatus1 = EngineStatus1(1 << 8);
+    /// Charge Indicator
+    pub const CHARGE_INDICATOR: EngineStatus1 = EngineStatus1(1 << 9);
+    /// Preheat Indicator
+    pub const PREHEAT_INDICATOR: EngineStatus1 = EngineStatus1(1 << 10);
+    /// High Boost Pressure
+    pub const HIGH_BOOST_PRESSURE: EngineStatus1 = EngineStatus1(1 << 11);
+    /// Rev Limit Exceeded
+    pub const REV_LIMIT_EXCEEDED: EngineStatus1 = EngineStatus1(1 << 12);
+    /// EGR System
+    pub const EGR_SYSTEM: EngineStatus1 = EngineStatus1(1 << 13);
+    /// Throttle Position Sensor
+    pub const THROTTLE_POSITION_SENSOR: EngineStatus1 = EngineStatus1(1 << 14);
+    /// Emergency Stop
+    pub const EMERGENCY_STOP: EngineStatus1 = EngineStatus1(1 << 15);
+
+    /// Whether all bits of flags are set
+    pub fn contains(&self, flags: EngineStatus1) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+
+    /// Get the names of the set flags, unknown bits have no name
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        if self.contains(EngineStatus1::CHECK_ENGINE) {
+            names.push("Check Engine");
+        }
+        if self.contains(EngineStatus1::OVER_TEMPERATURE) {
+            names.push("Over Temperature");
+        }
+        if self.contains(EngineStatus1::LOW_OIL_PRESSURE) {
+            names.push("Low Oil Pressure");
+        }
+        if self.contains(EngineStatus1::LOW_OIL_LEVEL) {
+            names.push("Low Oil Level");
+        }
+        if self.contains(EngineStatus1::LOW_FUEL_PRESSURE) {
+            names.push("Low Fuel Pressure");
+        }
+        if self.contains(EngineStatus1::LOW_SYSTEM_VOLTAGE) {
+            names.push("Low System Voltage");
+        }
+        if self.contains(EngineStatus1::LOW_COOLANT_LEVEL) {
+            names.push("Low Coolant Level");
+        }
+        if self.contains(EngineStatus1::WATER_FLOW) {
+            names.push("Water Flow");
+        }
+        if self.contains(EngineStatus1::WATER_IN_FUEL) {
+            names.push("Water In Fuel");
+        }
+        if self.contains(EngineStatus1::CHARGE_INDICATOR) {
+            names.push("Charge Indicator");
+        }
+        if self.contains(EngineStatus1::PREHEAT_INDICATOR) {
+            names.push("Preheat Indicator");
+        }
+        if self.contains(EngineStatus1::HIGH_BOOST_PRESSURE) {
+            names.push("High Boost Pressure");
+        }
+        if self.contains(EngineStatus1::REV_LIMIT_EXCEEDED) {
+            names.push("Rev Limit Exceeded");
+        }
+        if self.contains(EngineStatus1::EGR_SYSTEM) {
+            names.push("EGR System");
+        }
+        if self.contains(EngineStatus1::THROTTLE_POSITION_SENSOR) {
+            names.push("Throttle Position Sensor");
+        }
+        if self.contains(EngineStatus1::EMERGENCY_STOP) {
+            names.push("Emergency Stop");
+        }
+        names
+    }
+
+    /// Get the flags with the given names, None if a name is unknown
+    pub fn from_names(names: &[&str]) -> Option<EngineStatus1> {
+        let mut raw = 0;
+        for name in names {
+            raw |= <EngineStatus1 as ::bitfield::BitLookup>::from_name(name)?.0;
+        }
+        Some(EngineStatus1(raw))
+    }
+}
+
+impl std::ops::BitOr for EngineStatus1 {
+    type Output = EngineStatus1;
+
+    fn bitor(self, other: EngineStatus1) -> EngineStatus1 {
+        EngineStatus1(self.0 | other.0)
+    }
+}
+
+impl ::bitfield::BitLookup for EngineStatus1 {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(EngineStatus1(raw))
+    }
+
+    fn to_raw(&self) -> u64 {
+        self.0
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Check Engine" => Some(EngineStatus1::CHECK_ENGINE),
+            "Over Temperature" => Some(EngineStatus1::OVER_TEMPERATURE),
+            "Low Oil Pressure" => Some(EngineStatus1::LOW_OIL_PRESSURE),
+            "Low Oil Level" => Some(EngineStatus1::LOW_OIL_LEVEL),
+            "Low Fuel Pressure" => Some(EngineStatus1::LOW_FUEL_PRESSURE),
+            "Low System Voltage" => Some(EngineStatus1::LOW_SYSTEM_VOLTAGE),
+            "Low Coolant Level" => Some(EngineStatus1::LOW_COOLANT_LEVEL),
+            "Water Flow" => Some(EngineStatus1::WATER_FLOW),
+            "Water In Fuel" => Some(EngineStatus1::WATER_IN_FUEL),
+            "Charge Indicator" => Some(EngineStatus1::CHARGE_INDICATOR),
+            "Preheat Indicator" => Some(EngineStatus1::PREHEAT_INDICATOR),
+            "High Boost Pressure" => Some(EngineStatus1::HIGH_BOOST_PRESSURE),
+            "Rev Limit Exceeded" => Some(EngineStatus1::REV_LIMIT_EXCEEDED),
+            "EGR System" => Some(EngineStatus1::EGR_SYSTEM),
+            "Throttle Position Sensor" => Some(EngineStatus1::THROTTLE_POSITION_SENSOR),
+            "Emergency Stop" => Some(EngineStatus1::EMERGENCY_STOP),
+            _ => None,
+        }
+    }
+}
+
+/// Bit lookup ENGINE_STATUS_2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EngineStatus2(pub u64);
+
+impl EngineStatus2 {
+    /// Warning Level 1
+    pub const WARNING_LEVEL1: EngineStatus2 = EngineStatus2(1 << 0);
+    /// Warning Level 2
+    pub const WARNING_LEVEL2: EngineStatus2 = EngineStatus2(1 << 1);
+    /// Power Reduction
+    pub const POWER_REDUCTION: EngineStatus2 = EngineStatus2(1 << 2);
+    /// Maintenance Needed
+    pub const MAINTENANCE_NEEDED: EngineStatus2 = EngineStatus2(1 << 3);
+    /// Engine Comm Error
+    pub const ENGINE_COMM_ERROR: EngineStatus2 = EngineStatus2(1 << 4);
+    /// Sub or Secondary Throttle
+    pub const SUB_OR_SECONDARY_THROTTLE: EngineStatus2 = EngineStatus2(1 << 5);
+    /// Neutral Start Protect
+    pub const NEUTRAL_START_PROTECT: EngineStatus2 = EngineStatus2(1 << 6);
+    /// Engine Shutting Down
+    pub const ENGINE_SHUTTING_DOWN: EngineStatus2 = EngineStatus2(1 << 7);
+
+    /// Whether all bits of flags are set
+    pub fn contains(&self, flags: EngineStatus2) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+
+    /// Get the names of the set flags, unknown bits have no name
+    pub fn names(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        if self.contains(EngineStatus2::WARNING_LEVEL1) {
+            names.push("Warning Level 1");
+        }
+        if self.contains(EngineStatus2::WARNING_LEVEL2) {
+            names.push("Warning Level 2");
+        }
+        if self.contains(EngineStatus2::POWER_REDUCTION) {
+            names.push("Power Reduction");
+        }
+        if self.contains(EngineStatus2::MAINTENANCE_NEEDED) {
+            names.push("Maintenance Needed");
+        }
+        if self.contains(EngineStatus2::ENGINE_COMM_ERROR) {
+            names.push("Engine Comm Error");
+        }
+        if self.contains(EngineStatus2::SUB_OR_SECONDARY_THROTTLE) {
+            names.push("Sub or Secondary Throttle");
+        }
+        if self.contains(EngineStatus2::NEUTRAL_START_PROTECT) {
+            names.push("Neutral Start Protect");
+        }
+        if self.contains(EngineStatus2::ENGINE_SHUTTING_DOWN) {
+            names.push("Engine Shutting Down");
+        }
+        names
+    }
+
+    /// Get the flags with the given names, None if a name is unknown
+    pub fn from_names(names: &[&str]) -> Option<EngineStatus2> {
+        let mut raw = 0;
+        for name in names {
+            raw |= <EngineStatus2 as ::bitfield::BitLookup>::from_name(name)?.0;
+        }
+        Some(EngineStatus2(raw))
+    }
+}
+
+impl std::ops::BitOr for EngineStatus2 {
+    type Output = EngineStatus2;
+
+    fn bitor(self, other: EngineStatus2) -> EngineStatus2 {
+        EngineStatus2(self.0 | other.0)
+    }
+}
+
+impl ::bitfield::BitLookup for EngineStatus2 {
+    fn from_raw(raw: u64) -> ::bitfield::Result<Self> {
+        Ok(EngineStatus2(raw))
+    }
+
+    fn to_raw(&self) -> u64 {
+        self.0
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Warning Level 1" => Some(EngineStatus2::WARNING_LEVEL1),
+            "Warning Level 2" => Some(EngineStatus2::WARNING_LEVEL2),
+            "Power Reduction" => Some(EngineStatus2::POWER_REDUCTION),
+            "Maintenance Needed" => Some(EngineStatus2::MAINTENANCE_NEEDED),
+            "Engine Comm Error" => Some(EngineStatus2::ENGINE_COMM_ERROR),
+            "Sub or Secondary Throttle" => Some(EngineStatus2::SUB_OR_SECONDARY_THROTTLE),
+            "Neutral Start Protect" => Some(EngineStatus2::NEUTRAL_START_PROTECT),
+            "Engine Shutting Down" => Some(EngineStatus2::ENGINE_SHUTTING_DOWN),
+            _ => None,
+        }
+    }
+}
+
+/// System Time (PGN 126992)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemTime {
+    /// sid
+    pub sid: Option<u8>,
+    /// source
+    pub source: Option<SystemTimeLookup>,
+    /// date, d
+    pub date: Option<u16>,
+    /// time, s
+    pub time: Option<f64>,
+}
+
+impl SystemTime {
+    pub const PGN: u32 = 126992;
+    const SID_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 0,
+        end: 7,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const SOURCE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 8,
+        end: 11,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const DATE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 16,
+        end: 31,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const TIME_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 32,
+        end: 63,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 0.0001,
+        offset: 0.0,
+        na: true,
+    };
+}
+
+impl ::bitfield::BitDecode for SystemTime {
+    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+        let sid = Self::SID_FIELD.get_i64(bitfield)?.map(|value| value as u8);
+        let source = match Self::SOURCE_FIELD.get_i64(bitfield)? { Some(value) => Some(<SystemTimeLookup as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let date = Self::DATE_FIELD.get_i64(bitfield)?.map(|value| value as u16);
+        let time = Self::TIME_FIELD.get_f64(bitfield)?;
+        Ok(SystemTime {
+            sid,
+            source,
+            date,
+            time,
+        })
+    }
+}
+
+impl ::bitfield::BitEncode for SystemTime {
+    const BIT_LEN: usize = 64;
+
+    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+        Self::SID_FIELD.set_i64(bitfield, self.sid.map(|value| value as i64))?;
+        Self::SOURCE_FIELD.set_i64(bitfield, self.source.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("reserved", 12, 15, ::bitfield::FieldType::Reserved) }, &::bitfield::Value::NotAvailable, bitfield)?;
+        Self::DATE_FIELD.set_i64(bitfield, self.date.map(|value| value as i64))?;
+        Self::TIME_FIELD.set_f64(bitfield, self.time)?;
+        Ok(())
+    }
+}
+
+/// Product Information (PGN 126996)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductInformation {
+    /// nmea2000Version
+    pub nmea2000_version: Option<f64>,
+    /// productCode
+    pub product_code: Option<u16>,
+    /// modelId
+    pub model_id: String,
+    /// softwareVersionCode
+    pub software_version_code: String,
+    /// modelVersion
+    pub model_version: String,
+    /// modelSerialCode
+    pub model_serial_code: String,
+    /// certificationLevel
+    pub certification_level: Option<CertificationLevel>,
+    /// loadEquivalency
+    pub load_equivalency: Option<u8>,
+}
+
+impl ProductInformation {
+    pub const PGN: u32 = 126996;
+    const NMEA2000_VERSION_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 0,
+        end: 15,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 0.001,
+        offset: 0.0,
+        na: true,
+    };
+    const PRODUCT_CODE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 16,
+        end: 31,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const CERTIFICATION_LEVEL_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 1056,
+        end: 1063,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const LOAD_EQUIVALENCY_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 1064,
+        end: 1071,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+}
+
+impl ::bitfield::BitDecode for ProductInformation {
+    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+        let nmea2000_version = Self::NMEA2000_VERSION_FIELD.get_f64(bitfield)?;
+        let product_code = Self::PRODUCT_CODE_FIELD.get_i64(bitfield)?.map(|value| value as u16);
+        let model_id = match ::bitfield::dynamic::decode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("modelId", 32, 287, ::bitfield::FieldType::String) }, bitfield)? { ::bitfield::Value::String(text) => text, _ => String::new() };
+        let software_version_code = match ::bitfield::dynamic::decode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("softwareVersionCode", 288, 543, ::bitfield::FieldType::String) }, bitfield)? { ::bitfield::Value::String(text) => text, _ => String::new() };
+        let model_version = match ::bitfield::dynamic::decode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("modelVersion", 544, 799, ::bitfield::FieldType::String) }, bitfield)? { ::bitfield::Value::String(text) => text, _ => String::new() };
+        let model_serial_code = match ::bitfield::dynamic::decode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("modelSerialCode", 800, 1055, ::bitfield::FieldType::String) }, bitfield)? { ::bitfield::Value::String(text) => text, _ => String::new() };
+        let certification_level = match Self::CERTIFICATION_LEVEL_FIELD.get_i64(bitfield)? { Some(value) => Some(<CertificationLevel as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let load_equivalency = Self::LOAD_EQUIVALENCY_FIELD.get_i64(bitfield)?.map(|value| value as u8);
+        Ok(ProductInformation {
+            nmea2000_version,
+            product_code,
+            model_id,
+            software_version_code,
+            model_version,
+            model_serial_code,
+            certification_level,
+            load_equivalency,
+        })
+    }
+}
+
+impl ::bitfield::BitEncode for ProductInformation {
+    const BIT_LEN: usize = 1072;
+
+    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+        Self::NMEA2000_VERSION_FIELD.set_f64(bitfield, self.nmea2000_version)?;
+        Self::PRODUCT_CODE_FIELD.set_i64(bitfield, self.product_code.map(|value| value as i64))?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("modelId", 32, 287, ::bitfield::FieldType::String) }, &::bitfield::Value::String(self.model_id.clone()), bitfield)?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("softwareVersionCode", 288, 543, ::bitfield::FieldType::String) }, &::bitfield::Value::String(self.software_version_code.clone()), bitfield)?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("modelVersion", 544, 799, ::bitfield::FieldType::String) }, &::bitfield::Value::String(self.model_version.clone()), bitfield)?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("modelSerialCode", 800, 1055, ::bitfield::FieldType::String) }, &::bitfield::Value::String(self.model_serial_code.clone()), bitfield)?;
+        Self::CERTIFICATION_LEVEL_FIELD.set_i64(bitfield, self.certification_level.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::LOAD_EQUIVALENCY_FIELD.set_i64(bitfield, self.load_equivalency.map(|value| value as i64))?;
+        Ok(())
+    }
+}
+
+/// Configuration Information (PGN 126998)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigurationInformation {
+    /// installationDescription1
+    pub installation_description1: String,
+    /// installationDescription2
+    pub installation_description2: String,
+    /// manufacturerInformation
+    pub manufacturer_information: String,
+}
+
+impl ConfigurationInformation {
+    pub const PGN: u32 = 126998;
+}
+
+impl ::bitfield::BitDecode for ConfigurationInformation {
+    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+        let mut shift = 0;
+        let installation_description1 = match ::bitfield::dynamic::decode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("installationDescription1", 0, 15, ::bitfield::FieldType::StringLau) }, bitfield)? { ::bitfield::Value::String(text) => text, _ => String::new() };
+        shift += (bitfield.get_u8(0, 7)? as usize).max(2) * 8 - 16;
+        let installation_description2 = match ::bitfield::dynamic::decode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("installationDescription2", 16 + shift, 31 + shift, ::bitfield::FieldType::StringLau) }, bitfield)? { ::bitfield::Value::String(text) => text, _ => String::new() };
+        shift += (bitfield.get_u8(16 + shift, 23 + shift)? as usize).max(2) * 8 - 16;
+        let manufacturer_information = match ::bitfield::dynamic::decode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("manufacturerInformation", 32 + shift, 47 + shift, ::bitfield::FieldType::StringLau) }, bitfield)? { ::bitfield::Value::String(text) => text, _ => String::new() };
+        Ok(ConfigurationInformation {
+            installation_description1,
+            installation_description2,
+            manufacturer_information,
+        })
+    }
+}
+
+impl ::bitfield::BitEncode for ConfigurationInformation {
+    const BIT_LEN: usize = 48;
+
+    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+        let mut shift = 0;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("installationDescription1", 0, 15, ::bitfield::FieldType::StringLau) }, &::bitfield::Value::String(self.installation_description1.clone()), bitfield)?;
+        shift += self.installation_description1.len() * 8;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("installationDescription2", 16 + shift, 31 + shift, ::bitfield::FieldType::StringLau) }, &::bitfield::Value::String(self.installation_description2.clone()), bitfield)?;
+        shift += self.installation_description2.len() * 8;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("manufacturerInformation", 32 + shift, 47 + shift, ::bitfield::FieldType::StringLau) }, &::bitfield::Value::String(self.manufacturer_information.clone()), bitfield)?;
+        Ok(())
+    }
+
+    fn to_vec(&self) -> ::bitfield::Result<Vec<u8>> {
+        let bits = Self::BIT_LEN + self.installation_description1.len() * 8 + self.installation_description2.len() * 8 + self.manufacturer_information.len() * 8;
+        let mut data = vec![0xFF; bits.div_ceil(8)];
+        self.encode(&mut ::bitfield::MutableBitField::new(&mut data))?;
+        Ok(data)
+    }
+}
+
+/// Engine Parameters, Rapid Update (PGN 127488)
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineParametersRapidUpdate {
+    /// instance
+    pub instance: Option<EngineInstance>,
+    /// speed, rpm
+    pub speed: Option<f64>,
+    /// boostPressure, Pa
+    pub boost_pressure: Option<f64>,
+    /// tiltTrim, %
+    pub tilt_trim: Option<i8>,
+}
+
+impl EngineParametersRapidUpdate {
+    pub const PGN: u32 = 127488;
+    const INSTANCE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 0,
+        end: 7,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const SPEED_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 8,
+        end: 23,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 0.25,
+        offset: 0.0,
+        na: true,
+    };
+    const BOOST_PRESSURE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 24,
+        end: 39,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 100.0,
+        offset: 0.0,
+        na: true,
+    };
+    const TILT_TRIM_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 40,
+        end: 47,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+}
+
+impl ::bitfield::BitDecode for EngineParametersRapidUpdate {
+    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+        let instance = match Self::INSTANCE_FIELD.get_i64(bitfield)? { Some(value) => Some(<EngineInstance as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let speed = Self::SPEED_FIELD.get_f64(bitfield)?;
+        let boost_pressure = Self::BOOST_PRESSURE_FIELD.get_f64(bitfield)?;
+        let tilt_trim = Self::TILT_TRIM_FIELD.get_i64(bitfield)?.map(|value| value as i8);
+        Ok(EngineParametersRapidUpdate {
+            instance,
+            speed,
+            boost_pressure,
+            tilt_trim,
+        })
+    }
+}
+
+impl ::bitfield::BitEncode for EngineParametersRapidUpdate {
+    const BIT_LEN: usize = 64;
+
+    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+        Self::INSTANCE_FIELD.set_i64(bitfield, self.instance.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::SPEED_FIELD.set_f64(bitfield, self.speed)?;
+        Self::BOOST_PRESSURE_FIELD.set_f64(bitfield, self.boost_pressure)?;
+        Self::TILT_TRIM_FIELD.set_i64(bitfield, self.tilt_trim.map(|value| value as i64))?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("reserved", 48, 63, ::bitfield::FieldType::Reserved) }, &::bitfield::Value::NotAvailable, bitfield)?;
+        Ok(())
+    }
+}
+
+/// Engine Parameters, Dynamic (PGN 127489)
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineParametersDynamic {
+    /// instance
+    pub instance: Option<EngineInstance>,
+    /// oilPressure, Pa
+    pub oil_pressure: Option<f64>,
+    /// oilTemperature, K
+    pub oil_temperature: Option<f64>,
+    /// temperature, K
+    pub temperature: Option<f64>,
+    /// alternatorPotential, V
+    pub alternator_potential: Option<f64>,
+    /// fuelRate, L/h
+    pub fuel_rate: Option<f64>,
+    /// totalEngineHours, s
+    pub total_engine_hours: Option<u32>,
+    /// coolantPressure, Pa
+    pub coolant_pressure: Option<f64>,
+    /// fuelPressure, Pa
+    pub fuel_pressure: Option<f64>,
+    /// discreteStatus1
+    pub discrete_status1: EngineStatus1,
+    /// discreteStatus2
+    pub discrete_status2: EngineStatus2,
+    /// engineLoad, %
+    pub engine_load: Option<i8>,
+    /// engineTorque, %
+    pub engine_torque: Option<i8>,
+}
+
+impl EngineParametersDynamic {
+    pub const PGN: u32 = 127489;
+    const INSTANCE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 0,
+        end: 7,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const OIL_PRESSURE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 8,
+        end: 23,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 100.0,
+        offset: 0.0,
+        na: true,
+    };
+    const OIL_TEMPERATURE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 24,
+        end: 39,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 0.1,
+        offset: 0.0,
+        na: true,
+    };
+    const TEMPERATURE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 40,
+        end: 55,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 0.01,
+        offset: 0.0,
+        na: true,
+    };
+    const ALTERNATOR_POTENTIAL_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 56,
+        end: 71,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 0.01,
+        offset: 0.0,
+        na: true,
+    };
+    const FUEL_RATE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 72,
+        end: 87,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 0.1,
+        offset: 0.0,
+        na: true,
+    };
+    const TOTAL_ENGINE_HOURS_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 88,
+        end: 119,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const COOLANT_PRESSURE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 120,
+        end: 135,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 100.0,
+        offset: 0.0,
+        na: true,
+    };
+    const FUEL_PRESSURE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 136,
+        end: 151,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1000.0,
+        offset: 0.0,
+        na: true,
+    };
+    const DISCRETE_STATUS1_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 160,
+        end: 175,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: false,
+    };
+    const DISCRETE_STATUS2_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 176,
+        end: 191,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: false,
+    };
+    const ENGINE_LOAD_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 192,
+        end: 199,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const ENGINE_TORQUE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 200,
+        end: 207,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+}
+
+impl ::bitfield::BitDecode for EngineParametersDynamic {
+    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+        let instance = match Self::INSTANCE_FIELD.get_i64(bitfield)? { Some(value) => Some(<EngineInstance as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let oil_pressure = Self::OIL_PRESSURE_FIELD.get_f64(bitfield)?;
+        let oil_temperature = Self::OIL_TEMPERATURE_FIELD.get_f64(bitfield)?;
+        let temperature = Self::TEMPERATURE_FIELD.get_f64(bitfield)?;
+        let alternator_potential = Self::ALTERNATOR_POTENTIAL_FIELD.get_f64(bitfield)?;
+        let fuel_rate = Self::FUEL_RATE_FIELD.get_f64(bitfield)?;
+        let total_engine_hours = Self::TOTAL_ENGINE_HOURS_FIELD.get_i64(bitfield)?.map(|value| value as u32);
+        let coolant_pressure = Self::COOLANT_PRESSURE_FIELD.get_f64(bitfield)?;
+        let fuel_pressure = Self::FUEL_PRESSURE_FIELD.get_f64(bitfield)?;
+        let discrete_status1 = <EngineStatus1 as ::bitfield::BitLookup>::from_raw(Self::DISCRETE_STATUS1_FIELD.get_raw(bitfield)?)?;
+        let discrete_status2 = <EngineStatus2 as ::bitfield::BitLookup>::from_raw(Self::DISCRETE_STATUS2_FIELD.get_raw(bitfield)?)?;
+        let engine_load = Self::ENGINE_LOAD_FIELD.get_i64(bitfield)?.map(|value| value as i8);
+        let engine_torque = Self::ENGINE_TORQUE_FIELD.get_i64(bitfield)?.map(|value| value as i8);
+        Ok(EngineParametersDynamic {
+            instance,
+            oil_pressure,
+            oil_temperature,
+            temperature,
+            alternator_potential,
+            fuel_rate,
+            total_engine_hours,
+            coolant_pressure,
+            fuel_pressure,
+            discrete_status1,
+            discrete_status2,
+            engine_load,
+            engine_torque,
+        })
+    }
+}
+
+impl ::bitfield::BitEncode for EngineParametersDynamic {
+    const BIT_LEN: usize = 208;
+
+    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+        Self::INSTANCE_FIELD.set_i64(bitfield, self.instance.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::OIL_PRESSURE_FIELD.set_f64(bitfield, self.oil_pressure)?;
+        Self::OIL_TEMPERATURE_FIELD.set_f64(bitfield, self.oil_temperature)?;
+        Self::TEMPERATURE_FIELD.set_f64(bitfield, self.temperature)?;
+        Self::ALTERNATOR_POTENTIAL_FIELD.set_f64(bitfield, self.alternator_potential)?;
+        Self::FUEL_RATE_FIELD.set_f64(bitfield, self.fuel_rate)?;
+        Self::TOTAL_ENGINE_HOURS_FIELD.set_i64(bitfield, self.total_engine_hours.map(|value| value as i64))?;
+        Self::COOLANT_PRESSURE_FIELD.set_f64(bitfield, self.coolant_pressure)?;
+        Self::FUEL_PRESSURE_FIELD.set_f64(bitfield, self.fuel_pressure)?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("reserved", 152, 159, ::bitfield::FieldType::Reserved) }, &::bitfield::Value::NotAvailable, bitfield)?;
+        Self::DISCRETE_STATUS1_FIELD.set_i64(bitfield, Some(::bitfield::BitLookup::to_raw(&self.discrete_status1) as i64))?;
+        Self::DISCRETE_STATUS2_FIELD.set_i64(bitfield, Some(::bitfield::BitLookup::to_raw(&self.discrete_status2) as i64))?;
+        Self::ENGINE_LOAD_FIELD.set_i64(bitfield, self.engine_load.map(|value| value as i64))?;
+        Self::ENGINE_TORQUE_FIELD.set_i64(bitfield, self.engine_torque.map(|value| value as i64))?;
+        Ok(())
+    }
+}
+
+/// Position, Rapid Update (PGN 129025)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionRapidUpdate {
+    /// latitude, deg
+    pub latitude: Option<f64>,
+    /// longitude, deg
+    pub longitude: Option<f64>,
+}
+
+impl PositionRapidUpdate {
+    pub const PGN: u32 = 129025;
+    const LATITUDE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 0,
+        end: 31,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 1e-7,
+        offset: 0.0,
+        na: true,
+    };
+    const LONGITUDE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 32,
+        end: 63,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 1e-7,
+        offset: 0.0,
+        na: true,
+    };
+}
+
+impl ::bitfield::BitDecode for PositionRapidUpdate {
+    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+        let latitude = Self::LATITUDE_FIELD.get_f64(bitfield)?;
+        let longitude = Self::LONGITUDE_FIELD.get_f64(bitfield)?;
+        Ok(PositionRapidUpdate {
+            latitude,
+            longitude,
+        })
+    }
+}
+
+impl ::bitfield::BitEncode for PositionRapidUpdate {
+    const BIT_LEN: usize = 64;
+
+    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+        Self::LATITUDE_FIELD.set_f64(bitfield, self.latitude)?;
+        Self::LONGITUDE_FIELD.set_f64(bitfield, self.longitude)?;
+        Ok(())
+    }
+}
+
+/// AIS Class A Position Report (PGN 129038)
+#[derive(Debug, Clone, PartialEq)]
+pub struct AisClassAPositionReport {
+    /// messageId
+    pub message_id: Option<AisMessageId>,
+    /// repeatIndicator
+    pub repeat_indicator: Option<RepeatIndicator>,
+    /// userId
+    pub user_id: Option<u32>,
+    /// longitude, deg
+    pub longitude: Option<f64>,
+    /// latitude, deg
+    pub latitude: Option<f64>,
+    /// positionAccuracy
+    pub position_accuracy: Option<PositionAccuracy>,
+    /// raim
+    pub raim: Option<RaimFlag>,
+    /// timeStamp
+    pub time_stamp: Option<TimeStamp>,
+    /// cog, rad
+    pub cog: Option<f64>,
+    /// sog, m/s
+    pub sog: Option<f64>,
+    /// communicationState
+    pub communication_state: Vec<u8>,
+    /// aisTransceiverInformation
+    pub ais_transceiver_information: Option<AisTransceiver>,
+    /// heading, rad
+    pub heading: Option<f64>,
+    /// rateOfTurn, rad/s
+    pub rate_of_turn: Option<f64>,
+    /// navStatus
+    pub nav_status: Option<NavStatus>,
+    /// specialManeuverIndicator
+    pub special_maneuver_indicator: Option<AisSpecialManeuver>,
+    /// sequenceId
+    pub sequence_id: Option<u8>,
+}
+
+impl AisClassAPositionReport {
+    pub const PGN: u32 = 129038;
+    const MESSAGE_ID_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 0,
+        end: 5,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const REPEAT_INDICATOR_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 6,
+        end: 7,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const USER_ID_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 8,
+        end: 39,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const LONGITUDE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 40,
+        end: 71,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 1e-7,
+        offset: 0.0,
+        na: true,
+    };
+    const LATITUDE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 72,
+        end: 103,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 1e-7,
+        offset: 0.0,
+        na: true,
+    };
+    const POSITION_ACCURACY_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 104,
+        end: 104,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const RAIM_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 105,
+        end: 105,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const TIME_STAMP_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 106,
+        end: 111,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const COG_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 112,
+        end: 127,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 0.0001,
+        offset: 0.0,
+        na: true,
+    };
+    const SOG_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 128,
+        end: 143,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 0.01,
+        offset: 0.0,
+        na: true,
+    };
+    const AIS_TRANSCEIVER_INFORMATION_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 163,
+        end: 167,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const HEADING_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 168,
+        end: 183,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 0.0001,
+        offset: 0.0,
+        na: true,
+    };
+    const RATE_OF_TURN_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 184,
+        end: 199,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 3.125e-5,
+        offset: 0.0,
+        na: true,
+    };
+    const NAV_STATUS_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 200,
+        end: 203,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const SPECIAL_MANEUVER_INDICATOR_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 204,
+        end: 205,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const SEQUENCE_ID_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 216,
+        end: 223,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+}
+
+impl ::bitfield::BitDecode for AisClassAPositionReport {
+    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+        let message_id = match Self::MESSAGE_ID_FIELD.get_i64(bitfield)? { Some(value) => Some(<AisMessageId as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let repeat_indicator = match Self::REPEAT_INDICATOR_FIELD.get_i64(bitfield)? { Some(value) => Some(<RepeatIndicator as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let user_id = Self::USER_ID_FIELD.get_i64(bitfield)?.map(|value| value as u32);
+        let longitude = Self::LONGITUDE_FIELD.get_f64(bitfield)?;
+        let latitude = Self::LATITUDE_FIELD.get_f64(bitfield)?;
+        let position_accuracy = match Self::POSITION_ACCURACY_FIELD.get_i64(bitfield)? { Some(value) => Some(<PositionAccuracy as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let raim = match Self::RAIM_FIELD.get_i64(bitfield)? { Some(value) => Some(<RaimFlag as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let time_stamp = match Self::TIME_STAMP_FIELD.get_i64(bitfield)? { Some(value) => Some(<TimeStamp as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let cog = Self::COG_FIELD.get_f64(bitfield)?;
+        let sog = Self::SOG_FIELD.get_f64(bitfield)?;
+        let communication_state = match ::bitfield::dynamic::decode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("communicationState", 144, 162, ::bitfield::FieldType::Binary) }, bitfield)? { ::bitfield::Value::Bytes(bytes) => bytes, _ => Vec::new() };
+        let ais_transceiver_information = match Self::AIS_TRANSCEIVER_INFORMATION_FIELD.get_i64(bitfield)? { Some(value) => Some(<AisTransceiver as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let heading = Self::HEADING_FIELD.get_f64(bitfield)?;
+        let rate_of_turn = Self::RATE_OF_TURN_FIELD.get_f64(bitfield)?;
+        let nav_status = match Self::NAV_STATUS_FIELD.get_i64(bitfield)? { Some(value) => Some(<NavStatus as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let special_maneuver_indicator = match Self::SPECIAL_MANEUVER_INDICATOR_FIELD.get_i64(bitfield)? { Some(value) => Some(<AisSpecialManeuver as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let sequence_id = Self::SEQUENCE_ID_FIELD.get_i64(bitfield)?.map(|value| value as u8);
+        Ok(AisClassAPositionReport {
+            message_id,
+            repeat_indicator,
+            user_id,
+            longitude,
+            latitude,
+            position_accuracy,
+            raim,
+            time_stamp,
+            cog,
+            sog,
+            communication_state,
+            ais_transceiver_information,
+            heading,
+            rate_of_turn,
+            nav_status,
+            special_maneuver_indicator,
+            sequence_id,
+        })
+    }
+}
+
+impl ::bitfield::BitEncode for AisClassAPositionReport {
+    const BIT_LEN: usize = 224;
+
+    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+        Self::MESSAGE_ID_FIELD.set_i64(bitfield, self.message_id.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::REPEAT_INDICATOR_FIELD.set_i64(bitfield, self.repeat_indicator.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::USER_ID_FIELD.set_i64(bitfield, self.user_id.map(|value| value as i64))?;
+        Self::LONGITUDE_FIELD.set_f64(bitfield, self.longitude)?;
+        Self::LATITUDE_FIELD.set_f64(bitfield, self.latitude)?;
+        Self::POSITION_ACCURACY_FIELD.set_i64(bitfield, self.position_accuracy.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::RAIM_FIELD.set_i64(bitfield, self.raim.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::TIME_STAMP_FIELD.set_i64(bitfield, self.time_stamp.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::COG_FIELD.set_f64(bitfield, self.cog)?;
+        Self::SOG_FIELD.set_f64(bitfield, self.sog)?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("communicationState", 144, 162, ::bitfield::FieldType::Binary) }, &::bitfield::Value::Bytes(self.communication_state.clone()), bitfield)?;
+        Self::AIS_TRANSCEIVER_INFORMATION_FIELD.set_i64(bitfield, self.ais_transceiver_information.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::HEADING_FIELD.set_f64(bitfield, self.heading)?;
+        Self::RATE_OF_TURN_FIELD.set_f64(bitfield, self.rate_of_turn)?;
+        Self::NAV_STATUS_FIELD.set_i64(bitfield, self.nav_status.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::SPECIAL_MANEUVER_INDICATOR_FIELD.set_i64(bitfield, self.special_maneuver_indicator.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("reserved", 206, 207, ::bitfield::FieldType::Reserved) }, &::bitfield::Value::NotAvailable, bitfield)?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("aisSpare", 208, 210, ::bitfield::FieldType::Reserved) }, &::bitfield::Value::NotAvailable, bitfield)?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("reserved19", 211, 215, ::bitfield::FieldType::Reserved) }, &::bitfield::Value::NotAvailable, bitfield)?;
+        Self::SEQUENCE_ID_FIELD.set_i64(bitfield, self.sequence_id.map(|value| value as i64))?;
+        Ok(())
+    }
+}
+
+/// ISO Address Claim (PGN 60928)
+#[derive(Debug, Clone, PartialEq)]
+pub struct IsoAddressClaim {
+    /// uniqueNumber
+    pub unique_number: Vec<u8>,
+    /// manufacturerCode
+    pub manufacturer_code: Option<ManufacturerCode>,
+    /// deviceInstanceLower
+    pub device_instance_lower: Option<u8>,
+    /// deviceInstanceUpper
+    pub device_instance_upper: Option<u8>,
+    /// deviceFunction
+    pub device_function: Option<u8>,
+    /// deviceClass
+    pub device_class: Option<DeviceClass>,
+    /// systemInstance
+    pub system_instance: Option<u8>,
+    /// industryGroup
+    pub industry_group: Option<IndustryCode>,
+    /// arbitraryAddressCapable
+    pub arbitrary_address_capable: Option<YesNo>,
+}
+
+impl IsoAddressClaim {
+    pub const PGN: u32 = 60928;
+    const MANUFACTURER_CODE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 21,
+        end: 31,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const DEVICE_INSTANCE_LOWER_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 32,
+        end: 34,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const DEVICE_INSTANCE_UPPER_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 35,
+        end: 39,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const DEVICE_FUNCTION_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 40,
+        end: 47,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const DEVICE_CLASS_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 49,
+        end: 55,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const SYSTEM_INSTANCE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 56,
+        end: 59,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const INDUSTRY_GROUP_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 60,
+        end: 62,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const ARBITRARY_ADDRESS_CAPABLE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 63,
+        end: 63,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+
+    /// Name of device_function by device_class, lookup DEVICE_FUNCTION
+    pub fn device_function_name(&self) -> Option<&'static str> {
+        match (self.device_class.map(|value| ::bitfield::BitLookup::to_raw(&value))?, self.device_function? as u64) {
+            (10, 130) => Some("Diagnostic"),
+            (25, 130) => Some("PC Gateway"),
+            (60, 130) => Some("Bottom Depth"),
+            (60, 140) => Some("Ownship Attitude"),
+            _ => None,
+        }
+    }
+}
+
+impl ::bitfield::BitDecode for IsoAddressClaim {
+    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+        let unique_number = match ::bitfield::dynamic::decode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("uniqueNumber", 0, 20, ::bitfield::FieldType::Binary) }, bitfield)? { ::bitfield::Value::Bytes(bytes) => bytes, _ => Vec::new() };
+        let manufacturer_code = match Self::MANUFACTURER_CODE_FIELD.get_i64(bitfield)? { Some(value) => Some(<ManufacturerCode as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let device_instance_lower = Self::DEVICE_INSTANCE_LOWER_FIELD.get_i64(bitfield)?.map(|value| value as u8);
+        let device_instance_upper = Self::DEVICE_INSTANCE_UPPER_FIELD.get_i64(bitfield)?.map(|value| value as u8);
+        let device_function = Self::DEVICE_FUNCTION_FIELD.get_i64(bitfield)?.map(|value| value as u8);
+        let device_class = match Self::DEVICE_CLASS_FIELD.get_i64(bitfield)? { Some(value) => Some(<DeviceClass as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let system_instance = Self::SYSTEM_INSTANCE_FIELD.get_i64(bitfield)?.map(|value| value as u8);
+        let industry_group = match Self::INDUSTRY_GROUP_FIELD.get_i64(bitfield)? { Some(value) => Some(<IndustryCode as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let arbitrary_address_capable = match Self::ARBITRARY_ADDRESS_CAPABLE_FIELD.get_i64(bitfield)? { Some(value) => Some(<YesNo as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        Ok(IsoAddressClaim {
+            unique_number,
+            manufacturer_code,
+            device_instance_lower,
+            device_instance_upper,
+            device_function,
+            device_class,
+            system_instance,
+            industry_group,
+            arbitrary_address_capable,
+        })
+    }
+}
+
+impl ::bitfield::BitEncode for IsoAddressClaim {
+    const BIT_LEN: usize = 64;
+
+    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("uniqueNumber", 0, 20, ::bitfield::FieldType::Binary) }, &::bitfield::Value::Bytes(self.unique_number.clone()), bitfield)?;
+        Self::MANUFACTURER_CODE_FIELD.set_i64(bitfield, self.manufacturer_code.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::DEVICE_INSTANCE_LOWER_FIELD.set_i64(bitfield, self.device_instance_lower.map(|value| value as i64))?;
+        Self::DEVICE_INSTANCE_UPPER_FIELD.set_i64(bitfield, self.device_instance_upper.map(|value| value as i64))?;
+        Self::DEVICE_FUNCTION_FIELD.set_i64(bitfield, self.device_function.map(|value| value as i64))?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("spare", 48, 48, ::bitfield::FieldType::Reserved) }, &::bitfield::Value::NotAvailable, bitfield)?;
+        Self::DEVICE_CLASS_FIELD.set_i64(bitfield, self.device_class.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::SYSTEM_INSTANCE_FIELD.set_i64(bitfield, self.system_instance.map(|value| value as i64))?;
+        Self::INDUSTRY_GROUP_FIELD.set_i64(bitfield, self.industry_group.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Self::ARBITRARY_ADDRESS_CAPABLE_FIELD.set_i64(bitfield, self.arbitrary_address_capable.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        Ok(())
+    }
+}
+
+/// Repeating fields set1 of PgnListTransmitAndReceive
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgnListTransmitAndReceiveSet1 {
+    /// pgn
+    pub pgn: Option<u32>,
+}
+
+impl PgnListTransmitAndReceiveSet1 {
+    /// Size of one repetition in bits
+    pub const BIT_LEN: usize = 24;
+    const PGN_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 0,
+        end: 23,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+
+    /// Decode the repetition starting at bit shift
+    pub fn decode_at(bitfield: &::bitfield::BitField, shift: usize) -> ::bitfield::Result<Self> {
+        let pgn = Self::PGN_FIELD.moved(shift).get_i64(bitfield)?.map(|value| value as u32);
+        Ok(PgnListTransmitAndReceiveSet1 {
+            pgn,
+        })
+    }
+
+    /// Encode the repetition starting at bit shift
+    pub fn encode_at(&self, bitfield: &mut ::bitfield::MutableBitField, shift: usize) -> ::bitfield::Result<()> {
+        Self::PGN_FIELD.moved(shift).set_i64(bitfield, self.pgn.map(|value| value as i64))?;
+        Ok(())
+    }
+}
+
+/// PGN List (Transmit and Receive) (PGN 126464)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgnListTransmitAndReceive {
+    /// functionCode
+    pub function_code: Option<PgnListFunction>,
+    /// Repeating fields up to the end of the payload
+    pub set1: Vec<PgnListTransmitAndReceiveSet1>,
+}
+
+impl PgnListTransmitAndReceive {
+    pub const PGN: u32 = 126464;
+    const FUNCTION_CODE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 0,
+        end: 7,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+}
+
+impl ::bitfield::BitDecode for PgnListTransmitAndReceive {
+    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+        let function_code = match Self::FUNCTION_CODE_FIELD.get_i64(bitfield)? { Some(value) => Some(<PgnListFunction as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let mut set1 = Vec::new();
+        while 8 + (set1.len() + 1) * PgnListTransmitAndReceiveSet1::BIT_LEN <= bitfield.bit_len() {
+            set1.push(PgnListTransmitAndReceiveSet1::decode_at(bitfield, 8 + set1.len() * PgnListTransmitAndReceiveSet1::BIT_LEN)?);
+        }
+        Ok(PgnListTransmitAndReceive {
+            function_code,
+            set1,
+        })
+    }
+}
+
+impl ::bitfield::BitEncode for PgnListTransmitAndReceive {
+    const BIT_LEN: usize = 8;
+
+    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+        Self::FUNCTION_CODE_FIELD.set_i64(bitfield, self.function_code.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        for (index, row) in self.set1.iter().enumerate() {
+            row.encode_at(bitfield, 8 + index * PgnListTransmitAndReceiveSet1::BIT_LEN)?;
+        }
+        Ok(())
+    }
+
+    fn to_vec(&self) -> ::bitfield::Result<Vec<u8>> {
+        let bits = Self::BIT_LEN + self.set1.len() * PgnListTransmitAndReceiveSet1::BIT_LEN;
+        let mut data = vec![0xFF; bits.div_ceil(8)];
+        self.encode(&mut ::bitfield::MutableBitField::new(&mut data))?;
+        Ok(data)
+    }
+}
+
+/// Repeating fields set1 of GnssSatsInView
+#[derive(Debug, Clone, PartialEq)]
+pub struct GnssSatsInViewSet1 {
+    /// prn
+    pub prn: Option<u8>,
+    /// elevation, rad
+    pub elevation: Option<f64>,
+    /// azimuth, rad
+    pub azimuth: Option<f64>,
+    /// snr, dB
+    pub snr: Option<f64>,
+    /// rangeResiduals, m
+    pub range_residuals: Option<f64>,
+    /// status
+    pub status: Option<SatelliteStatus>,
+}
+
+impl GnssSatsInViewSet1 {
+    /// Size of one repetition in bits
+    pub const BIT_LEN: usize = 96;
+    const PRN_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 0,
+        end: 7,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const ELEVATION_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 8,
+        end: 23,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 0.0001,
+        offset: 0.0,
+        na: true,
+    };
+    const AZIMUTH_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 24,
+        end: 39,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 0.0001,
+        offset: 0.0,
+        na: true,
+    };
+    const SNR_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 40,
+        end: 55,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 0.01,
+        offset: 0.0,
+        na: true,
+    };
+    const RANGE_RESIDUALS_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 56,
+        end: 87,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: true,
+        resolution: 1e-5,
+        offset: 0.0,
+        na: true,
+    };
+    const STATUS_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 88,
+        end: 91,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+
+    /// Decode the repetition starting at bit shift
+    pub fn decode_at(bitfield: &::bitfield::BitField, shift: usize) -> ::bitfield::Result<Self> {
+        let prn = Self::PRN_FIELD.moved(shift).get_i64(bitfield)?.map(|value| value as u8);
+        let elevation = Self::ELEVATION_FIELD.moved(shift).get_f64(bitfield)?;
+        let azimuth = Self::AZIMUTH_FIELD.moved(shift).get_f64(bitfield)?;
+        let snr = Self::SNR_FIELD.moved(shift).get_f64(bitfield)?;
+        let range_residuals = Self::RANGE_RESIDUALS_FIELD.moved(shift).get_f64(bitfield)?;
+        let status = match Self::STATUS_FIELD.moved(shift).get_i64(bitfield)? { Some(value) => Some(<SatelliteStatus as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        Ok(GnssSatsInViewSet1 {
+            prn,
+            elevation,
+            azimuth,
+            snr,
+            range_residuals,
+            status,
+        })
+    }
+
+    /// Encode the repetition starting at bit shift
+    pub fn encode_at(&self, bitfield: &mut ::bitfield::MutableBitField, shift: usize) -> ::bitfield::Result<()> {
+        Self::PRN_FIELD.moved(shift).set_i64(bitfield, self.prn.map(|value| value as i64))?;
+        Self::ELEVATION_FIELD.moved(shift).set_f64(bitfield, self.elevation)?;
+        Self::AZIMUTH_FIELD.moved(shift).set_f64(bitfield, self.azimuth)?;
+        Self::SNR_FIELD.moved(shift).set_f64(bitfield, self.snr)?;
+        Self::RANGE_RESIDUALS_FIELD.moved(shift).set_f64(bitfield, self.range_residuals)?;
+        Self::STATUS_FIELD.moved(shift).set_i64(bitfield, self.status.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("reserved11", 92 + shift, 95 + shift, ::bitfield::FieldType::Reserved) }, &::bitfield::Value::NotAvailable, bitfield)?;
+        Ok(())
+    }
+}
+
+/// GNSS Sats in View (PGN 129540)
+#[derive(Debug, Clone, PartialEq)]
+pub struct GnssSatsInView {
+    /// sid
+    pub sid: Option<u8>,
+    /// rangeResidualMode
+    pub range_residual_mode: Option<RangeResidualMode>,
+    /// Repeating fields, their number is written to satsInView
+    pub set1: Vec<GnssSatsInViewSet1>,
+}
+
+impl GnssSatsInView {
+    pub const PGN: u32 = 129540;
+    const SID_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 0,
+        end: 7,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const RANGE_RESIDUAL_MODE_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 8,
+        end: 9,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+    const SATS_IN_VIEW_FIELD: ::bitfield::FieldSpec = ::bitfield::FieldSpec {
+        start: 16,
+        end: 23,
+        endianness: ::bitfield::Endianness::Intel,
+        signed: false,
+        resolution: 1.0,
+        offset: 0.0,
+        na: true,
+    };
+}
+
+impl ::bitfield::BitDecode for GnssSatsInView {
+    fn decode(bitfield: &::bitfield::BitField) -> ::bitfield::Result<Self> {
+        let sid = Self::SID_FIELD.get_i64(bitfield)?.map(|value| value as u8);
+        let range_residual_mode = match Self::RANGE_RESIDUAL_MODE_FIELD.get_i64(bitfield)? { Some(value) => Some(<RangeResidualMode as ::bitfield::BitLookup>::from_raw(value as u64)?), None => None };
+        let sats_in_view = Self::SATS_IN_VIEW_FIELD.get_i64(bitfield)?.map(|value| value as u8);
+        let mut set1 = Vec::new();
+        for index in 0..sats_in_view.unwrap_or(0) as usize {
+            set1.push(GnssSatsInViewSet1::decode_at(bitfield, 24 + index * GnssSatsInViewSet1::BIT_LEN)?);
+        }
+        Ok(GnssSatsInView {
+            sid,
+            range_residual_mode,
+            set1,
+        })
+    }
+}
+
+impl ::bitfield::BitEncode for GnssSatsInView {
+    const BIT_LEN: usize = 24;
+
+    fn encode(&self, bitfield: &mut ::bitfield::MutableBitField) -> ::bitfield::Result<()> {
+        Self::SID_FIELD.set_i64(bitfield, self.sid.map(|value| value as i64))?;
+        Self::RANGE_RESIDUAL_MODE_FIELD.set_i64(bitfield, self.range_residual_mode.map(|value| ::bitfield::BitLookup::to_raw(&value) as i64))?;
+        ::bitfield::dynamic::encode_field(&::bitfield::FieldDescriptor { endianness: ::bitfield::Endianness::Intel, ..::bitfield::FieldDescriptor::new("reserved", 10, 15, ::bitfield::FieldType::Reserved) }, &::bitfield::Value::NotAvailable, bitfield)?;
+        Self::SATS_IN_VIEW_FIELD.set_count(bitfield, self.set1.len())?;
+        for (index, row) in self.set1.iter().enumerate() {
+            row.encode_at(bitfield, 24 + index * GnssSatsInViewSet1::BIT_LEN)?;
+        }
+        Ok(())
+    }
+
+    fn to_vec(&self) -> ::bitfield::Result<Vec<u8>> {
+        let bits = Self::BIT_LEN + self.set1.len() * GnssSatsInViewSet1::BIT_LEN;
+        let mut data = vec![0xFF; bits.div_ceil(8)];
+        self.encode(&mut ::bitfield::MutableBitField::new(&mut data))?;
+        Ok(data)
+    }
+}